@@ -0,0 +1,273 @@
+/*!
+    FEED HANDLER
+
+    This contains all relevant properties and functions of the client-side feed handler, which rebuilds
+    the order books of ALL instruments from the Incremental Feed and the Recovery Feed:
+        - Incremental messages are applied in the order of their sequential numbers (duplicates are dropped)
+        - if the Incremental Feed is sent on two lines (A and B), the lines are arbitrated by sequential number:
+          the first copy of a message is applied, whatever its line, so a message lost on ONE line is taken
+          from the other one (a gap detected because ONE line is late is filled when the other one delivers)
+        - a gap in the sequential numbers stops the Incremental Feed: the messages received after the gap
+          are buffered until a Recovery feed whose last_msg covers the gap arrives
+        - a Recovery feed rebuilds the book of its instrument, then the messages of the instrument received
+          after its last_msg are applied again
+
+    * NOTE: Sequential numbers are shared by ALL instruments, so the messages missing may have modified any book.
+      When the Recovery feed of ONE instrument covers a gap, the books of the other instruments are stale
+      until their own Recovery feed (sent at the same time by the exchange) is applied.
+*/
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use client::{FeedListener, LocalBook};
+use market_data::{FeedEncoding, SnapshotAssembler, decode_incremental};
+use objects::{IncrementalMessage, RecoveryFeed};
+
+/**
+    A feed handler has the following properties:
+        - encoding:
+            encoding of the Incremental Feed and the Recovery Feed
+        - assembler:
+            reassembly of the fragments of the Recovery Feed
+        - books:
+            local book of ALL instruments seen on the feeds
+        - stale:
+            symbols of the books which missed messages (skipped after a gap), until their Recovery feed is applied
+        - last_seq:
+            sequential number of the last Incremental message applied (0 = none yet)
+        - last_recovery:
+            last_msg of the last Recovery feed applied (0 = none yet)
+        - messages:
+            ALL Incremental messages received after last_recovery: applied ones (to apply them again after
+            the Recovery feed of their instrument) and buffered ones (received after a gap)
+        - in_gap:
+            true if messages are missing after last_seq (the Incremental Feed is stopped)
+        - duplicates:
+            number of Incremental messages dropped because they were already received (e.g, on the other line)
+        - listeners:
+            ALL listeners notified of the changes of the books
+*/
+pub struct FeedHandler {
+    encoding: FeedEncoding,
+    assembler: SnapshotAssembler,
+    books: HashMap<String, LocalBook>,
+    stale: HashSet<String>,
+    last_seq: i64,
+    last_recovery: i64,
+    /**
+        Key = sequential number -> value = Incremental message
+    */
+    messages: BTreeMap<i64, IncrementalMessage>,
+    in_gap: bool,
+    duplicates: i64,
+    listeners: Vec<Box<dyn FeedListener>>,
+}
+
+impl FeedHandler {
+    /**
+        Constructor
+        @params
+            m_encoding: encoding of the Incremental Feed and the Recovery Feed
+        @return
+            New feed handler without any book, expecting the first message of the Incremental Feed
+    */
+    pub fn new(m_encoding: FeedEncoding) -> FeedHandler {
+        FeedHandler {
+            encoding: m_encoding,
+            assembler: SnapshotAssembler::new(m_encoding),
+            books: HashMap::new(),
+            stale: HashSet::new(),
+            last_seq: 0,
+            last_recovery: 0,
+            messages: BTreeMap::new(),
+            in_gap: false,
+            duplicates: 0,
+            listeners: Vec::new(),
+        }
+    }
+
+    /**
+        Add a listener notified of the changes of the books
+
+        @params
+            listener: the listener
+    */
+    pub fn add_listener(&mut self, listener: Box<dyn FeedListener>) {
+        self.listeners.push(listener);
+    }
+
+    /**
+        This function gets the local book of an instrument
+
+        @params
+            symbol: symbol of the instrument
+        @return
+            - The local book
+            - None if the instrument was NOT seen on the feeds yet
+    */
+    pub fn get_book(&self, symbol: &str) -> Option<LocalBook> {
+        self.books.get(symbol).cloned()
+    }
+
+    // Return symbols of ALL instruments seen on the feeds
+    pub fn get_symbols(&self) -> Vec<String> {
+        self.books.keys().cloned().collect()
+    }
+
+    // Return true if the book of an instrument may miss messages (a gap was NOT recovered yet)
+    pub fn is_stale(&self, symbol: &str) -> bool {
+        self.stale.contains(symbol)
+    }
+
+    // Return range of the messages missing before the first message buffered (None if NOT in a gap)
+    pub fn get_missing(&self) -> Option<(i64, i64)> {
+        self.messages.range(self.last_seq + 1..).next().map(|(seq_number, _)| (self.last_seq + 1, seq_number - 1))
+    }
+
+    // Return true if messages are missing on the Incremental Feed (messages are buffered)
+    pub fn in_gap(&self) -> bool {
+        self.in_gap
+    }
+
+    // Return number of Incremental messages dropped because they were already received
+    pub fn get_duplicates(&self) -> i64 {
+        self.duplicates
+    }
+
+    // Return sequential number of the last Incremental message applied
+    pub fn get_last_seq(&self) -> i64 {
+        self.last_seq
+    }
+
+    /**
+        This function processes a datagram received on the Incremental Feed or the Recovery Feed
+
+        @params
+            data: the datagram (a fragment of a Recovery feed, or an Incremental message)
+        @return
+            - Nothing if the datagram was processed
+            - Or the reason the datagram is invalid
+    */
+    pub fn on_datagram(&mut self, data: &[u8]) -> Result<(), String> {
+        // Try the Recovery Feed first, then the Incremental Feed
+        match self.assembler.add_datagram(data) {
+            Ok(Some(feed)) => self.on_recovery(&feed),
+            Ok(None) => {}
+            Err(_) => {
+                let message = decode_incremental(data, self.encoding)?;
+                self.on_incremental(message);
+            }
+        }
+        Ok(())
+    }
+
+    /**
+        This function processes an Incremental message: the message is applied if it is the next one,
+        buffered if messages are missing before it, dropped if it was already received
+
+        @params
+            message: the Incremental message
+    */
+    pub fn on_incremental(&mut self, message: IncrementalMessage) {
+        let seq_number = message.get_num();
+        if seq_number <= self.last_seq || self.messages.contains_key(&seq_number) {
+            self.duplicates += 1;
+            return;
+        }
+        self.messages.insert(seq_number, message);
+        self.apply_messages();
+    }
+
+    /**
+        This function processes a Recovery feed: the book of its instrument is rebuilt if ALL the messages
+        after its last_msg were received (the feed is ignored otherwise, e.g older than the book).
+        The empty feed sent while there is NO instrument only moves last_recovery, NO book is created
+
+        @params
+            feed: the Recovery feed
+    */
+    pub fn on_recovery(&mut self, feed: &RecoveryFeed) {
+        let last_msg = feed.get_last_msg();
+        if last_msg < self.last_recovery {
+            return;
+        }
+        // ALL the messages after last_msg are needed to bring the book up to date
+        let newest = self.messages.keys().next_back().cloned().unwrap_or(0);
+        if (last_msg + 1..=newest).any(|seq_number| !self.messages.contains_key(&seq_number)) {
+            return;
+        }
+        // The Recovery feed covers the messages missing (the message after the last one applied is missing,
+        // otherwise it would have been applied): skip them
+        if last_msg > self.last_seq {
+            // The messages received before last_msg update the (stale) books of the other instruments
+            let received: Vec<IncrementalMessage> = self.messages.range(self.last_seq + 1..=last_msg).map(|(_, message)| message.clone()).collect();
+            for message in received {
+                if message.get_symbol() != feed.get_symbol() {
+                    self.update_book(&message);
+                }
+            }
+            self.stale.extend(self.books.keys().cloned());
+            self.last_seq = last_msg;
+            self.in_gap = false;
+        }
+        self.last_recovery = last_msg;
+        self.messages = self.messages.split_off(&(last_msg + 1));
+
+        // Rebuild the book, then apply the messages of the instrument applied after last_msg
+        let symbol = feed.get_symbol();
+        if symbol.is_empty() && feed.get_state().is_empty() && !self.books.contains_key(&symbol) {
+            self.apply_messages();
+            return;
+        }
+        let book = self.books.entry(symbol.clone()).or_insert_with(|| LocalBook::new(&symbol));
+        book.reset(feed);
+        for message in self.messages.range(..=self.last_seq).map(|(_, message)| message) {
+            if message.get_symbol() == symbol {
+                book.apply(message);
+            }
+        }
+        self.stale.remove(&symbol);
+        let book = &self.books[&symbol];
+        for listener in &mut self.listeners {
+            listener.on_recovery(book, last_msg);
+        }
+        // Messages buffered after the gap
+        self.apply_messages();
+    }
+
+    /**
+        This function applies the messages received in sequence after the last message applied,
+        and detects a gap if messages are buffered after a missing one
+    */
+    fn apply_messages(&mut self) {
+        while let Some(message) = self.messages.get(&(self.last_seq + 1)).cloned() {
+            self.last_seq += 1;
+            self.update_book(&message);
+        }
+        // Messages left are buffered after a gap (the gap may also be filled by messages received late)
+        let first_buffered = self.messages.range(self.last_seq + 1..).next().map(|(seq_number, _)| *seq_number);
+        if let Some(first_buffered) = first_buffered {
+            if !self.in_gap {
+                for listener in &mut self.listeners {
+                    listener.on_gap(self.last_seq + 1, first_buffered - 1);
+                }
+            }
+        }
+        self.in_gap = first_buffered.is_some();
+    }
+
+    /**
+        This function applies an Incremental message to the book of its instrument (created if needed)
+        and notifies the listeners
+
+        @params
+            message: the Incremental message
+    */
+    fn update_book(&mut self, message: &IncrementalMessage) {
+        let symbol = message.get_symbol();
+        let book = self.books.entry(symbol.clone()).or_insert_with(|| LocalBook::new(&symbol));
+        book.apply(message);
+        for listener in &mut self.listeners {
+            listener.on_update(book, message);
+        }
+    }
+}
@@ -0,0 +1,42 @@
+/*!
+    FEED LISTENER
+
+    This contains the callbacks of a feed handler (see FeedHandler), ALL of them do nothing by default
+*/
+
+use client::LocalBook;
+use objects::IncrementalMessage;
+
+/**
+    A feed listener is notified by a feed handler of EVERY change of the local books, in the order they happen
+*/
+pub trait FeedListener {
+    /**
+        This function is called after an Incremental message was applied to the local book of its instrument
+
+        @params
+            book: the local book after the message was applied
+            message: the Incremental message
+    */
+    fn on_update(&mut self, _book: &LocalBook, _message: &IncrementalMessage) {}
+
+    /**
+        This function is called when a gap is detected on the Incremental Feed
+        (the messages received after the gap are buffered until a Recovery feed covers the gap)
+
+        @params
+            first_missing: sequential number of the first message missing
+            last_missing: sequential number of the last message missing
+    */
+    fn on_gap(&mut self, _first_missing: i64, _last_missing: i64) {}
+
+    /**
+        This function is called after the local book of an instrument was rebuilt from a Recovery feed
+        (and the buffered Incremental messages of the instrument were applied)
+
+        @params
+            book: the local book rebuilt
+            last_msg: sequential number of the last message the Recovery feed includes
+    */
+    fn on_recovery(&mut self, _book: &LocalBook, _last_msg: i64) {}
+}
@@ -0,0 +1,106 @@
+/*!
+    FEED RECEIVER
+
+    This contains all relevant properties and functions of the client-side sockets joining the multicast groups
+    of the Incremental Feed (both lines, if there are two) and the Recovery Feed, and passing the datagrams to a feed handler
+*/
+
+use std::io;
+use std::net::{Ipv4Addr, SocketAddrV4, UdpSocket};
+use std::time::Duration;
+use client::FeedHandler;
+use config::Config;
+
+// Maximum size of a UDP datagram (a datagram larger than the buffer would be truncated)
+const MAX_DATAGRAM_SIZE: usize = 65536;
+// Maximum time (in milliseconds) waiting for a datagram on ONE socket before checking the next one
+const POLL_INTERVAL: u64 = 50;
+
+/**
+    A feed receiver has the following properties:
+        - sockets:
+            ONE socket per port of the multicast groups (groups on the same port share a socket)
+*/
+pub struct FeedReceiver {
+    sockets: Vec<UdpSocket>,
+}
+
+impl FeedReceiver {
+    /**
+        Constructor
+        @params
+            config: configuration of the exchange (multicast groups of the feeds and multicast interface)
+        @return
+            - New feed receiver, member of the multicast groups of the Incremental Feed and the Recovery Feed
+            (and of the second line of the Incremental Feed, if configured)
+            - Or the error if a group is invalid or cannot be joined
+    */
+    pub fn join(config: &Config) -> io::Result<FeedReceiver> {
+        let interface: Ipv4Addr = if config.get_multicast_interface().is_empty() {
+            Ipv4Addr::UNSPECIFIED
+        } else {
+            config.get_multicast_interface().parse().map_err(invalid_input)?
+        };
+        let mut sockets: Vec<(u16, UdpSocket)> = Vec::new();
+        let mut groups = vec![config.get_incremental_feed_group(), config.get_recovery_feed_group()];
+        if !config.get_incremental_feed_group_b().is_empty() {
+            groups.push(config.get_incremental_feed_group_b());
+        }
+        for group in &groups {
+            let group: SocketAddrV4 = group.parse().map_err(invalid_input)?;
+            if !sockets.iter().any(|&(port, _)| port == group.port()) {
+                let socket = UdpSocket::bind(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, group.port()))?;
+                socket.set_read_timeout(Some(Duration::from_millis(POLL_INTERVAL)))?;
+                sockets.push((group.port(), socket));
+            }
+            let socket = &sockets.iter().find(|&&(port, _)| port == group.port()).unwrap().1;
+            socket.join_multicast_v4(group.ip(), &interface)?;
+        }
+        Ok(FeedReceiver {
+            sockets: sockets.into_iter().map(|(_, socket)| socket).collect(),
+        })
+    }
+
+    /**
+        This function receives the datagrams (at most ONE per socket) and passes them to a feed handler
+        (invalid datagrams are ignored)
+
+        @params
+            handler: the feed handler
+        @return
+            - Nothing if the sockets were checked
+            - Or the error of a socket
+    */
+    pub fn poll(&self, handler: &mut FeedHandler) -> io::Result<()> {
+        let mut buffer = vec![0u8; MAX_DATAGRAM_SIZE];
+        for socket in &self.sockets {
+            match socket.recv_from(&mut buffer) {
+                Ok((size, _)) => { let _ = handler.on_datagram(&buffer[..size]); }
+                Err(ref e) if e.kind() == io::ErrorKind::WouldBlock || e.kind() == io::ErrorKind::TimedOut => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    /**
+        This function passes ALL datagrams received to a feed handler, until an error happens
+
+        @params
+            handler: the feed handler
+        @return
+            The error of a socket
+    */
+    pub fn run(&self, handler: &mut FeedHandler) -> io::Error {
+        loop {
+            if let Err(e) = self.poll(handler) {
+                return e;
+            }
+        }
+    }
+}
+
+// Return the error of an invalid address
+fn invalid_input<E: ToString>(e: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, e.to_string())
+}
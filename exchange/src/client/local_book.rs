@@ -0,0 +1,143 @@
+/*!
+    LOCAL BOOK
+
+    This contains all relevant properties and functions of the order book of an instrument
+    as rebuilt by a client from the Incremental Feed and the Recovery Feed
+*/
+
+use std::collections::BTreeMap;
+use objects::{IncrementalMessage, Order, RecoveryFeed};
+
+/**
+    A local book has the following properties:
+        - symbol:
+            symbol of the instrument
+        - buys_by_price:
+            ALL orders on BUY side (key = price, value = list of orders from HIGHEST to LOWEST priority)
+        - sells_by_price:
+            ALL orders on SELL side (key = price, value = list of orders from HIGHEST to LOWEST priority)
+*/
+#[derive(PartialEq, Clone, Debug)]
+pub struct LocalBook {
+    symbol: String,
+    buys_by_price: BTreeMap<i64, Vec<Order>>,
+    sells_by_price: BTreeMap<i64, Vec<Order>>,
+}
+
+impl LocalBook {
+    /**
+        Constructor
+        @params
+            m_symbol: symbol of the instrument
+        @return
+            New empty local book
+    */
+    pub fn new(m_symbol: &str) -> LocalBook {
+        LocalBook {
+            symbol: m_symbol.to_string(),
+            buys_by_price: BTreeMap::new(),
+            sells_by_price: BTreeMap::new(),
+        }
+    }
+
+    // Return symbol of the instrument
+    pub fn get_symbol(&self) -> String {
+        self.symbol.clone()
+    }
+
+    /**
+        This function applies an Incremental message: the orders at the price of the message
+        are replaced by the orders of the message
+
+        @params
+            message: the Incremental message (of the instrument of the book)
+    */
+    pub fn apply(&mut self, message: &IncrementalMessage) {
+        self.set_level(message.get_price(), message.get_orders());
+    }
+
+    /**
+        This function replaces the whole book by the state of a Recovery feed
+
+        @params
+            feed: the Recovery feed (of the instrument of the book)
+    */
+    pub fn reset(&mut self, feed: &RecoveryFeed) {
+        self.buys_by_price.clear();
+        self.sells_by_price.clear();
+        for level in feed.get_state() {
+            if let Some(price) = level.first().map(|order| order.get_price()) {
+                self.set_level(price, level);
+            }
+        }
+    }
+
+    /**
+        This function replaces the orders at a price (the price level is removed if there is NO order)
+
+        @params
+            price: the price
+            orders: ALL orders at the price (ALL on ONE side)
+    */
+    fn set_level(&mut self, price: i64, orders: Vec<Order>) {
+        self.buys_by_price.remove(&price);
+        self.sells_by_price.remove(&price);
+        match orders.first().map(|order| order.get_side()) {
+            Some('1') => { self.buys_by_price.insert(price, orders); }
+            Some('2') => { self.sells_by_price.insert(price, orders); }
+            _ => {}
+        }
+    }
+
+    /**
+        This function gets the best bid (HIGHEST price on BUY side)
+
+        @return
+            - (price, total quantity at the price)
+            - None if there is NO order on BUY side
+    */
+    pub fn best_bid(&self) -> Option<(i64, i64)> {
+        self.buys_by_price.iter().next_back().map(|(price, orders)| (*price, total_qty(orders)))
+    }
+
+    /**
+        This function gets the best offer (LOWEST price on SELL side)
+
+        @return
+            - (price, total quantity at the price)
+            - None if there is NO order on SELL side
+    */
+    pub fn best_ask(&self) -> Option<(i64, i64)> {
+        self.sells_by_price.iter().next().map(|(price, orders)| (*price, total_qty(orders)))
+    }
+
+    /**
+        This function gets ALL the orders at a price
+
+        @params
+            price: the price
+        @return
+            A list of orders (from HIGHEST to LOWEST priority), empty if there is NO order at that price
+    */
+    pub fn get_orders_by_price(&self, price: i64) -> Vec<Order> {
+        self.buys_by_price.get(&price).or_else(|| self.sells_by_price.get(&price)).cloned().unwrap_or_default()
+    }
+
+    /**
+        This function gets the orders of one side, price level by price level
+
+        @params
+            side: side of the book ('1' = BUY, '2' = SELL)
+        @return
+            A list of (price, list of orders at the price), in increasing order of the prices
+    */
+    pub fn get_levels(&self, side: char) -> Vec<(i64, Vec<Order>)> {
+        let levels = if side == '1' { &self.buys_by_price } else { &self.sells_by_price };
+        levels.iter().map(|(price, orders)| (*price, orders.clone())).collect()
+    }
+}
+
+// Return total quantity of a list of orders
+fn total_qty(orders: &[Order]) -> i64 {
+    orders.iter().map(|order| order.get_qty()).sum()
+}
@@ -0,0 +1,10 @@
+pub use self::local_book::LocalBook;
+pub use self::feed_listener::FeedListener;
+pub use self::feed_handler::FeedHandler;
+pub use self::feed_receiver::FeedReceiver;
+pub use self::replay_client::ReplayClient;
+mod local_book;
+mod feed_listener;
+mod feed_handler;
+mod feed_receiver;
+mod replay_client;
\ No newline at end of file
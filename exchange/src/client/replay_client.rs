@@ -0,0 +1,75 @@
+/*!
+    REPLAY CLIENT
+
+    This contains all relevant properties and functions of the client-side connection to the replay server,
+    to get the Incremental messages missed (e.g, after a gap) without waiting for the next Recovery Feed
+*/
+
+extern crate serde_json;
+
+use std::io;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpStream;
+use objects::{IncrementalMessage, ReplayRequest, ReplayResponse};
+
+/**
+    A replay client has the following properties:
+        - writer:
+            connection to the replay server (requests)
+        - reader:
+            connection to the replay server (responses and messages)
+*/
+pub struct ReplayClient {
+    writer: TcpStream,
+    reader: BufReader<TcpStream>,
+}
+
+impl ReplayClient {
+    /**
+        Constructor
+        @params
+            address: address of the replay server
+        @return
+            - New replay client, connected to the server
+            - Or the error if the server cannot be reached
+    */
+    pub fn connect(address: &str) -> io::Result<ReplayClient> {
+        let writer = TcpStream::connect(address)?;
+        let reader = BufReader::new(writer.try_clone()?);
+        Ok(ReplayClient { writer, reader })
+    }
+
+    /**
+        This function asks the server for a range of Incremental messages
+
+        @params
+            first: sequential number of the first message
+            last: sequential number of the last message
+        @return
+            - ALL the messages of the range, in order
+            - Or the reason they cannot be replayed (rejected by the server, connection lost)
+    */
+    pub fn request(&mut self, first: i64, last: i64) -> Result<Vec<IncrementalMessage>, String> {
+        let request = serde_json::to_string(&ReplayRequest::new(first, last)).unwrap();
+        writeln!(self.writer, "{}", request).map_err(|e| e.to_string())?;
+        let response: ReplayResponse = serde_json::from_str(&self.read_line()?).map_err(|e| e.to_string())?;
+        if response.get_status() != '0' {
+            return Err(response.get_reason());
+        }
+        let mut messages = Vec::new();
+        for _ in 0..response.get_count() {
+            messages.push(serde_json::from_str(&self.read_line()?).map_err(|e| e.to_string())?);
+        }
+        Ok(messages)
+    }
+
+    // Return the next line sent by the server (error if the connection was closed)
+    fn read_line(&mut self) -> Result<String, String> {
+        let mut line = String::new();
+        match self.reader.read_line(&mut line) {
+            Ok(0) => Err("connection closed by the replay server".to_string()),
+            Ok(_) => Ok(line),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+}
@@ -0,0 +1,43 @@
+/*!
+    CLOCK
+
+    This contains the interface between the exchange and the time source of its time stamps
+    (wall clock in production, a simulated clock for reproducible scenario runs and tests)
+*/
+
+extern crate time;
+
+/**
+    A clock gives the current time.
+    Implementations:
+        - SystemClock: the wall clock
+        - SimulatedClock: a clock advanced manually
+*/
+pub trait Clock: Send {
+    // Return current time, in milliseconds since the epoch
+    fn now_ms(&self) -> i64;
+
+    // Return a copy of the clock (copies of a SimulatedClock share the same time)
+    fn clone_box(&self) -> Box<dyn Clock>;
+
+    // Return current time stamp (UTC format: YYYYMMDD-HH:MM:SS.sss)
+    fn transact_time(&self) -> String {
+        utc_timestamp(self.now_ms())
+    }
+}
+
+/**
+    This function converts a number of milliseconds since the epoch to a time stamp
+
+    @params
+        ms: number of milliseconds since the epoch
+    @return
+        Time stamp (UTC format: YYYYMMDD-HH:MM:SS.sss)
+*/
+pub fn utc_timestamp(ms: i64) -> String {
+    let spec = time::Timespec::new(ms.div_euclid(1000), (ms.rem_euclid(1000) * 1_000_000) as i32);
+    let mut timestamp = time::at_utc(spec).strftime("%Y%m%d-%H:%M:%S.%f").unwrap().to_string();
+    // Remove unnecessary characters (nanoseconds -> milliseconds)
+    timestamp.truncate(21);
+    timestamp
+}
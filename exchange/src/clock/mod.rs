@@ -0,0 +1,7 @@
+pub use self::clock::{Clock, utc_timestamp};
+pub use self::system_clock::SystemClock;
+pub use self::simulated_clock::SimulatedClock;
+#[allow(clippy::module_inception)]
+mod clock;
+mod system_clock;
+mod simulated_clock;
\ No newline at end of file
@@ -0,0 +1,65 @@
+/*!
+    SIMULATED CLOCK
+
+    This contains all relevant properties and functions of the clock advanced manually
+    (e.g, for scenario runs and tests, so that every time stamp is reproducible)
+*/
+
+use std::sync::{Arc, Mutex};
+use clock::Clock;
+
+/**
+    A simulated clock has the following properties:
+        - now:
+            current time, in milliseconds since the epoch
+            (shared by ALL clones of the clock, so that the time can be advanced while the matching engine owns it)
+*/
+#[derive(Clone, Debug)]
+pub struct SimulatedClock {
+    now: Arc<Mutex<i64>>,
+}
+
+impl SimulatedClock {
+    /**
+        Constructor
+        @params
+            m_start_ms: initial time, in milliseconds since the epoch
+        @return
+            New simulated clock (the time only changes when the clock is advanced or set)
+    */
+    pub fn new(m_start_ms: i64) -> SimulatedClock {
+        SimulatedClock {
+            now: Arc::new(Mutex::new(m_start_ms)),
+        }
+    }
+
+    /**
+        Move the clock forward
+
+        @params
+            ms: number of milliseconds
+    */
+    pub fn advance(&self, ms: i64) {
+        *self.now.lock().unwrap() += ms;
+    }
+
+    /**
+        Set the time of the clock
+
+        @params
+            ms: new time, in milliseconds since the epoch
+    */
+    pub fn set(&self, ms: i64) {
+        *self.now.lock().unwrap() = ms;
+    }
+}
+
+impl Clock for SimulatedClock {
+    fn now_ms(&self) -> i64 {
+        *self.now.lock().unwrap()
+    }
+
+    fn clone_box(&self) -> Box<dyn Clock> {
+        Box::new(self.clone())
+    }
+}
@@ -0,0 +1,26 @@
+/*!
+    SYSTEM CLOCK
+
+    This contains the clock reading the wall clock of the system
+*/
+
+extern crate time;
+
+use clock::Clock;
+
+/**
+    A system clock has NO property, the time is read from the system
+*/
+#[derive(Clone, Copy, Default, Debug)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_ms(&self) -> i64 {
+        let spec = time::get_time();
+        spec.sec * 1000 + i64::from(spec.nsec / 1_000_000)
+    }
+
+    fn clone_box(&self) -> Box<dyn Clock> {
+        Box::new(*self)
+    }
+}
@@ -0,0 +1,552 @@
+/*!
+    CONFIGURATION
+
+    This contains the runtime configuration of the exchange (network endpoints, multicast settings, snapshot period).
+    Every setting has a default value, which can be overridden (in this order, the last one wins):
+        1. by the configuration file (TOML format, see ghex.toml), given with --config <path>
+        2. by environment variables: GHEX_<SETTING> (e.g, GHEX_RECOVERY_PERIOD=10)
+        3. by command-line flags: --<setting> <value> (e.g, --recovery-period 10)
+*/
+extern crate toml;
+
+use std::env;
+use std::fs::File;
+use std::io::Read;
+use market_data::{FeedEncoding, FaultConfig};
+use objects::{SelfTradePrevention, MAX_PAYLOAD};
+
+// Names of the feeds with faults (longest first: a name can be the beginning of another one)
+const FAULT_FEEDS: [&str; 8] = ["incremental_b", "incremental", "recovery", "trade", "mbp", "depth", "quote", "bar"];
+
+/**
+    The configuration has the following settings:
+        - feed_bind_address:
+            address the socket of the matching engine (Incremental Feed and Trade Feed) is bound to
+        - recovery_bind_address:
+            address the socket of the recovery thread (Recovery Feed) is bound to
+        - multicast_interface:
+            IP address of the network interface used for multicasting
+            (if set, the multicast sockets are bound to it instead of the IP address of the bind addresses)
+        - multicast_ttl:
+            time-to-live of the multicast packets (1 = local network only, at most 255)
+        - incremental_feed_group:
+            multicast group of the Incremental Feed (MUST specify the PORT)
+        - incremental_feed_group_b:
+            multicast group of the second line of the Incremental Feed (MUST specify the PORT),
+            every Incremental message is sent on both lines (if empty, ONE line only)
+        - trade_feed_group:
+            multicast group of the Trade Feed (MUST specify the PORT)
+        - recovery_feed_group:
+            multicast group of the Recovery Feed (MUST specify the PORT)
+        - mbp_feed_group:
+            multicast group of the market-by-price (MBP) feed (MUST specify the PORT)
+        - depth_feed_group:
+            multicast group of the depth feed (MUST specify the PORT)
+        - quote_feed_group:
+            multicast group of the BBO feed (MUST specify the PORT)
+        - bar_feed_group:
+            multicast group of the bar feed (MUST specify the PORT)
+        - bar_intervals:
+            lengths (in seconds) of the OHLCV bars built from the trades (e.g, "60,300" on the command line)
+        - bar_history:
+            number of completed bars kept per instrument and interval (the bar feed publishes EVERY bar)
+        - depth_levels:
+            number of price levels (per side) of the depth feed (0 = NO depth feed)
+        - recovery_period:
+            period (in seconds) between 2 Recovery Feeds (at least 1)
+        - snapshot_max_payload:
+            maximum size (in bytes) of the part of a recovery feed sent in ONE datagram
+            (a larger recovery feed is split into several fragments), from 1 to 65535
+        - order_entry_address:
+            address the order entry server listens on
+        - replay_address:
+            address the replay server (retransmission of Incremental messages) listens on
+        - replay_buffer_size:
+            number of the most recent Incremental messages the replay server can retransmit
+        - fix_address:
+            address the FIX acceptor listens on
+        - fix_comp_id:
+            SenderCompID of the exchange in FIX sessions
+        - feed_encoding:
+            encoding of the Incremental Feed and the Recovery Feed (json or binary)
+        - self_trade_prevention:
+            what happens when an order would trade against a resting order of the same account
+            (none, cancel_newest, cancel_oldest, cancel_both or decrement_and_cancel)
+        - market_data_file:
+            path of the file ALL market data events are recorded in (if empty, NOT recorded)
+        - fault_seed:
+            seed of the fault injection (the same seed gives the same faults)
+        - faults:
+            faults injected on each feed (see FaultConfig, NO fault by default), e.g:
+                [faults.incremental]
+                drop_probability = 0.01
+            (GHEX_FAULTS_INCREMENTAL_DROP_PROBABILITY=0.01, --faults-incremental-drop-probability 0.01)
+*/
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    feed_bind_address: String,
+    recovery_bind_address: String,
+    multicast_interface: String,
+    multicast_ttl: u32,
+    incremental_feed_group: String,
+    incremental_feed_group_b: String,
+    trade_feed_group: String,
+    recovery_feed_group: String,
+    mbp_feed_group: String,
+    depth_feed_group: String,
+    quote_feed_group: String,
+    bar_feed_group: String,
+    bar_intervals: Vec<u64>,
+    bar_history: usize,
+    depth_levels: usize,
+    recovery_period: u64,
+    snapshot_max_payload: usize,
+    order_entry_address: String,
+    replay_address: String,
+    replay_buffer_size: usize,
+    fix_address: String,
+    fix_comp_id: String,
+    feed_encoding: FeedEncoding,
+    self_trade_prevention: SelfTradePrevention,
+    market_data_file: String,
+    fault_seed: u64,
+    faults: FeedFaults,
+}
+
+/**
+    The faults of ALL the feeds:
+        - incremental, incremental_b:
+            Incremental Feed (line A, line B)
+        - recovery, trade, mbp, depth, quote, bar:
+            Recovery Feed, Trade Feed, MBP feed, depth feed, BBO feed, bar feed
+*/
+#[derive(PartialEq, Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FeedFaults {
+    incremental: FaultConfig,
+    incremental_b: FaultConfig,
+    recovery: FaultConfig,
+    trade: FaultConfig,
+    mbp: FaultConfig,
+    depth: FaultConfig,
+    quote: FaultConfig,
+    bar: FaultConfig,
+}
+
+impl FeedFaults {
+    /**
+        This function gets the faults of a feed
+
+        @params
+            feed: name of the feed (see FAULT_FEEDS)
+        @return
+            - The faults of the feed
+            - None if the feed is unknown
+    */
+    fn get_mut(&mut self, feed: &str) -> Option<&mut FaultConfig> {
+        match feed {
+            "incremental" => Some(&mut self.incremental),
+            "incremental_b" => Some(&mut self.incremental_b),
+            "recovery" => Some(&mut self.recovery),
+            "trade" => Some(&mut self.trade),
+            "mbp" => Some(&mut self.mbp),
+            "depth" => Some(&mut self.depth),
+            "quote" => Some(&mut self.quote),
+            "bar" => Some(&mut self.bar),
+            _ => None,
+        }
+    }
+
+    /**
+        This function checks the faults of ALL the feeds
+
+        @return
+            - Nothing if the faults are valid
+            - Or the reason the faults of a feed are invalid
+    */
+    fn validate(&self) -> Result<(), String> {
+        let feeds = [
+            ("incremental", &self.incremental),
+            ("incremental_b", &self.incremental_b),
+            ("recovery", &self.recovery),
+            ("trade", &self.trade),
+            ("mbp", &self.mbp),
+            ("depth", &self.depth),
+            ("quote", &self.quote),
+            ("bar", &self.bar),
+        ];
+        for &(feed, faults) in feeds.iter() {
+            faults.validate().map_err(|e| format!("invalid faults of the {} feed: {}", feed, e))?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            feed_bind_address: "0.0.0.0:0".to_string(),
+            recovery_bind_address: "0.0.0.0:21001".to_string(),
+            multicast_interface: String::new(),
+            multicast_ttl: 1,
+            incremental_feed_group: "239.194.5.3:21003".to_string(),
+            incremental_feed_group_b: String::new(),
+            trade_feed_group: "239.194.5.4:21003".to_string(),
+            recovery_feed_group: "239.255.255.255:21003".to_string(),
+            mbp_feed_group: "239.194.5.5:21003".to_string(),
+            depth_feed_group: "239.194.5.6:21003".to_string(),
+            quote_feed_group: "239.194.5.7:21003".to_string(),
+            bar_feed_group: "239.194.5.8:21003".to_string(),
+            bar_intervals: vec![60],
+            bar_history: 100,
+            depth_levels: 5,
+            recovery_period: 5,
+            snapshot_max_payload: 1400,
+            order_entry_address: "0.0.0.0:21005".to_string(),
+            replay_address: "0.0.0.0:21007".to_string(),
+            replay_buffer_size: 10000,
+            fix_address: "0.0.0.0:21006".to_string(),
+            fix_comp_id: "GHEX".to_string(),
+            feed_encoding: FeedEncoding::Json,
+            self_trade_prevention: SelfTradePrevention::None,
+            market_data_file: String::new(),
+            fault_seed: 0,
+            faults: FeedFaults::default(),
+        }
+    }
+}
+
+impl Config {
+    /**
+        This function builds the configuration of the exchange from ALL the sources
+        (default values, configuration file, environment variables, command-line flags)
+
+        @params
+            args: command-line arguments (without the program name)
+        @return
+            - The configuration
+            - Or the reason the configuration is invalid (e.g, unknown setting, file not found)
+    */
+    pub fn load(args: &[String]) -> Result<Config, String> {
+        // Configuration file (if any)
+        let mut config = match args.iter().position(|arg| arg == "--config") {
+            Some(index) => {
+                let path = args.get(index + 1).ok_or("missing value for --config")?;
+                let mut contents = String::new();
+                File::open(path).and_then(|mut file| file.read_to_string(&mut contents))
+                    .map_err(|e| format!("cannot read {}: {}", path, e))?;
+                Config::from_toml(&contents)?
+            }
+            None => Config::default(),
+        };
+        config.apply_env(env::vars())?;
+        config.apply_args(args)?;
+        Ok(config)
+    }
+
+    /**
+        This function reads a configuration in TOML format (missing settings take their default values)
+
+        @params
+            contents: the configuration, e.g:
+                recovery_period = 10
+                order_entry_address = "0.0.0.0:22005"
+        @return
+            - The configuration
+            - Or the reason the configuration is invalid
+    */
+    pub fn from_toml(contents: &str) -> Result<Config, String> {
+        let config: Config = toml::from_str(contents).map_err(|e| format!("invalid configuration: {}", e))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /**
+        This function checks the values of the settings which are NOT checked by their type
+        (e.g, recovery_period = 0), after the configuration file is read and after every set
+
+        @return
+            - Nothing if the configuration is valid
+            - Or the reason the configuration is invalid
+    */
+    fn validate(&self) -> Result<(), String> {
+        if self.multicast_ttl > 255 {
+            return Err(format!("invalid value '{}' for multicast_ttl (number up to 255 expected)", self.multicast_ttl));
+        }
+        if self.recovery_period == 0 {
+            return Err(format!("invalid value '{}' for recovery_period (number from 1 expected)", self.recovery_period));
+        }
+        if self.snapshot_max_payload == 0 || self.snapshot_max_payload > MAX_PAYLOAD {
+            return Err(format!("invalid value '{}' for snapshot_max_payload (number from 1 to {} expected)",
+                self.snapshot_max_payload, MAX_PAYLOAD));
+        }
+        self.faults.validate()
+    }
+
+    /**
+        This function overrides settings with the environment variables GHEX_<SETTING>
+
+        @params
+            vars: the environment variables (name, value)
+        @return
+            - Nothing if ALL the GHEX_ variables are valid settings
+            - Or the reason a variable is invalid
+    */
+    pub fn apply_env<I: Iterator<Item = (String, String)>>(&mut self, vars: I) -> Result<(), String> {
+        for (name, value) in vars {
+            if let Some(setting) = name.strip_prefix("GHEX_") {
+                self.set(&setting.to_lowercase(), &value)?;
+            }
+        }
+        Ok(())
+    }
+
+    /**
+        This function overrides settings with the command-line flags --<setting> <value>
+        (--config <path> is skipped, the file is read by Config::load)
+
+        @params
+            args: command-line arguments (without the program name)
+        @return
+            - Nothing if ALL the flags are valid settings
+            - Or the reason a flag is invalid
+    */
+    pub fn apply_args(&mut self, args: &[String]) -> Result<(), String> {
+        let mut index = 0;
+        while index < args.len() {
+            let flag = &args[index];
+            if !flag.starts_with("--") {
+                return Err(format!("unexpected argument '{}'", flag));
+            }
+            let value = args.get(index + 1).ok_or(format!("missing value for {}", flag))?;
+            if flag != "--config" {
+                self.set(&flag[2..].replace('-', "_"), value)?;
+            }
+            index += 2;
+        }
+        Ok(())
+    }
+
+    /**
+        This function changes ONE setting
+
+        @params
+            name: name of the setting (e.g, "recovery_period")
+            value: new value of the setting
+        @return
+            - Nothing if the setting was changed
+            - Or the reason the setting is invalid (unknown setting, value which is not a number or out of range)
+    */
+    pub fn set(&mut self, name: &str, value: &str) -> Result<(), String> {
+        let invalid_number = |_| format!("invalid value '{}' for {} (number expected)", value, name);
+        let previous = self.clone();
+        match name {
+            "feed_bind_address" => self.feed_bind_address = value.to_string(),
+            "recovery_bind_address" => self.recovery_bind_address = value.to_string(),
+            "multicast_interface" => self.multicast_interface = value.to_string(),
+            "multicast_ttl" => self.multicast_ttl = value.parse().map_err(invalid_number)?,
+            "incremental_feed_group" => self.incremental_feed_group = value.to_string(),
+            "incremental_feed_group_b" => self.incremental_feed_group_b = value.to_string(),
+            "trade_feed_group" => self.trade_feed_group = value.to_string(),
+            "recovery_feed_group" => self.recovery_feed_group = value.to_string(),
+            "mbp_feed_group" => self.mbp_feed_group = value.to_string(),
+            "depth_feed_group" => self.depth_feed_group = value.to_string(),
+            "quote_feed_group" => self.quote_feed_group = value.to_string(),
+            "bar_feed_group" => self.bar_feed_group = value.to_string(),
+            "bar_intervals" => {
+                self.bar_intervals = value.split(',').filter(|interval| !interval.trim().is_empty())
+                    .map(|interval| interval.trim().parse().map_err(invalid_number))
+                    .collect::<Result<Vec<u64>, String>>()?
+            }
+            "bar_history" => self.bar_history = value.parse().map_err(invalid_number)?,
+            "depth_levels" => self.depth_levels = value.parse().map_err(invalid_number)?,
+            "recovery_period" => self.recovery_period = value.parse().map_err(invalid_number)?,
+            "snapshot_max_payload" => self.snapshot_max_payload = value.parse().map_err(invalid_number)?,
+            "order_entry_address" => self.order_entry_address = value.to_string(),
+            "replay_address" => self.replay_address = value.to_string(),
+            "replay_buffer_size" => self.replay_buffer_size = value.parse().map_err(invalid_number)?,
+            "fix_address" => self.fix_address = value.to_string(),
+            "fix_comp_id" => self.fix_comp_id = value.to_string(),
+            "feed_encoding" => self.feed_encoding = FeedEncoding::parse(value)?,
+            "self_trade_prevention" => self.self_trade_prevention = SelfTradePrevention::parse(value)?,
+            "market_data_file" => self.market_data_file = value.to_string(),
+            "fault_seed" => self.fault_seed = value.parse().map_err(invalid_number)?,
+            _ => {
+                // Faults of a feed: faults_<feed>_<fault>
+                let fault = name.strip_prefix("faults_").and_then(|fault| {
+                    FAULT_FEEDS.iter().find_map(|feed| fault.strip_prefix(feed).and_then(|rest| rest.strip_prefix('_')).map(|rest| (*feed, rest)))
+                });
+                match fault {
+                    Some((feed, fault)) => self.faults.get_mut(feed).unwrap().set(fault, value)?,
+                    None => return Err(format!("unknown setting '{}'", name)),
+                }
+            }
+        }
+        // Values out of range are rejected (the setting keeps its previous value)
+        if let Err(reason) = self.validate() {
+            *self = previous;
+            return Err(reason);
+        }
+        Ok(())
+    }
+
+    // Return address the socket of the matching engine is bound to
+    pub fn get_feed_bind_address(&self) -> String {
+        self.feed_bind_address.clone()
+    }
+
+    // Return address the socket of the recovery thread is bound to
+    pub fn get_recovery_bind_address(&self) -> String {
+        self.recovery_bind_address.clone()
+    }
+
+    // Return IP address of the network interface used for multicasting
+    pub fn get_multicast_interface(&self) -> String {
+        self.multicast_interface.clone()
+    }
+
+    // Return time-to-live of the multicast packets
+    pub fn get_multicast_ttl(&self) -> u32 {
+        self.multicast_ttl
+    }
+
+    // Return multicast group of the Incremental Feed
+    pub fn get_incremental_feed_group(&self) -> String {
+        self.incremental_feed_group.clone()
+    }
+
+    // Return multicast group of the second line of the Incremental Feed (empty if ONE line only)
+    pub fn get_incremental_feed_group_b(&self) -> String {
+        self.incremental_feed_group_b.clone()
+    }
+
+    // Return multicast group of the Trade Feed
+    pub fn get_trade_feed_group(&self) -> String {
+        self.trade_feed_group.clone()
+    }
+
+    // Return multicast group of the Recovery Feed
+    pub fn get_recovery_feed_group(&self) -> String {
+        self.recovery_feed_group.clone()
+    }
+
+    // Return multicast group of the MBP feed
+    pub fn get_mbp_feed_group(&self) -> String {
+        self.mbp_feed_group.clone()
+    }
+
+    // Return multicast group of the depth feed
+    pub fn get_depth_feed_group(&self) -> String {
+        self.depth_feed_group.clone()
+    }
+
+    // Return multicast group of the BBO feed
+    pub fn get_quote_feed_group(&self) -> String {
+        self.quote_feed_group.clone()
+    }
+
+    // Return multicast group of the bar feed
+    pub fn get_bar_feed_group(&self) -> String {
+        self.bar_feed_group.clone()
+    }
+
+    // Return lengths (in seconds) of the OHLCV bars
+    pub fn get_bar_intervals(&self) -> Vec<u64> {
+        self.bar_intervals.clone()
+    }
+
+    // Return number of completed bars kept per instrument and interval
+    pub fn get_bar_history(&self) -> usize {
+        self.bar_history
+    }
+
+    // Return number of price levels (per side) of the depth feed
+    pub fn get_depth_levels(&self) -> usize {
+        self.depth_levels
+    }
+
+    // Return period (in seconds) between 2 Recovery Feeds
+    pub fn get_recovery_period(&self) -> u64 {
+        self.recovery_period
+    }
+
+    // Return maximum size (in bytes) of the part of a recovery feed sent in ONE datagram
+    pub fn get_snapshot_max_payload(&self) -> usize {
+        self.snapshot_max_payload
+    }
+
+    // Return address the order entry server listens on
+    pub fn get_order_entry_address(&self) -> String {
+        self.order_entry_address.clone()
+    }
+
+    // Return address the replay server listens on
+    pub fn get_replay_address(&self) -> String {
+        self.replay_address.clone()
+    }
+
+    // Return number of the most recent Incremental messages the replay server can retransmit
+    pub fn get_replay_buffer_size(&self) -> usize {
+        self.replay_buffer_size
+    }
+
+    // Return address the FIX acceptor listens on
+    pub fn get_fix_address(&self) -> String {
+        self.fix_address.clone()
+    }
+
+    // Return SenderCompID of the exchange in FIX sessions
+    pub fn get_fix_comp_id(&self) -> String {
+        self.fix_comp_id.clone()
+    }
+
+    // Return encoding of the Incremental Feed and the Recovery Feed
+    pub fn get_feed_encoding(&self) -> FeedEncoding {
+        self.feed_encoding
+    }
+
+    // Return self-trade prevention mode of the matching engine
+    pub fn get_self_trade_prevention(&self) -> SelfTradePrevention {
+        self.self_trade_prevention
+    }
+
+    // Return path of the file market data events are recorded in
+    pub fn get_market_data_file(&self) -> String {
+        self.market_data_file.clone()
+    }
+
+    // Return seed of the fault injection
+    pub fn get_fault_seed(&self) -> u64 {
+        self.fault_seed
+    }
+
+    /**
+        This function gets the faults injected on a feed
+
+        @params
+            feed: name of the feed ("incremental", "incremental_b", "recovery", "trade", "mbp", "depth", "quote" or "bar")
+        @return
+            The faults of the feed (NO fault if the feed is unknown)
+    */
+    pub fn get_faults(&self, feed: &str) -> FaultConfig {
+        self.faults.clone().get_mut(feed).cloned().unwrap_or_default()
+    }
+
+    /**
+        This function gets the address a multicast socket is bound to
+        (the IP address is replaced by multicast_interface, if set)
+
+        @params
+            bind_address: the bind address of the socket (e.g, feed_bind_address)
+        @return
+            The address to bind the socket to
+    */
+    pub fn multicast_bind_address(&self, bind_address: &str) -> String {
+        if self.multicast_interface.is_empty() {
+            return bind_address.to_string();
+        }
+        let port = bind_address.rsplit(':').next().unwrap_or("0");
+        format!("{}:{}", self.multicast_interface, port)
+    }
+}
@@ -1,22 +1,78 @@
-use std::net::UdpSocket;
-use std::net::Ipv6Addr;
-use std::str;
+extern crate exchange;
 
+use std::{env, process};
+use exchange::client::{FeedHandler, FeedListener, FeedReceiver, LocalBook, ReplayClient};
+use exchange::config::Config;
+use exchange::objects::IncrementalMessage;
 
-fn main() {
-    /** NOTE: port number must match
-        RECOVERY_MULTICAST_GROUP_ADDRESS/INCREMENTAL_FEED_MULTICAST_GROUP_ADDRESS port number
-    **/
-    let socket = UdpSocket::bind("0.0.0.0:21003").unwrap();
+// Display the best bid and offer of a book
+fn print_top(book: &LocalBook) {
+    println!("{:?}: bid {:?} / ask {:?}", book.get_symbol(), book.best_bid(), book.best_ask());
+}
+
+// Listener displaying the changes of the local books
+struct PrintListener;
 
-    // specify multicast address for incremental feed or recovery feed
-    socket.join_multicast_v4(&"239.255.255.255".parse().unwrap(), &"0.0.0.0".parse().unwrap());
+impl FeedListener for PrintListener {
+    fn on_update(&mut self, book: &LocalBook, message: &IncrementalMessage) {
+        print!("#{} {} order {}: ", message.get_num(), message.get_action(), message.get_order_id());
+        print_top(book);
+    }
+
+    fn on_gap(&mut self, first_missing: i64, last_missing: i64) {
+        println!("GAP: messages {} to {} missing, asking for a replay", first_missing, last_missing);
+    }
+
+    fn on_recovery(&mut self, book: &LocalBook, last_msg: i64) {
+        print!("RECOVERED at #{}: ", last_msg);
+        print_top(book);
+    }
+}
+
+fn main() {
+    // Same configuration as the exchange (multicast groups and encoding of the feeds), e.g --feed-encoding binary
+    let args: Vec<String> = env::args().skip(1).collect();
+    let config = Config::load(&args).unwrap_or_else(|reason| {
+        println!("Invalid configuration: {}", reason);
+        process::exit(1);
+    });
 
+    // Join the Incremental Feed and the Recovery Feed, and rebuild the order books from them
+    let receiver = FeedReceiver::join(&config).unwrap();
+    let mut handler = FeedHandler::new(config.get_feed_encoding());
+    handler.add_listener(Box::new(PrintListener));
 
-    while(true) {
-        let mut recv_buffer = vec![0u8; 1000];
-        let (size, addr) = socket.recv_from(&mut recv_buffer).unwrap();
-	    let content = str::from_utf8(&recv_buffer[..size]).unwrap().trim();
-        println!("From {:?}: {:?}", addr, content);
+    // Messages missed are asked to the replay server (ONCE per gap), otherwise the next Recovery Feed fills the gap
+    let mut replay: Option<ReplayClient> = None;
+    let mut requested = None;
+    loop {
+        if let Err(e) = receiver.poll(&mut handler) {
+            println!("Feed error: {}", e);
+            return;
+        }
+        let missing = handler.get_missing();
+        if missing.is_none() || missing == requested {
+            continue;
+        }
+        requested = missing;
+        let (first, last) = missing.unwrap();
+        if replay.is_none() {
+            replay = ReplayClient::connect(&config.get_replay_address()).ok();
+        }
+        let result = match replay.as_mut() {
+            Some(client) => client.request(first, last),
+            None => Err("replay server unreachable".to_string()),
+        };
+        match result {
+            Ok(messages) => {
+                for message in messages {
+                    handler.on_incremental(message);
+                }
+            }
+            Err(reason) => {
+                println!("REPLAY of {} to {} failed ({}), waiting for recovery", first, last, reason);
+                replay = None;
+            }
+        }
     }
 }
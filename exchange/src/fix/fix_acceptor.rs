@@ -0,0 +1,120 @@
+/*!
+    FIX ACCEPTOR
+
+    This contains all relevant properties and functions of the FIX 4.4 acceptor:
+    the TCP server which FIX clients connect to, ONE FIX session per connection
+*/
+
+use fix::{FixMessage, FixSession};
+use gateway::OrderEntryGateway;
+use std::io;
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+// Maximum time (in milliseconds) waiting for data from a client before checking Execution Reports and heartbeats
+const POLL_INTERVAL: u64 = 50;
+
+/**
+    A FIX acceptor has the following properties:
+        - listener:
+            socket listening for connections of the clients
+        - comp_id:
+            SenderCompID of the exchange
+*/
+pub struct FixAcceptor {
+    listener: TcpListener,
+    comp_id: String,
+}
+
+impl FixAcceptor {
+    /**
+        Constructor
+        @params
+            address: address the acceptor listens on (MUST specify the PORT, port 0 lets the system choose one)
+            m_comp_id: SenderCompID of the exchange
+        @return
+            - New FIX acceptor (NOT accepting connections yet)
+            - Or the error if the address cannot be bound
+    */
+    pub fn bind(address: &str, m_comp_id: &str) -> io::Result<FixAcceptor> {
+        Ok(FixAcceptor {
+            listener: TcpListener::bind(address)?,
+            comp_id: m_comp_id.to_string(),
+        })
+    }
+
+    // Return address the acceptor listens on
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /**
+        This function starts accepting connections (in a new thread), ONE thread per session
+
+        @params
+            gateway: the order entry gateway processing the requests of ALL sessions
+        @return
+            Handle of the thread accepting connections
+    */
+    pub fn start(self, gateway: &Arc<Mutex<OrderEntryGateway>>) -> thread::JoinHandle<()> {
+        let gateway = gateway.clone();
+        thread::spawn(move || {
+            for stream in self.listener.incoming() {
+                match stream {
+                    Ok(stream) => {
+                        let mut session = FixSession::new(&self.comp_id, &gateway);
+                        thread::spawn(move || {
+                            if let Err(e) = run_session(stream, &mut session) {
+                                println!("FIX session error: {}", e);
+                            }
+                            session.close();
+                        });
+                    }
+                    Err(e) => println!("FIX connection error: {}", e),
+                }
+            }
+        })
+    }
+}
+
+/**
+    This function runs a FIX session until the client disconnects (or the session is closed)
+
+    @params
+        stream: connection of the client
+        session: the FIX session of the connection
+*/
+fn run_session(mut stream: TcpStream, session: &mut FixSession) -> io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_millis(POLL_INTERVAL)))?;
+    // Data received and not processed yet (a message can be received in several parts)
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut chunk = [0u8; 4096];
+    while !session.is_closed() {
+        match stream.read(&mut chunk) {
+            // Client disconnected
+            Ok(0) => return Ok(()),
+            Ok(size) => buffer.extend_from_slice(&chunk[..size]),
+            Err(ref e) if e.kind() == io::ErrorKind::WouldBlock || e.kind() == io::ErrorKind::TimedOut => {},
+            Err(e) => return Err(e),
+        }
+        let mut outgoing = Vec::new();
+        while let Some(message) = FixMessage::extract(&mut buffer) {
+            // Garbled messages are ignored
+            if let Ok(message) = message {
+                outgoing.append(&mut session.on_message(message));
+            }
+            if session.is_closed() {
+                break;
+            }
+        }
+        outgoing.append(&mut session.poll_reports());
+        outgoing.append(&mut session.on_timer());
+        for message in outgoing {
+            stream.write_all(message.as_bytes())?;
+        }
+    }
+    Ok(())
+}
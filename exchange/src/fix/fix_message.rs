@@ -0,0 +1,206 @@
+/*!
+    FIX MESSAGE
+
+    This contains all relevant properties and functions of a FIX 4.4 message
+    (encoding to and decoding from the tag=value format)
+*/
+
+// Version of the FIX protocol (BeginString)
+pub const BEGIN_STRING: &str = "FIX.4.4";
+// Field delimiter
+pub const SOH: char = '\x01';
+// Tags of the standard header fields, in the order they are encoded (after BeginString and BodyLength)
+const HEADER_TAGS: [u32; 7] = [35, 49, 56, 34, 43, 122, 52];
+
+/**
+    A FIX message has the following properties:
+        - fields:
+            list of (tag, value) of ALL fields of the message, except BeginString (8), BodyLength (9) and CheckSum (10)
+            which are computed when the message is encoded
+*/
+#[derive(PartialEq, Clone, Debug)]
+pub struct FixMessage {
+    fields: Vec<(u32, String)>,
+}
+
+impl FixMessage {
+    /**
+        Constructor
+        @params
+            m_msg_type: type of the message (MsgType, e.g "D" for NewOrderSingle)
+        @return
+            New message without any field other than MsgType
+    */
+    pub fn new(m_msg_type: &str) -> FixMessage {
+        FixMessage {
+            fields: vec![(35, m_msg_type.to_string())],
+        }
+    }
+
+    // Return type of message (MsgType)
+    pub fn get_msg_type(&self) -> String {
+        self.get_field(35).unwrap_or_default()
+    }
+
+    /**
+        This function gets the value of a field
+
+        @params
+            tag: tag of the field
+        @return
+            - Value of the field
+            - None if the message has NO such field
+    */
+    pub fn get_field(&self, tag: u32) -> Option<String> {
+        self.fields.iter().find(|field| field.0 == tag).map(|field| field.1.clone())
+    }
+
+    /**
+        This function sets the value of a field (the field is added if the message does not have it yet)
+
+        @params
+            tag: tag of the field
+            value: new value of the field
+    */
+    pub fn set_field(&mut self, tag: u32, value: &str) {
+        match self.fields.iter_mut().find(|field| field.0 == tag) {
+            Some(field) => field.1 = value.to_string(),
+            None => self.fields.push((tag, value.to_string())),
+        }
+    }
+
+    /**
+        This function encodes the message in tag=value format:
+            8=FIX.4.4|9=<body length>|35=<msg type>|<header fields>|<body fields>|10=<checksum>|
+        (| is the SOH delimiter)
+
+        @return
+            The message ready to be sent
+    */
+    pub fn encode(&self) -> String {
+        let mut body = String::new();
+        // Standard header fields first, then the other fields in the order they were set
+        for tag in HEADER_TAGS.iter() {
+            if let Some(value) = self.get_field(*tag) {
+                body.push_str(&format!("{}={}{}", tag, value, SOH));
+            }
+        }
+        for &(tag, ref value) in &self.fields {
+            if !HEADER_TAGS.contains(&tag) {
+                body.push_str(&format!("{}={}{}", tag, value, SOH));
+            }
+        }
+        let mut message = format!("8={}{}9={}{}{}", BEGIN_STRING, SOH, body.len(), SOH, body);
+        let checksum = checksum(message.as_bytes());
+        message.push_str(&format!("10={:03}{}", checksum, SOH));
+        message
+    }
+
+    /**
+        This function decodes a message in tag=value format
+
+        @params
+            raw: the message received (including BeginString, BodyLength and CheckSum)
+        @return
+            - The message decoded
+            - Or the reason the message is garbled (wrong BeginString, BodyLength or CheckSum, malformed field)
+    */
+    pub fn decode(raw: &str) -> Result<FixMessage, String> {
+        let mut fields: Vec<(u32, String)> = Vec::new();
+        for field in raw.split(SOH).filter(|field| !field.is_empty()) {
+            let mut parts = field.splitn(2, '=');
+            let tag = parts.next().unwrap_or("").parse::<u32>();
+            match (tag, parts.next()) {
+                (Ok(tag), Some(value)) => fields.push((tag, value.to_string())),
+                _ => return Err(format!("malformed field '{}'", field)),
+            }
+        }
+        if fields.len() < 4 || fields[0] != (8, BEGIN_STRING.to_string()) || fields[1].0 != 9 || fields[2].0 != 35 {
+            return Err("message must start with BeginString, BodyLength and MsgType".to_string());
+        }
+        // Body: from MsgType to the delimiter before CheckSum
+        let body_start = raw.find(&format!("{}35=", SOH)).unwrap() + 1;
+        let checksum_start = match raw.rfind(&format!("{}10=", SOH)) {
+            Some(index) => index + 1,
+            None => return Err("CheckSum missing".to_string()),
+        };
+        if fields[1].1 != (checksum_start - body_start).to_string() {
+            return Err(format!("wrong BodyLength {}", fields[1].1));
+        }
+        let (last_tag, ref last_value) = fields[fields.len() - 1];
+        if last_tag != 10 || *last_value != format!("{:03}", checksum(&raw.as_bytes()[..checksum_start])) {
+            return Err("wrong CheckSum".to_string());
+        }
+        fields.remove(0);
+        fields.remove(0);
+        fields.pop();
+        Ok(FixMessage { fields })
+    }
+
+    /**
+        This function takes the first complete message out of the data received on a connection
+
+        @params
+            buffer: data received and not processed yet (the message is removed from it)
+        @return
+            - The first message (decoded, or the reason it is garbled)
+            - None if there is NO complete message in the buffer yet
+    */
+    pub fn extract(buffer: &mut Vec<u8>) -> Option<Result<FixMessage, String>> {
+        // Skip data before the beginning of a message
+        let start = match find(buffer, b"8=", 0) {
+            Some(start) => start,
+            None => {
+                buffer.clear();
+                return None;
+            }
+        };
+        buffer.drain(..start);
+        // BodyLength is the second field
+        let length_start = find(buffer, &[SOH as u8, b'9', b'='], 0)? + 3;
+        let length_end = find(buffer, &[SOH as u8], length_start)?;
+        let body_length = String::from_utf8_lossy(&buffer[length_start..length_end]).parse::<usize>();
+        let body_length = match body_length {
+            Ok(body_length) => body_length,
+            Err(_) => {
+                // Garbled message: drop it up to the next message
+                let next = find(buffer, b"8=", 1).unwrap_or(buffer.len());
+                buffer.drain(..next);
+                return Some(Err("malformed BodyLength".to_string()));
+            }
+        };
+        // Message ends with the CheckSum field (10=nnn|) after the body
+        let checksum_start = length_end + 1 + body_length;
+        let message_end = find(buffer, &[SOH as u8], checksum_start)? + 1;
+        let raw: Vec<u8> = buffer.drain(..message_end).collect();
+        Some(FixMessage::decode(&String::from_utf8_lossy(&raw)))
+    }
+}
+
+/**
+    This function calculates the checksum of a message (sum of ALL bytes modulo 256)
+
+    @params
+        data: the message up to (and including) the delimiter before the CheckSum field
+*/
+fn checksum(data: &[u8]) -> u32 {
+    data.iter().fold(0u32, |sum, byte| (sum + u32::from(*byte)) % 256)
+}
+
+/**
+    This function finds a pattern in data
+
+    @params
+        data: the data to search in
+        pattern: the pattern to find
+        from: the index from which the search starts
+    @return
+        - Index of the first occurrence of the pattern
+        - None if NOT FOUND
+*/
+fn find(data: &[u8], pattern: &[u8], from: usize) -> Option<usize> {
+    if data.len() < from + pattern.len() {
+        return None;
+    }
+    (from..=data.len() - pattern.len()).find(|&index| &data[index..index + pattern.len()] == pattern)
+}
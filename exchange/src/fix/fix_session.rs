@@ -0,0 +1,674 @@
+/*!
+    FIX SESSION
+
+    This contains all relevant properties and functions of a FIX 4.4 session (acceptor side):
+        - Session messages: Logon (A), Logout (5), Heartbeat (0), TestRequest (1), ResendRequest (2),
+        SequenceReset (4), Reject (3)
+        - Application messages: NewOrderSingle (D), OrderCancelRequest (F), OrderCancelReplaceRequest (G)
+        which are sent to the matching engine (via the order entry gateway), responses are
+        ExecutionReport (8) and OrderCancelReject (9)
+*/
+
+use clock::{Clock, utc_timestamp};
+use fix::FixMessage;
+use gateway::OrderEntryGateway;
+use objects::{Order, OrderEntryRequest, ExecutionReport, ExchangeError};
+use std::collections::HashMap;
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::{Duration, Instant};
+
+// Heartbeat interval (in seconds) used if the client does not specify one at Logon
+const DEFAULT_HEART_BT_INT: u64 = 30;
+
+/**
+    Request of the client being processed, used to fill the responses
+        - msg_type: type of the request ('D', 'F' or 'G')
+        - cl_ord_id: ClOrdID of the request
+        - orig_cl_ord_id: OrigClOrdID of the request (cancel/replace requests only)
+        - order_id: ID of the order to be cancelled/replaced (cancel/replace requests only)
+*/
+struct Request {
+    msg_type: char,
+    cl_ord_id: String,
+    orig_cl_ord_id: String,
+    order_id: String,
+}
+
+/**
+    A FIX session has the following properties:
+        - comp_id:
+            SenderCompID of the exchange
+        - target_comp_id:
+            SenderCompID of the client (known after Logon)
+        - logged_on:
+            true if the client is logged on
+        - closed:
+            true if the connection must be closed (after Logout or a fatal session error)
+        - heart_bt_int:
+            heartbeat interval (in seconds) agreed at Logon
+        - next_out_seq:
+            MsgSeqNum of the next message sent to the client
+        - next_in_seq:
+            MsgSeqNum expected for the next message received from the client
+        - resend_requested:
+            true if a ResendRequest was sent and the gap is NOT filled yet
+        - sent:
+            ALL messages sent to the client (message with MsgSeqNum n is at index n - 1), kept for ResendRequest
+        - last_sent / last_received:
+            time the last message was sent to / received from the client
+        - test_request_sent:
+            true if a TestRequest was sent because the client was silent
+        - gateway:
+            order entry gateway processing the requests (shared by ALL sessions)
+        - clock:
+            time source of SendingTime (clock of the matching engine of the gateway)
+        - session_id:
+            ID of the session in the order entry gateway
+        - report_receiver:
+            a channel to receive Execution Reports of the orders of the session
+        - cl_ord_ids / order_ids:
+            ClOrdID of the open orders (key = order ID -> value = ClOrdID) and the reverse mapping
+        - cum_qty:
+            quantity traded of the open orders (key = order ID -> value = (cumulative quantity, cumulative notional))
+        - exec_id_count:
+            used to assign ExecID of Execution Reports
+*/
+pub struct FixSession {
+    comp_id: String,
+    target_comp_id: String,
+    logged_on: bool,
+    closed: bool,
+    heart_bt_int: u64,
+    next_out_seq: i64,
+    next_in_seq: i64,
+    resend_requested: bool,
+    sent: Vec<FixMessage>,
+    last_sent: Instant,
+    last_received: Instant,
+    test_request_sent: bool,
+    gateway: Arc<Mutex<OrderEntryGateway>>,
+    clock: Box<dyn Clock>,
+    session_id: i64,
+    report_receiver: mpsc::Receiver<ExecutionReport>,
+    cl_ord_ids: HashMap<String, String>,
+    order_ids: HashMap<String, String>,
+    cum_qty: HashMap<String, (i64, i64)>,
+    exec_id_count: i64,
+}
+
+impl FixSession {
+    /**
+        Constructor
+        @params
+            m_comp_id: SenderCompID of the exchange
+            m_gateway: order entry gateway processing the requests
+        @return
+            New session waiting for Logon (registered in the order entry gateway)
+    */
+    pub fn new(m_comp_id: &str, m_gateway: &Arc<Mutex<OrderEntryGateway>>) -> FixSession {
+        let (report_sender, report_receiver) = mpsc::channel();
+        let (session_id, clock) = {
+            let mut gateway = m_gateway.lock().unwrap();
+            (gateway.open_session(&report_sender), gateway.get_clock())
+        };
+        FixSession {
+            comp_id: m_comp_id.to_string(),
+            target_comp_id: String::new(),
+            logged_on: false,
+            closed: false,
+            heart_bt_int: DEFAULT_HEART_BT_INT,
+            next_out_seq: 1,
+            next_in_seq: 1,
+            resend_requested: false,
+            sent: Vec::new(),
+            last_sent: Instant::now(),
+            last_received: Instant::now(),
+            test_request_sent: false,
+            gateway: m_gateway.clone(),
+            clock,
+            session_id,
+            report_receiver,
+            cl_ord_ids: HashMap::new(),
+            order_ids: HashMap::new(),
+            cum_qty: HashMap::new(),
+            exec_id_count: 0,
+        }
+    }
+
+    // Return true if the connection must be closed
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    /**
+        This function unregisters the session from the order entry gateway (when the connection is closed).
+        Orders of the session stay on the order book
+    */
+    pub fn close(&mut self) {
+        self.closed = true;
+        self.gateway.lock().unwrap().close_session(self.session_id);
+    }
+
+    /**
+        This function processes a message received from the client
+
+        @params
+            message: the message received
+        @return
+            List of messages (encoded) to be sent to the client
+    */
+    pub fn on_message(&mut self, message: FixMessage) -> Vec<String> {
+        let mut outgoing = Vec::new();
+        self.last_received = Instant::now();
+        self.test_request_sent = false;
+        let msg_type = message.get_msg_type();
+        let seq = match message.get_field(34).and_then(|seq| seq.parse::<i64>().ok()) {
+            Some(seq) => seq,
+            None => {
+                outgoing.push(self.logout("MsgSeqNum missing"));
+                self.closed = true;
+                return outgoing;
+            }
+        };
+
+        // The first message MUST be a Logon, otherwise the connection is closed without response
+        if !self.logged_on {
+            if msg_type != "A" {
+                self.closed = true;
+                return outgoing;
+            }
+            self.target_comp_id = message.get_field(49).unwrap_or_default();
+            self.heart_bt_int = message.get_field(108).and_then(|interval| interval.parse::<u64>().ok()).unwrap_or(DEFAULT_HEART_BT_INT);
+            let reset = message.get_field(141) == Some("Y".to_string());
+            if reset {
+                self.next_in_seq = 1;
+                self.next_out_seq = 1;
+                self.sent.clear();
+            }
+            let mut logon = FixMessage::new("A");
+            logon.set_field(98, "0");
+            logon.set_field(108, &self.heart_bt_int.to_string());
+            if reset {
+                logon.set_field(141, "Y");
+            }
+            outgoing.push(self.send(logon));
+            self.logged_on = true;
+        }
+
+        // SequenceReset (Reset mode) sets the next MsgSeqNum expected whatever the MsgSeqNum of the message
+        if msg_type == "4" && message.get_field(123) != Some("Y".to_string()) {
+            if let Some(new_seq) = message.get_field(36).and_then(|new_seq| new_seq.parse::<i64>().ok()) {
+                self.next_in_seq = new_seq;
+            }
+            return outgoing;
+        }
+
+        // Check MsgSeqNum
+        if seq > self.next_in_seq {
+            // Messages are missing: ask the client to send them again (ONCE for a gap)
+            if !self.resend_requested {
+                let mut resend_request = FixMessage::new("2");
+                resend_request.set_field(7, &self.next_in_seq.to_string());
+                resend_request.set_field(16, "0");
+                outgoing.push(self.send(resend_request));
+                self.resend_requested = true;
+            }
+            // ResendRequest and Logout are processed anyway, other messages will be sent again by the client
+            if msg_type != "2" && msg_type != "5" {
+                return outgoing;
+            }
+        } else if seq < self.next_in_seq {
+            // Messages sent again (PossDupFlag) are ignored, otherwise the session is broken
+            if message.get_field(43) != Some("Y".to_string()) {
+                let text = format!("MsgSeqNum too low, expecting {} but received {}", self.next_in_seq, seq);
+                outgoing.push(self.logout(&text));
+                self.closed = true;
+            }
+            return outgoing;
+        } else {
+            self.next_in_seq += 1;
+            self.resend_requested = false;
+        }
+
+        match msg_type.as_str() {
+            // Logon already processed, Heartbeat does not need any response
+            "A" | "0" => {},
+            "1" => {
+                // TestRequest: respond with a Heartbeat carrying the TestReqID
+                let mut heartbeat = FixMessage::new("0");
+                heartbeat.set_field(112, &message.get_field(112).unwrap_or_default());
+                outgoing.push(self.send(heartbeat));
+            }
+            "2" => {
+                let begin = message.get_field(7).and_then(|seq| seq.parse::<i64>().ok()).unwrap_or(1);
+                let end = message.get_field(16).and_then(|seq| seq.parse::<i64>().ok()).unwrap_or(0);
+                outgoing.append(&mut self.resend(begin, end));
+            }
+            "4" => {
+                // SequenceReset (GapFill mode): messages up to NewSeqNo are NOT sent again
+                if let Some(new_seq) = message.get_field(36).and_then(|new_seq| new_seq.parse::<i64>().ok()) {
+                    if new_seq > self.next_in_seq {
+                        self.next_in_seq = new_seq;
+                    }
+                }
+            }
+            "5" => {
+                // Logout: confirm and close the connection
+                outgoing.push(self.logout(""));
+                self.closed = true;
+            }
+            "D" | "F" | "G" => outgoing.append(&mut self.on_request(&message, seq)),
+            _ => outgoing.push(self.reject(seq, &msg_type, None, 11, "unsupported MsgType")),
+        }
+        outgoing
+    }
+
+    /**
+        This function sends the Execution Reports of the orders of the session
+        caused by the requests of other sessions (e.g, fills of resting orders)
+
+        @return
+            List of messages (encoded) to be sent to the client
+    */
+    pub fn poll_reports(&mut self) -> Vec<String> {
+        if !self.logged_on {
+            return Vec::new();
+        }
+        self.drain_reports(None)
+    }
+
+    /**
+        This function checks the heartbeat interval:
+            - A Heartbeat is sent if NO message was sent during the interval
+            - A TestRequest is sent if NO message was received during the interval (plus 20%)
+            - The connection is closed if the client does not respond to the TestRequest
+
+        @return
+            List of messages (encoded) to be sent to the client
+    */
+    pub fn on_timer(&mut self) -> Vec<String> {
+        let mut outgoing = Vec::new();
+        if !self.logged_on || self.closed {
+            return outgoing;
+        }
+        let interval = Duration::from_secs(self.heart_bt_int);
+        if self.last_received.elapsed() >= interval * 2 + interval / 5 {
+            outgoing.push(self.logout("heartbeat timeout"));
+            self.closed = true;
+            return outgoing;
+        }
+        if !self.test_request_sent && self.last_received.elapsed() >= interval + interval / 5 {
+            let mut test_request = FixMessage::new("1");
+            test_request.set_field(112, &self.next_out_seq.to_string());
+            outgoing.push(self.send(test_request));
+            self.test_request_sent = true;
+        }
+        if self.last_sent.elapsed() >= interval {
+            outgoing.push(self.send(FixMessage::new("0")));
+        }
+        outgoing
+    }
+
+    /**
+        This function processes an application message (NewOrderSingle, OrderCancelRequest, OrderCancelReplaceRequest)
+
+        @params
+            message: the message received
+            seq: MsgSeqNum of the message
+        @return
+            List of messages (encoded) to be sent to the client
+    */
+    fn on_request(&mut self, message: &FixMessage, seq: i64) -> Vec<String> {
+        let msg_type = message.get_msg_type();
+        // Check the required fields
+        let required: &[u32] = match msg_type.as_str() {
+            "D" => &[11, 55, 54, 38, 40],
+            "F" => &[11, 41, 55, 54],
+            _ => &[11, 41, 55, 54, 38, 40],
+        };
+        for tag in required {
+            if message.get_field(*tag).is_none() {
+                return vec![self.reject(seq, &msg_type, Some(*tag), 1, "required tag missing")];
+            }
+        }
+        // Quantity, price, stop price and display quantity (MaxFloor) MUST be integers
+        let mut numbers = [0i64; 4];
+        for (index, tag) in [38u32, 44u32, 99u32, 111u32].iter().enumerate() {
+            if let Some(value) = message.get_field(*tag) {
+                numbers[index] = match value.parse::<i64>() {
+                    Ok(number) => number,
+                    Err(_) => return vec![self.reject(seq, &msg_type, Some(*tag), 6, "incorrect data format for value")],
+                };
+            }
+        }
+        let (qty, price, stop_px, display_qty) = (numbers[0], numbers[1], numbers[2], numbers[3]);
+
+        let mut request = Request {
+            msg_type: msg_type.chars().next().unwrap(),
+            cl_ord_id: message.get_field(11).unwrap(),
+            orig_cl_ord_id: message.get_field(41).unwrap_or_default(),
+            order_id: String::new(),
+        };
+        let entry_request = if request.msg_type == 'D' {
+            let mut order = Order::new(qty, price, first_char(message.get_field(54)));
+            order.set_symbol(&message.get_field(55).unwrap());
+            order.set_account(&message.get_field(1).unwrap_or_default());
+            order.set_ord_type(first_char(message.get_field(40)));
+            order.set_stop_px(stop_px);
+            order.set_display_qty(display_qty);
+            // ExecInst (18) 6 = Participate don't initiate, i.e, post-only (rejected if it would match)
+            if message.get_field(18).is_some_and(|exec_inst| exec_inst.split(' ').any(|value| value == "6")) {
+                order.set_post_only('1');
+            }
+            order.set_time_in_force(message.get_field(59).map_or('0', |tif| first_char(Some(tif))));
+            OrderEntryRequest::new_order(&order)
+        } else {
+            // Order to be cancelled/replaced: OrderID if specified, otherwise the order entered with OrigClOrdID
+            request.order_id = match message.get_field(37).or_else(|| self.order_ids.get(&request.orig_cl_ord_id).cloned()) {
+                Some(order_id) => order_id,
+                None => {
+                    let reason = ExchangeError::UnknownOrder(request.orig_cl_ord_id.clone());
+                    return vec![self.cancel_reject(&request, &reason)];
+                }
+            };
+            if request.msg_type == 'F' {
+                OrderEntryRequest::cancel(&request.order_id)
+            } else {
+                let mut replace = OrderEntryRequest::replace(&request.order_id, price, qty);
+                replace.set_stop_px(stop_px);
+                replace
+            }
+        };
+
+        // Reports caused by the request are collected while the gateway is locked,
+        // so that they are NOT mixed with reports caused by requests of other sessions
+        let gateway = self.gateway.clone();
+        let mut gateway = gateway.lock().unwrap();
+        let mut outgoing = self.drain_reports(None);
+        let result = gateway.handle(self.session_id, &entry_request);
+        if result.is_ok() && request.msg_type != 'D' {
+            // From now on, the order is identified by the ClOrdID of the request
+            self.order_ids.remove(&request.orig_cl_ord_id);
+            self.order_ids.insert(request.cl_ord_id.clone(), request.order_id.clone());
+            self.cl_ord_ids.insert(request.order_id.clone(), request.cl_ord_id.clone());
+        }
+        outgoing.append(&mut self.drain_reports(Some(&request)));
+        outgoing
+    }
+
+    /**
+        This function converts the Execution Reports received from the order entry gateway to FIX messages
+
+        @params
+            request: the request of the client which caused the reports (None if caused by other sessions)
+        @return
+            List of messages (encoded) to be sent to the client
+    */
+    fn drain_reports(&mut self, request: Option<&Request>) -> Vec<String> {
+        let mut outgoing = Vec::new();
+        while let Ok(report) = self.report_receiver.try_recv() {
+            let order_id = report.get_order_id();
+            let mut orig_cl_ord_id = None;
+            if let Some(request) = request {
+                if report.get_exec_type() == '8' && request.msg_type != 'D' {
+                    // Rejected cancel/replace requests are answered by an OrderCancelReject
+                    let reason = report.get_reject_reason().unwrap();
+                    outgoing.push(self.cancel_reject(request, &reason));
+                    continue;
+                }
+                if request.msg_type == 'D' && !self.cl_ord_ids.contains_key(&order_id) && (report.get_exec_type() == '0' || report.get_exec_type() == '8') {
+                    // Response to a new order: the order is identified by the ClOrdID of the request
+                    self.cl_ord_ids.insert(order_id.clone(), request.cl_ord_id.clone());
+                    self.order_ids.insert(request.cl_ord_id.clone(), order_id.clone());
+                }
+                if request.msg_type != 'D' && order_id == request.order_id && (report.get_exec_type() == '4' || report.get_exec_type() == '5') {
+                    orig_cl_ord_id = Some(request.orig_cl_ord_id.clone());
+                }
+            }
+            let execution_report = self.execution_report(&report, orig_cl_ord_id);
+            outgoing.push(self.send(execution_report));
+            // Orders which are NOT open anymore are forgotten
+            if report.get_exec_type() == '8' || report.get_leaves_qty() == 0 {
+                if let Some(cl_ord_id) = self.cl_ord_ids.remove(&order_id) {
+                    self.order_ids.remove(&cl_ord_id);
+                }
+                self.cum_qty.remove(&order_id);
+            }
+        }
+        outgoing
+    }
+
+    /**
+        This function creates a FIX ExecutionReport (8) from an Execution Report of the matching engine
+
+        @params
+            report: the Execution Report of the matching engine
+            orig_cl_ord_id: OrigClOrdID (responses to cancel/replace requests only)
+    */
+    fn execution_report(&mut self, report: &ExecutionReport, orig_cl_ord_id: Option<String>) -> FixMessage {
+        let order_id = report.get_order_id();
+        let exec_type = report.get_exec_type();
+        // Quantity traded so far
+        let (cum_qty, cum_notional) = {
+            let cum = self.cum_qty.entry(order_id.clone()).or_insert((0, 0));
+            if exec_type == 'F' {
+                cum.0 += report.get_last_qty();
+                cum.1 += report.get_last_qty() * report.get_last_px();
+            }
+            *cum
+        };
+        let ord_status = match exec_type {
+            '4' | '8' => exec_type,
+            _ if report.get_leaves_qty() == 0 && cum_qty > 0 => '2',
+            _ if cum_qty > 0 => '1',
+            _ => '0',
+        };
+        self.exec_id_count += 1;
+
+        let mut message = FixMessage::new("8");
+        message.set_field(37, if order_id == "-1" { "NONE" } else { &order_id });
+        message.set_field(11, &self.cl_ord_ids.get(&order_id).cloned().unwrap_or_default());
+        if let Some(orig_cl_ord_id) = orig_cl_ord_id {
+            message.set_field(41, &orig_cl_ord_id);
+        }
+        message.set_field(17, &self.exec_id_count.to_string());
+        message.set_field(150, &exec_type.to_string());
+        message.set_field(39, &ord_status.to_string());
+        message.set_field(55, &report.get_symbol());
+        message.set_field(54, &report.get_side().to_string());
+        if report.get_price() > 0 {
+            message.set_field(44, &report.get_price().to_string());
+        }
+        message.set_field(151, &report.get_leaves_qty().to_string());
+        message.set_field(14, &cum_qty.to_string());
+        let avg_px = if cum_qty > 0 { cum_notional as f64 / cum_qty as f64 } else { 0.0 };
+        message.set_field(6, &avg_px.to_string());
+        if exec_type == 'F' {
+            message.set_field(31, &report.get_last_px().to_string());
+            message.set_field(32, &report.get_last_qty().to_string());
+        }
+        message.set_field(60, &report.get_transact_time());
+        if let Some(reason) = report.get_reject_reason() {
+            message.set_field(103, ord_rej_reason(&reason));
+            message.set_field(58, &reason.to_string());
+        }
+        message
+    }
+
+    /**
+        This function creates an OrderCancelReject (9) for a cancel/replace request
+
+        @params
+            request: the rejected request
+            reason: the reason of the rejection
+        @return
+            The message (encoded) to be sent to the client
+    */
+    fn cancel_reject(&mut self, request: &Request, reason: &ExchangeError) -> String {
+        let mut message = FixMessage::new("9");
+        message.set_field(37, if request.order_id.is_empty() { "NONE" } else { &request.order_id });
+        message.set_field(11, &request.cl_ord_id);
+        message.set_field(41, &request.orig_cl_ord_id);
+        // Status of the order after the rejection
+        let ord_status = match self.cum_qty.get(&request.order_id) {
+            Some(&(cum_qty, _)) if cum_qty > 0 => '1',
+            _ if self.cl_ord_ids.contains_key(&request.order_id) => '0',
+            _ => '8',
+        };
+        message.set_field(39, &ord_status.to_string());
+        message.set_field(434, if request.msg_type == 'F' { "1" } else { "2" });
+        message.set_field(102, match *reason {
+            ExchangeError::UnknownOrder(_) => "1",
+            _ => "99",
+        });
+        message.set_field(58, &reason.to_string());
+        self.send(message)
+    }
+
+    /**
+        This function creates a session-level Reject (3)
+
+        @params
+            ref_seq: MsgSeqNum of the rejected message
+            ref_msg_type: MsgType of the rejected message
+            ref_tag: tag of the field causing the rejection (if any)
+            reason: SessionRejectReason (e.g, 1 = required tag missing)
+            text: description of the rejection
+        @return
+            The message (encoded) to be sent to the client
+    */
+    fn reject(&mut self, ref_seq: i64, ref_msg_type: &str, ref_tag: Option<u32>, reason: i64, text: &str) -> String {
+        let mut message = FixMessage::new("3");
+        message.set_field(45, &ref_seq.to_string());
+        if let Some(ref_tag) = ref_tag {
+            message.set_field(371, &ref_tag.to_string());
+        }
+        message.set_field(372, ref_msg_type);
+        message.set_field(373, &reason.to_string());
+        message.set_field(58, text);
+        self.send(message)
+    }
+
+    /**
+        This function creates a Logout (5)
+
+        @params
+            text: reason of the logout (empty if none)
+        @return
+            The message (encoded) to be sent to the client
+    */
+    fn logout(&mut self, text: &str) -> String {
+        let mut message = FixMessage::new("5");
+        if !text.is_empty() {
+            message.set_field(58, text);
+        }
+        self.send(message)
+    }
+
+    /**
+        This function sends again the messages requested by the client (ResendRequest).
+        Application messages are sent again with PossDupFlag, session messages are replaced by
+        SequenceReset (GapFill mode)
+
+        @params
+            begin: MsgSeqNum of the first message requested
+            end: MsgSeqNum of the last message requested (0 = ALL messages after begin)
+        @return
+            List of messages (encoded) to be sent to the client
+    */
+    fn resend(&mut self, begin: i64, end: i64) -> Vec<String> {
+        let mut outgoing = Vec::new();
+        let last = self.next_out_seq - 1;
+        let end = if end == 0 || end > last { last } else { end };
+        let begin = begin.max(1);
+        // First MsgSeqNum of the session messages which are NOT sent again
+        let mut gap_start: Option<i64> = None;
+        for seq in begin..=end {
+            let message = self.sent[(seq - 1) as usize].clone();
+            if is_session_message(&message.get_msg_type()) {
+                gap_start = gap_start.or(Some(seq));
+                continue;
+            }
+            if let Some(gap_seq) = gap_start.take() {
+                outgoing.push(self.gap_fill(gap_seq, seq));
+            }
+            let mut duplicate = message;
+            duplicate.set_field(43, "Y");
+            duplicate.set_field(122, &duplicate.get_field(52).unwrap_or_default());
+            duplicate.set_field(52, &utc_timestamp(self.clock.now_ms()));
+            outgoing.push(duplicate.encode());
+        }
+        if let Some(gap_seq) = gap_start {
+            outgoing.push(self.gap_fill(gap_seq, end + 1));
+        }
+        if !outgoing.is_empty() {
+            self.last_sent = Instant::now();
+        }
+        outgoing
+    }
+
+    /**
+        This function creates a SequenceReset (GapFill mode) sent instead of session messages
+
+        @params
+            seq: MsgSeqNum of the first message NOT sent again
+            new_seq: MsgSeqNum of the next message sent again (or of the next new message)
+        @return
+            The message (encoded) to be sent to the client
+    */
+    fn gap_fill(&self, seq: i64, new_seq: i64) -> String {
+        let mut message = FixMessage::new("4");
+        self.set_header(&mut message, seq);
+        message.set_field(43, "Y");
+        message.set_field(123, "Y");
+        message.set_field(36, &new_seq.to_string());
+        message.encode()
+    }
+
+    /**
+        This function sends a new message to the client: fills the header,
+        assigns the next MsgSeqNum and keeps the message (for ResendRequest)
+
+        @params
+            message: the message to be sent
+        @return
+            The message encoded
+    */
+    fn send(&mut self, mut message: FixMessage) -> String {
+        let seq = self.next_out_seq;
+        self.set_header(&mut message, seq);
+        self.next_out_seq += 1;
+        self.last_sent = Instant::now();
+        let encoded = message.encode();
+        self.sent.push(message);
+        encoded
+    }
+
+    // Fill the standard header of a message (SenderCompID, TargetCompID, MsgSeqNum, SendingTime)
+    fn set_header(&self, message: &mut FixMessage, seq: i64) {
+        message.set_field(49, &self.comp_id);
+        message.set_field(56, &self.target_comp_id);
+        message.set_field(34, &seq.to_string());
+        message.set_field(52, &utc_timestamp(self.clock.now_ms()));
+    }
+}
+
+// Return true if the message is a session message (NOT sent again after a ResendRequest)
+fn is_session_message(msg_type: &str) -> bool {
+    matches!(msg_type, "0" | "1" | "2" | "3" | "4" | "5" | "A")
+}
+
+// Return OrdRejReason of a reason of rejection
+fn ord_rej_reason(reason: &ExchangeError) -> &'static str {
+    match *reason {
+        ExchangeError::UnknownOrder(_) => "5",
+        ExchangeError::NonPositiveQuantity(_) | ExchangeError::InvalidDisplayQuantity(_) => "13",
+        ExchangeError::InvalidSide(_) | ExchangeError::InvalidOrderType(_) | ExchangeError::InvalidTimeInForce(_) => "11",
+        _ => "99",
+    }
+}
+
+// Return first character of a field ('*' if the field is missing or empty)
+fn first_char(value: Option<String>) -> char {
+    value.and_then(|value| value.chars().next()).unwrap_or('*')
+}
@@ -0,0 +1,6 @@
+pub use self::fix_message::FixMessage;
+pub use self::fix_session::FixSession;
+pub use self::fix_acceptor::FixAcceptor;
+mod fix_message;
+mod fix_session;
+mod fix_acceptor;
\ No newline at end of file
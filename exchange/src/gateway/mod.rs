@@ -0,0 +1,4 @@
+pub use self::order_entry_gateway::{OrderEntryGateway, CONSOLE_SESSION};
+pub use self::order_entry_server::OrderEntryServer;
+mod order_entry_gateway;
+mod order_entry_server;
\ No newline at end of file
@@ -0,0 +1,176 @@
+/*!
+    ORDER ENTRY GATEWAY
+
+    This contains all relevant properties and functions of the order entry gateway:
+    the ONLY entry point of requests (from network sessions and from the console) to the matching engine
+*/
+
+use clock::Clock;
+use matching_engine::MatchingEngine;
+use objects::{OrderEntryRequest, ExecutionReport, ExchangeError, Trade};
+use std::collections::HashMap;
+use std::sync::mpsc;
+
+// Session ID of the console (command-line interface of main.rs)
+// * NOTE: The console displays the results of its requests itself, hence it does NOT receive Execution Reports
+pub const CONSOLE_SESSION: i64 = 0;
+
+/**
+    An order entry gateway has the following properties:
+        - match_eng:
+            the matching engine processing the requests
+        - report_receiver:
+            a channel to receive Execution Reports from the matching engine
+        - sessions:
+            sessions connected to the gateway (key = session ID -> value = channel to send Execution Reports to the session)
+        - owners:
+            owner of every order entered via the gateway (key = order ID -> value = session ID)
+        - session_count:
+            used to assign ID for new sessions
+*/
+pub struct OrderEntryGateway {
+    match_eng: MatchingEngine,
+    report_receiver: mpsc::Receiver<ExecutionReport>,
+    sessions: HashMap<i64, mpsc::Sender<ExecutionReport>>,
+    owners: HashMap<String, i64>,
+    session_count: i64,
+}
+
+impl OrderEntryGateway {
+    /**
+        Constructor
+        @params
+            m_match_eng: the matching engine (its Execution Reports are routed by the gateway from now on)
+        @return
+            New order entry gateway without any session
+    */
+    pub fn new(mut m_match_eng: MatchingEngine) -> OrderEntryGateway {
+        let (report_sender, report_receiver) = mpsc::channel();
+        m_match_eng.set_report_channel(&report_sender);
+        OrderEntryGateway {
+            match_eng: m_match_eng,
+            report_receiver,
+            sessions: HashMap::new(),
+            owners: HashMap::new(),
+            session_count: CONSOLE_SESSION + 1,
+        }
+    }
+
+    /**
+        This function registers a new session
+
+        @params
+            sender: a channel to send Execution Reports to the session
+        @return
+            ID of the new session
+    */
+    pub fn open_session(&mut self, sender: &mpsc::Sender<ExecutionReport>) -> i64 {
+        let session_id = self.session_count;
+        self.session_count += 1;
+        self.sessions.insert(session_id, sender.clone());
+        session_id
+    }
+
+    /**
+        This function unregisters a session.
+        Orders of the session stay on the order book, but their Execution Reports are dropped
+
+        @params
+            session_id: ID of the session to be closed
+    */
+    pub fn close_session(&mut self, session_id: i64) {
+        self.sessions.remove(&session_id);
+    }
+
+    /**
+        This function processes a request of a session (NEW ORDER, CANCEL or REPLACE)
+        and routes the resulting Execution Reports:
+            - Reports about an order go to the session which entered the order
+            - Reports about a new order (or a rejected request) go to the session which sent the request
+        A session can only cancel/replace the orders it is recorded as owner of, the request is rejected (as an unknown
+        order) otherwise, including when the owner of the order is unknown
+
+        @params
+            session_id: ID of the session sending the request
+            request: the request to be processed
+        @return
+            - List of trades resulting from the request (empty for CANCEL requests)
+            - Or the reason the request was rejected
+    */
+    pub fn handle(&mut self, session_id: i64, request: &OrderEntryRequest) -> Result<Vec<Trade>, ExchangeError> {
+        // Sessions can only cancel/replace their own orders (the console can cancel/replace ANY order)
+        let is_owner = session_id == CONSOLE_SESSION || self.owners.get(&request.get_order_id()) == Some(&session_id);
+        if (request.get_msg_type() == 'F' || request.get_msg_type() == 'G') && !is_owner {
+            let reason = ExchangeError::UnknownOrder(request.get_order_id());
+            self.send(session_id, ExecutionReport::rejected(&request.get_order_id(), '*', request.get_price(), &reason));
+            return Err(reason);
+        }
+        let result = match request.get_msg_type() {
+            'D' => self.match_eng.insert(&request.to_order()).map(|(_, trades)| trades),
+            'F' => self.match_eng.delete(&request.get_order_id()).map(|_| Vec::new()),
+            'G' => self.match_eng.update(&request.get_order_id(), &request.to_order()),
+            msg_type => {
+                let reason = ExchangeError::InvalidMessage(format!("unknown message type '{}'", msg_type));
+                self.send(session_id, ExecutionReport::rejected(&request.get_order_id(), '*', 0, &reason));
+                Err(reason)
+            }
+        };
+        self.route_reports(session_id);
+        result
+    }
+
+    /**
+        This function sends ALL Execution Reports received from the matching engine to their sessions
+
+        @params
+            session_id: ID of the session whose request produced the reports
+    */
+    fn route_reports(&mut self, session_id: i64) {
+        while let Ok(report) = self.report_receiver.try_recv() {
+            let owner = match self.owners.get(&report.get_order_id()) {
+                Some(&owner) => owner,
+                None => {
+                    // A report about an order which is unknown to the gateway is the response to the request
+                    // (if it is a new order, the session which sent the request becomes its owner)
+                    if report.get_exec_type() == '0' {
+                        self.owners.insert(report.get_order_id(), session_id);
+                    }
+                    session_id
+                }
+            };
+            // Orders which are NOT open anymore (cancelled or fully matched) no longer need an owner
+            if (report.get_exec_type() == '4' || report.get_exec_type() == 'F') && report.get_leaves_qty() == 0 {
+                self.owners.remove(&report.get_order_id());
+            }
+            self.send(owner, report);
+        }
+    }
+
+    /**
+        This function sends an Execution Report to a session (if the session is still connected)
+
+        @params
+            session_id: ID of the session
+            report: the Execution Report to be sent
+    */
+    fn send(&self, session_id: i64, report: ExecutionReport) {
+        if let Some(sender) = self.sessions.get(&session_id) {
+            let _ = sender.send(report);
+        }
+    }
+
+    // Publish the OHLCV bars whose interval is over (see MatchingEngine::flush_bars)
+    pub fn flush_bars(&mut self) {
+        self.match_eng.flush_bars();
+    }
+
+    // Return a copy of the clock of the matching engine (see MatchingEngine::get_clock)
+    pub fn get_clock(&self) -> Box<dyn Clock> {
+        self.match_eng.get_clock()
+    }
+
+    // Display the FULL order book of every instrument (see MatchingEngine::print_status)
+    pub fn print_status(&self) {
+        self.match_eng.print_status();
+    }
+}
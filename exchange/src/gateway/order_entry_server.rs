@@ -0,0 +1,133 @@
+/*!
+    ORDER ENTRY SERVER
+
+    This contains all relevant properties and functions of the TCP order entry server.
+    Every client connected to the server has its own session:
+        - Client sends requests (NEW ORDER, CANCEL, REPLACE), ONE request per line in JSON format
+        (see OrderEntryRequest)
+        - Server sends back Execution Reports (acknowledgements, rejects, fills, cancellations)
+        of the orders of the client, ONE report per line in JSON format
+*/
+
+extern crate serde_json;
+
+use gateway::OrderEntryGateway;
+use objects::{OrderEntryRequest, ExecutionReport, ExchangeError};
+use std::io;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+
+/**
+    An order entry server has the following properties:
+        - listener:
+            socket listening for connections of the clients
+*/
+pub struct OrderEntryServer {
+    listener: TcpListener,
+}
+
+impl OrderEntryServer {
+    /**
+        Constructor
+        @params
+            address: address the server listens on (MUST specify the PORT, port 0 lets the system choose one)
+        @return
+            - New order entry server (NOT accepting connections yet)
+            - Or the error if the address cannot be bound
+    */
+    pub fn bind(address: &str) -> io::Result<OrderEntryServer> {
+        Ok(OrderEntryServer {
+            listener: TcpListener::bind(address)?,
+        })
+    }
+
+    // Return address the server listens on
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /**
+        This function starts accepting connections (in a new thread), ONE thread per session
+
+        @params
+            gateway: the order entry gateway processing the requests of ALL sessions
+        @return
+            Handle of the thread accepting connections
+    */
+    pub fn start(self, gateway: &Arc<Mutex<OrderEntryGateway>>) -> thread::JoinHandle<()> {
+        let gateway = gateway.clone();
+        thread::spawn(move || {
+            for stream in self.listener.incoming() {
+                match stream {
+                    Ok(stream) => {
+                        let gateway = gateway.clone();
+                        thread::spawn(move || {
+                            if let Err(e) = run_session(stream, &gateway) {
+                                println!("Order entry session error: {}", e);
+                            }
+                        });
+                    }
+                    Err(e) => println!("Order entry connection error: {}", e),
+                }
+            }
+        })
+    }
+}
+
+/**
+    This function runs the session of a client until the client disconnects (or the connection is lost),
+    then closes the session in the gateway
+
+    @params
+        stream: connection of the client
+        gateway: the order entry gateway processing the requests
+*/
+fn run_session(stream: TcpStream, gateway: &Arc<Mutex<OrderEntryGateway>>) -> io::Result<()> {
+    let (report_sender, report_receiver) = mpsc::channel::<ExecutionReport>();
+    let session_id = gateway.lock().unwrap().open_session(&report_sender);
+
+    // Send Execution Reports of the session back to the client (in a separate thread, because reports
+    // about orders of the session can be caused by requests of other sessions)
+    let mut writer = stream.try_clone()?;
+    let writer_thread = thread::spawn(move || {
+        for report in report_receiver {
+            let line = serde_json::to_string(&report).unwrap();
+            if writeln!(writer, "{}", line).is_err() {
+                break;
+            }
+        }
+    });
+
+    // Process the requests of the client, ONE request per line
+    for line in BufReader::new(stream).lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(e) => {
+                // Connection lost (e.g, reset by the client): the session is closed below all the same
+                println!("Order entry session error: {}", e);
+                break;
+            }
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<OrderEntryRequest>(&line) {
+            Ok(request) => {
+                // Responses are sent to the client as Execution Reports
+                let _ = gateway.lock().unwrap().handle(session_id, &request);
+            }
+            Err(e) => {
+                let reason = ExchangeError::InvalidMessage(e.to_string());
+                let _ = report_sender.send(ExecutionReport::rejected("-1", '*', 0, &reason));
+            }
+        }
+    }
+
+    // Client disconnected: stop sending Execution Reports to the client
+    gateway.lock().unwrap().close_session(session_id);
+    drop(report_sender);
+    let _ = writer_thread.join();
+    Ok(())
+}
@@ -4,33 +4,113 @@ extern crate serde;
 extern crate serde_json;
 
 
-mod objects;
-mod matching_engine;
+pub mod objects;
+pub mod matching_engine;
+pub mod gateway;
+pub mod fix;
+pub mod config;
+pub mod market_data;
+pub mod client;
+pub mod clock;
 // NOTE: Run tests using 'cargo test -- --test-threads=1'
 #[cfg(test)]
 mod tests {
 	/**
-		* blackbox test
-		* snapshot/incremental updates should always be checked after each order operation
-		* check reverse side
-		* notation 1x2: 1 (price) x 2 (quantity)
-
-		scenarios:
-		1. buy 1x1, sell 2x1 -> no match
-		2. buy 1x1, sell 1x1 -> match
-		3. buy 1x1, buy 2x1, sell 3x1 -> no match, sell 2x1 -> match buy 2
-		4. buy 1x1, buy 2x1, sell 1x1 -> match buy 2
-		5. buy 1x1, buy 1x1, buy 3x1, buy 3x1, sell 3x2 -> match buy 3s
-		6. buy 1x1, buy 1x1, buy 3x1, buy 3x1, sell 1x4 -> match all buys
-		7. buy 1x1, del buy 1x1 -> market empty
-		8. buy 1x2, sell 1x1 -> match buy 1, del buy 1 -> market empty
-
-	**/
+        * blackbox test
+        * snapshot/incremental updates should always be checked after each order operation
+        * check reverse side
+        * notation 1x2: 1 (price) x 2 (quantity)
+
+        scenarios:
+        1. buy 1x1, sell 2x1 -> no match
+        2. buy 1x1, sell 1x1 -> match
+        3. buy 1x1, buy 2x1, sell 3x1 -> no match, sell 2x1 -> match buy 2
+        4. buy 1x1, buy 2x1, sell 1x1 -> match buy 2
+        5. buy 1x1, buy 1x1, buy 3x1, buy 3x1, sell 3x2 -> match buy 3s
+        6. buy 1x1, buy 1x1, buy 3x1, buy 3x1, sell 1x4 -> match all buys
+        7. buy 1x1, del buy 1x1 -> market empty
+        8. buy 1x2, sell 1x1 -> match buy 1, del buy 1 -> market empty
+        9. buy 1x2, buy 2x1, sell 1x3 -> trades reported for buy 2, then buy 1
+        10. sell 1x1, sell 5x1, market buy 3 -> match both sells, remaining quantity not rested
+        11. sell 1x1, IOC buy 1x2 -> match sell 1, remaining quantity cancelled
+        12. sell 1x1, sell 2x1, FOK buy 1x2 -> killed (book untouched), FOK buy 2x2 -> match both sells
+        13. invalid requests -> rejected with reason (book untouched), reported on the report channel
+        14. buy AAA 1x1, sell BBB 1x1 -> no match (different instruments), sell AAA 1x1 -> match buy AAA
+        15. client A sell 1x1, client B buy 1x2 (TCP order entry) -> fills reported to both clients,
+            client A cancels order of client B -> rejected, client B cancels its order -> cancelled,
+            order with an unknown owner -> cancel/replace of a session rejected, cancel of the console accepted
+        16. FIX message encoded then decoded -> same message, garbled message -> rejected
+        17. FIX client: logon, sell 1x1, buy 1x2 -> fills reported, cancel unknown order -> OrderCancelReject,
+            replace buy 1x3 -> replaced, resend request -> messages sent again, sequence gap -> resend request, logout
+        18. configuration file, then environment variables, then command-line flags -> last one wins,
+            unknown setting/invalid value -> rejected
+        19. sell 1x1, buy 1x2 (market data captured/recorded, NO socket) -> sell rested, sell matched, buy rested, trade
+        20. incremental/recovery feeds encoded (JSON, binary) then decoded -> same messages (non-ASCII symbol included), symbol over 255 bytes -> truncated on a character boundary, truncated/wrong template -> rejected
+        21. recovery feed of 50 levels split into fragments, received out of order/twice -> reassembled,
+            fragment lost then newer snapshot -> older snapshot dropped, newer one reassembled,
+            maximum payload larger than 65535 bytes -> clamped when splitting, rejected by the configuration,
+            fragment count larger than MAX_FRAGMENTS -> rejected
+        22. buy 10x1, buy 10x2, buy 9x1, buy 8x1 (depth of 2 levels), sell 10x3 -> MBP feed: new/change/delete
+            of price levels, depth feed only when one of the 2 best levels is modified
+        23. buy 10x1, buy 10x2, update qty of both (decrease, increase), sell 10x2, delete, update price
+            -> market-by-order feed: add/modify/execute/cancel with order ID, quantity and position in the queue
+        24. buy 10x1, buy 9x1, buy 10x2, sell 11x1, update qty of buy at 9, sell 10x3, delete sell at 11
+            -> BBO feed: quote only when the best bid or the best offer changes
+        25. trades at 12:00:10, 12:00:50, 12:01:05, 12:05:00 (1 and 5 minute bars) -> OHLCV bars completed by
+            the first trade of a later interval or by flush once their interval is over, 2 most recent bars kept,
+            bar of the current interval queried from the matching engine, bar of a quiet instrument published by flush_bars
+        26. feed handler: buy AAA 10x1, sell BBB 20x1, buy AAA 11x1, sell AAA 12x2, message 3 lost -> gap detected,
+            message 4 buffered, older recovery feed ignored, recovery feeds of AAA and BBB -> same books as without loss,
+            message lost on the default instrument (empty symbol) -> recovered by its recovery feed,
+            empty recovery feed sent while there is NO instrument -> NO book created
+        27. A/B lines: every incremental message sent on both groups, messages lost on ONE line (different ones
+            on each line), line B late -> books rebuilt without recovery, duplicates dropped
+        28. fault injection: same seed -> same faults, losses in bursts, duplicates, reordering within the window,
+            delay, faults configured per feed (2 feeds on the same multicast group -> faults of each feed kept),
+            held/delayed datagrams released by the simulated clock of the socket ONLY
+        29. replay server keeping the 4 most recent messages, 6 messages published -> range replayed (same messages),
+            aged out/NOT published yet/invalid range -> rejected, gap of the feed handler filled by a replay
+        30. simulated clock at 2026-01-02 12:00:00: buy 10x2, +1.5s sell 10x1, +60s sell 10x1 -> orders, trades,
+            Execution Reports and bars stamped with the simulated time, same events in every run,
+            FIX messages stamped (SendingTime) with the clock of the matching engine
+        31. buy 10x1, buy 10x2, buy 10x1 (same time stamp), increase qty of the first buy, decrease qty of the third buy,
+            sell 10x2 -> priority sequences assigned on entry/loss of priority, book/Ord/feeds ranked by sequence
+        32. sell 10x1, sell 11x1, sell 12x2, buy STOP at 11, buy STOP LIMIT 11 at 10, sell STOP at 5 -> waiting (NOT on
+            the feeds), buy 10x1 -> STOP LIMIT elected, then STOP elected (cascade), cancel sell STOP, 2 stops elected
+            by ONE trade -> elected in priority order, stop already reached -> elected at once, invalid stops -> rejected
+        33. sell ICEBERG 10x10 (display 3), sell 10x2 -> only the slice on the feeds, buy 10x4 -> slice exhausted and
+            replenished at the rear, FOK buy 10x8 -> trades through the reserve, decrease qty below the slice,
+            invalid display quantities -> rejected
+        34. sell HIDDEN 10x5, sell 10x2 -> hidden order NOT on the feeds, ranked after the displayed order, buy 10x3 ->
+            displayed order matched first, post-only buys: crossing -> rejected (hidden liquidity too) or repriced,
+            NOT crossing -> rests, replace to a crossing price -> rejected, invalid post-only/hidden orders -> rejected
+        35. self-trade prevention (sell 10x2 and buy 10x3 of the same account): none -> trade, cancel newest/oldest/both,
+            decrement and cancel -> orders cancelled/restated and reported, FOK covered only by its own account -> killed,
+            orders without account -> trade,
+            cancellation routed to the session owning the resting order, self_trade_prevention setting
+
+    **/
 	use super::objects::Order;
 	use super::objects::IncrementalMessage;
+	use super::objects::ExchangeError;
+	use super::objects::ExecutionReport;
+	use super::gateway::{OrderEntryGateway, OrderEntryServer, CONSOLE_SESSION};
+	use super::fix::{FixMessage, FixAcceptor, FixSession};
+	use super::config::Config;
+	use super::market_data::{CapturePublisher, FilePublisher, NullPublisher, MarketDataPublisher, UdpPublisher};
+	use super::market_data::{FeedEncoding, encode_incremental, decode_incremental, encode_recovery, decode_recovery};
+	use super::market_data::{SnapshotAssembler, MAX_FRAGMENTS, BarAggregator, FaultConfig, FaultInjector, FaultySocket, MAX_HOLD, encode_fragment};
+	use super::market_data::{ReplayBuffer, ReplayServer};
+	use super::client::{FeedHandler, FeedListener, LocalBook, ReplayClient};
+ 	use super::objects::SelfTradePrevention;
+	use super::objects::{MarketDataEvent, RecoveryFeed, SnapshotFragment, PriceLevel, Trade, Bar, OrderEntryRequest};
  	use super::matching_engine::MatchingEngine;
+	use super::clock::{Clock, SimulatedClock, utc_timestamp};
  	use std::sync::mpsc::channel;
- 	use std::{io, thread, time};
+ 	use std::thread;
+ 	use std::sync::{Arc, Mutex};
+ 	use std::net::{TcpStream, UdpSocket};
+ 	use std::io::{BufRead, BufReader, Write};
  	// case tests
  	#[test]
  	//#[ignore]
@@ -41,51 +121,48 @@ mod tests {
  		let mut match_eng = MatchingEngine::new(&tx);
 		
  		// create listener thread to check increment/snapshot
- 		let thread_2 = thread::spawn(move || {
+ 		thread::spawn(move || {
  			let mut id_1 = "".to_string();
  			loop {
 	 			let data = rx.try_recv();
-	 			match data {
-	 				Ok(v) => {
-	 					let val: IncrementalMessage = ::serde_json::from_str(v.as_str()).unwrap();
-	 					let affected_ords = val.get_orders();
-	 					// if seq num of incremental msg is 1, check buy order was inserted correctly
-	 					if val.get_num() == 1 {
-	 						id_1 = affected_ords[0].get_id();
-	 						assert_eq!(affected_ords[0].get_qty(), 1);
-	 						assert_eq!(affected_ords[0].get_price(), 1);
-	 					}
-	 					else if val.get_num() == 2 {
-	 						// check order inserted correctly
-	 						assert_eq!(affected_ords[0].get_qty(), 1);
-	 						assert_eq!(affected_ords[0].get_price(), 2);
-	 						// check previous order not affected (no match, see below) 
-	 						tx_2.send(id_1.clone());
-	 					}
-	 					else {
-	 						// error: only 2 increment msgs should be broadcast in this scenario
-	 						panic!("Error: incorrect number of increments received");
-	 					}
-
-	 				}
-	 				Err(r) => {},
+	 			if let Ok(v) = data {
+	 				let val: IncrementalMessage = ::serde_json::from_str(v.as_str()).unwrap();
+	 				let affected_ords = val.get_orders();
+	 				// if seq num of incremental msg is 1, check buy order was inserted correctly
+	 				if val.get_num() == 1 {
+	 					id_1 = affected_ords[0].get_id();
+	 					assert_eq!(affected_ords[0].get_qty(), 1);
+	 					assert_eq!(affected_ords[0].get_price(), 1);
+	 				}
+	 				else if val.get_num() == 2 {
+	 					// check order inserted correctly
+	 					assert_eq!(affected_ords[0].get_qty(), 1);
+	 					assert_eq!(affected_ords[0].get_price(), 2);
+	 					// check previous order not affected (no match, see below) 
+	 					tx_2.send(id_1.clone()).unwrap();
+	 				}
+	 				else {
+	 					// error: only 2 increment msgs should be broadcast in this scenario
+	 					panic!("Error: incorrect number of increments received");
+	 				}
+
 	 			}
 	 		}
  		});
 
- 		let mut buy_order = Order::new(1, 1, '1');
- 		let mut sell_order = Order::new(1, 2, '2');
- 		match_eng.insert(&buy_order);
- 		match_eng.insert(&sell_order);
+ 		let buy_order = Order::new(1, 1, '1');
+ 		let sell_order = Order::new(1, 2, '2');
+ 		match_eng.insert(&buy_order).unwrap();
+ 		match_eng.insert(&sell_order).unwrap();
  		
 		let id = rx_2.recv();
 		match id {
 			Ok(v) => {
 				// no match: check that previously inserted order was not affected
-				assert_eq!(match_eng.find_order_by_id(&v).get_qty(), 1);
+				assert_eq!(match_eng.find_order_by_id(&v).unwrap().get_qty(), 1);
 				
 			}
-			Err(r) => {println!("error");},
+			Err(_) => {println!("error");},
 		}
 		
 	}
@@ -98,47 +175,44 @@ mod tests {
  		let (tx_2, rx_2) = channel();
  		let mut match_eng = MatchingEngine::new(&tx);
  		let mut id_1 = "".to_string();
- 		let thread_2 = thread::spawn(move || {
+ 		thread::spawn(move || {
  			loop {
 	 			let data = rx.try_recv();
-	 			match data {
-	 				Ok(v) => {
-	 					let val: IncrementalMessage = ::serde_json::from_str(v.as_str()).unwrap();
-	 					let affected_ords = val.get_orders();
-	 					// check buy order was inserted correctly
-	 					if val.get_num() == 1 {
-	 						id_1 = affected_ords[0].get_id();
-	 						assert_eq!(affected_ords[0].get_qty(), 1);
-	 						assert_eq!(affected_ords[0].get_price(), 1);
-	 					}
-	 					else if val.get_num() == 2 {
-	 						// check order inserted was matched (see below)
-	 						assert_eq!(affected_ords.len(), 0);
-	 						tx_2.send(id_1.clone());
-	 					}
-	 					else {
-	 						panic!("Error: incorrect number of increments received");
-	 					}
-
-	 				}
-	 				Err(r) => {},
+	 			if let Ok(v) = data {
+	 				let val: IncrementalMessage = ::serde_json::from_str(v.as_str()).unwrap();
+	 				let affected_ords = val.get_orders();
+	 				// check buy order was inserted correctly
+	 				if val.get_num() == 1 {
+	 					id_1 = affected_ords[0].get_id();
+	 					assert_eq!(affected_ords[0].get_qty(), 1);
+	 					assert_eq!(affected_ords[0].get_price(), 1);
+	 				}
+	 				else if val.get_num() == 2 {
+	 					// check order inserted was matched (see below)
+	 					assert_eq!(affected_ords.len(), 0);
+	 					tx_2.send(id_1.clone()).unwrap();
+	 				}
+	 				else {
+	 					panic!("Error: incorrect number of increments received");
+	 				}
+
 	 			}
 	 		}
  		});
 
- 		let mut buy_order = Order::new(1, 1, '1');
- 		let mut sell_order = Order::new(1, 1, '2');
- 		match_eng.insert(&buy_order);
- 		match_eng.insert(&sell_order);
+ 		let buy_order = Order::new(1, 1, '1');
+ 		let sell_order = Order::new(1, 1, '2');
+ 		match_eng.insert(&buy_order).unwrap();
+ 		match_eng.insert(&sell_order).unwrap();
  	
  		let id = rx_2.recv();
 		match id {
 			Ok(v) => {
 				// match: check first order no longer exists
-				assert_eq!(match_eng.find_order_by_id(&v).get_side(), '*');
+				assert!(match_eng.find_order_by_id(&v).is_err());
 				
 			}
-			Err(r) => {println!("error");},
+			Err(_) => {println!("error");},
 		}
  	}
  	#[test]
@@ -150,56 +224,49 @@ mod tests {
  		let (tx_2, rx_2) = channel();
  		let mut match_eng = MatchingEngine::new(&tx);
 		
- 		let thread_2 = thread::spawn(move || {
- 			let mut id_1 = "".to_string(); 	
- 			let mut id_2 = "".to_string();
+ 		thread::spawn(move || {
  			let mut ids: Vec<String> = Vec::new();		
  			loop {
 	 			let data = rx.try_recv();
-	 			match data {
-	 				Ok(v) => {
-	 					let val: IncrementalMessage = ::serde_json::from_str(v.as_str()).unwrap();
-	 					let affected_ords = val.get_orders();
-	 					// if seq num of incremental msg is 1, check buy order was inserted correctly
-	 					if val.get_num() == 1 {
-	 						id_1 = affected_ords[0].get_id();
-	 						ids.push(id_1.clone());
-	 						assert_eq!(affected_ords[0].get_qty(), 1);
-	 						assert_eq!(affected_ords[0].get_price(), 1);
-	 					}
-	 					else if val.get_num() == 2 {
-	 						id_2 = affected_ords[0].get_id();
-	 						ids.push(id_2.clone());
-	 						assert_eq!(affected_ords[0].get_qty(), 1);
-	 						assert_eq!(affected_ords[0].get_price(), 2);
-	 						
-	 					}
-	 					else if val.get_num() == 3 {
-	 						assert_eq!(affected_ords[0].get_qty(), 1);
-	 						assert_eq!(affected_ords[0].get_price(), 3);
-
-	 						tx_2.send(ids.clone());
-	 					}
-	 					else if val.get_num() == 4 {
-	 						// check only buy order at 2 was matched (see below)
-	 						assert_eq!(affected_ords.len(), 0);
-	 					}
-	 					else {
-	 						panic!("Error: incorrect number of increments received");
-	 					}
-
-	 				}
-	 				Err(r) => {},
+	 			if let Ok(v) = data {
+	 				let val: IncrementalMessage = ::serde_json::from_str(v.as_str()).unwrap();
+	 				let affected_ords = val.get_orders();
+	 				// if seq num of incremental msg is 1, check buy order was inserted correctly
+	 				if val.get_num() == 1 {
+	 					ids.push(affected_ords[0].get_id());
+	 					assert_eq!(affected_ords[0].get_qty(), 1);
+	 					assert_eq!(affected_ords[0].get_price(), 1);
+	 				}
+	 				else if val.get_num() == 2 {
+	 					ids.push(affected_ords[0].get_id());
+	 					assert_eq!(affected_ords[0].get_qty(), 1);
+	 					assert_eq!(affected_ords[0].get_price(), 2);
+	 					
+	 				}
+	 				else if val.get_num() == 3 {
+	 					assert_eq!(affected_ords[0].get_qty(), 1);
+	 					assert_eq!(affected_ords[0].get_price(), 3);
+
+	 					tx_2.send(ids.clone()).unwrap();
+	 				}
+	 				else if val.get_num() == 4 {
+	 					// check only buy order at 2 was matched (see below)
+	 					assert_eq!(affected_ords.len(), 0);
+	 				}
+	 				else {
+	 					panic!("Error: incorrect number of increments received");
+	 				}
+
 	 			}
 	 		}
  		});
 
- 		let mut buy_order = Order::new(1, 1, '1');
- 		let mut buy_order_2 = Order::new(1, 2, '1');
- 		let mut sell_order = Order::new(1, 3, '2');
- 		match_eng.insert(&buy_order);
- 		match_eng.insert(&buy_order_2);
- 		match_eng.insert(&sell_order);
+ 		let buy_order = Order::new(1, 1, '1');
+ 		let buy_order_2 = Order::new(1, 2, '1');
+ 		let sell_order = Order::new(1, 3, '2');
+ 		match_eng.insert(&buy_order).unwrap();
+ 		match_eng.insert(&buy_order_2).unwrap();
+ 		match_eng.insert(&sell_order).unwrap();
  		
 		let id = rx_2.recv();
 		let mut id_1 = "".to_string();
@@ -208,18 +275,18 @@ mod tests {
 			Ok(v) => {
 				id_1 = v[0].clone();
 				id_2 = v[1].clone();
-				assert_eq!(match_eng.find_order_by_id(&v[0]).get_qty(), 1);
-				assert_eq!(match_eng.find_order_by_id(&v[1]).get_qty(), 1);
+				assert_eq!(match_eng.find_order_by_id(&v[0]).unwrap().get_qty(), 1);
+				assert_eq!(match_eng.find_order_by_id(&v[1]).unwrap().get_qty(), 1);
 			}
-			Err(r) => {println!("error");},
+			Err(_) => {println!("error");},
 		}
 
-		let mut sell_order_2 = Order::new(1, 2, '2');
-		match_eng.insert(&sell_order_2);
+		let sell_order_2 = Order::new(1, 2, '2');
+		match_eng.insert(&sell_order_2).unwrap();
 		// check buy order at 1 not affected
-		assert_eq!(match_eng.find_order_by_id(&id_1).get_qty(), 1);
+		assert_eq!(match_eng.find_order_by_id(&id_1).unwrap().get_qty(), 1);
 		// check match occured (assert buy at 2 no longer exists)
-		assert_eq!(match_eng.find_order_by_id(&id_2).get_side(), '*');
+		assert!(match_eng.find_order_by_id(&id_2).is_err());
 
  	}
 
@@ -231,59 +298,52 @@ mod tests {
  		let (tx_2, rx_2) = channel();
  		let mut match_eng = MatchingEngine::new(&tx);
 		
- 		let thread_2 = thread::spawn(move || {
- 			let mut id_1 = "".to_string(); 	
- 			let mut id_2 = "".to_string();
+ 		thread::spawn(move || {
  			let mut ids: Vec<String> = Vec::new();		
  			loop {
 	 			let data = rx.try_recv();
-	 			match data {
-	 				Ok(v) => {
-	 					let val: IncrementalMessage = ::serde_json::from_str(v.as_str()).unwrap();
-	 					let affected_ords = val.get_orders();
-	 					// if seq num of incremental msg is 1, check buy order was inserted correctly
-	 					if val.get_num() == 1 {
-	 						id_1 = affected_ords[0].get_id();
-	 						ids.push(id_1.clone());
-	 						assert_eq!(affected_ords[0].get_qty(), 1);
-	 						assert_eq!(affected_ords[0].get_price(), 1);
-	 					}
-	 					else if val.get_num() == 2 {
-	 						id_2 = affected_ords[0].get_id();
-	 						ids.push(id_2.clone());
-	 						assert_eq!(affected_ords[0].get_qty(), 1);
-	 						assert_eq!(affected_ords[0].get_price(), 2);
-	 						
-	 					}
-	 					else if val.get_num() == 3 {
-	 						// check order inserted was matched correctly (see below)
-	 						assert_eq!(affected_ords.len(), 0);
-	 						tx_2.send(ids.clone());
-	 					}
-	 					else {
-	 						panic!("Error: incorrect number of increments received");
-	 					}
-
-	 				}
-	 				Err(r) => {},
+	 			if let Ok(v) = data {
+	 				let val: IncrementalMessage = ::serde_json::from_str(v.as_str()).unwrap();
+	 				let affected_ords = val.get_orders();
+	 				// if seq num of incremental msg is 1, check buy order was inserted correctly
+	 				if val.get_num() == 1 {
+	 					ids.push(affected_ords[0].get_id());
+	 					assert_eq!(affected_ords[0].get_qty(), 1);
+	 					assert_eq!(affected_ords[0].get_price(), 1);
+	 				}
+	 				else if val.get_num() == 2 {
+	 					ids.push(affected_ords[0].get_id());
+	 					assert_eq!(affected_ords[0].get_qty(), 1);
+	 					assert_eq!(affected_ords[0].get_price(), 2);
+	 					
+	 				}
+	 				else if val.get_num() == 3 {
+	 					// check order inserted was matched correctly (see below)
+	 					assert_eq!(affected_ords.len(), 0);
+	 					tx_2.send(ids.clone()).unwrap();
+	 				}
+	 				else {
+	 					panic!("Error: incorrect number of increments received");
+	 				}
+
 	 			}
 	 		}
  		});
 
- 		let mut buy_order = Order::new(1, 1, '1');
- 		let mut buy_order_2 = Order::new(1, 2, '1');
- 		let mut sell_order = Order::new(1, 1, '2');
- 		match_eng.insert(&buy_order);
- 		match_eng.insert(&buy_order_2);
- 		match_eng.insert(&sell_order);
+ 		let buy_order = Order::new(1, 1, '1');
+ 		let buy_order_2 = Order::new(1, 2, '1');
+ 		let sell_order = Order::new(1, 1, '2');
+ 		match_eng.insert(&buy_order).unwrap();
+ 		match_eng.insert(&buy_order_2).unwrap();
+ 		match_eng.insert(&sell_order).unwrap();
  		
 		let id = rx_2.recv();
 		match id {
 			Ok(v) => {
-				assert_eq!(match_eng.find_order_by_id(&v[0]).get_qty(), 1);
-				assert_eq!(match_eng.find_order_by_id(&v[1]).get_side(), '*');
+				assert_eq!(match_eng.find_order_by_id(&v[0]).unwrap().get_qty(), 1);
+				assert!(match_eng.find_order_by_id(&v[1]).is_err());
 			}
-			Err(r) => {println!("error");},
+			Err(_) => {println!("error");},
 		}
  	}
 
@@ -295,85 +355,63 @@ mod tests {
  		let (tx_2, rx_2) = channel();
  		let mut match_eng = MatchingEngine::new(&tx);
 		
- 		let thread_2 = thread::spawn(move || {
- 			let mut id_1 = "".to_string(); 	
- 			let mut id_2 = "".to_string();
- 			let mut id_3 = "".to_string();
- 			let mut id_4 = "".to_string();
+ 		thread::spawn(move || {
  			let mut ids: Vec<String> = Vec::new();		
  			loop {
 	 			let data = rx.try_recv();
-	 			match data {
-	 				Ok(v) => {
-	 					let val: IncrementalMessage = ::serde_json::from_str(v.as_str()).unwrap();
-	 					let affected_ords = val.get_orders();
-	 					// if seq num of incremental msg is 1, check buy order was inserted correctly
-	 					if val.get_num() == 1 {
-	 						id_1 = affected_ords[0].get_id();
-	 						ids.push(id_1.clone());
-	 						assert_eq!(affected_ords[0].get_qty(), 1);
-	 						assert_eq!(affected_ords[0].get_price(), 1);
-	 					}
-	 					else if val.get_num() == 2 {
-	 						id_2 = affected_ords[0].get_id();
-	 						ids.push(id_2.clone());
-	 						assert_eq!(affected_ords[0].get_qty(), 1);
-	 						assert_eq!(affected_ords[0].get_price(), 1);
-	 						
-	 					}
-	 					else if val.get_num() == 3 {
-	 						id_3 = affected_ords[0].get_id();
-	 						ids.push(id_3.clone());
-	 						assert_eq!(affected_ords[0].get_qty(), 1);
-	 						assert_eq!(affected_ords[0].get_price(), 3);
-	 					}
-	 					else if val.get_num() == 4 {
-	 						id_4 = affected_ords[0].get_id();
-	 						ids.push(id_4.clone());
-	 						assert_eq!(affected_ords[0].get_qty(), 1);
-	 						assert_eq!(affected_ords[0].get_price(), 3);
-	 					}
-	 					else if val.get_num() == 5 {
-	 						// 1 more buy at 3 should be remaining to be matched
-	 						assert_eq!(affected_ords.len(), 1);
-	 					}
-	 					else if val.get_num() == 6 {
-	 						// check order inserted was matched correctly (see below)
-	 						assert_eq!(affected_ords.len(), 0);
-	 						tx_2.send(ids.clone());
-	 					}
-	 					else {
-	 						panic!("Error: incorrect number of increments received");
-	 					}
-
-	 				}
-	 				Err(r) => {},
+	 			if let Ok(v) = data {
+	 				let val: IncrementalMessage = ::serde_json::from_str(v.as_str()).unwrap();
+	 				let affected_ords = val.get_orders();
+	 				// if seq num of incremental msg is 1, check buy order was inserted correctly
+	 				if val.get_num() == 1 || val.get_num() == 2 {
+	 					ids.push(affected_ords[0].get_id());
+	 					assert_eq!(affected_ords[0].get_qty(), 1);
+	 					assert_eq!(affected_ords[0].get_price(), 1);
+	 				}
+	 				else if val.get_num() == 3 || val.get_num() == 4 {
+	 					ids.push(affected_ords[0].get_id());
+	 					assert_eq!(affected_ords[0].get_qty(), 1);
+	 					assert_eq!(affected_ords[0].get_price(), 3);
+	 				}
+	 				else if val.get_num() == 5 {
+	 					// 1 more buy at 3 should be remaining to be matched
+	 					assert_eq!(affected_ords.len(), 1);
+	 				}
+	 				else if val.get_num() == 6 {
+	 					// check order inserted was matched correctly (see below)
+	 					assert_eq!(affected_ords.len(), 0);
+	 					tx_2.send(ids.clone()).unwrap();
+	 				}
+	 				else {
+	 					panic!("Error: incorrect number of increments received");
+	 				}
+
 	 			}
 	 		}
  		});
 
- 		let mut buy_order = Order::new(1, 1, '1');
- 		let mut buy_order_2 = Order::new(1, 1, '1');
- 		let mut buy_order_3 = Order::new(1, 3, '1');
- 		let mut buy_order_4 = Order::new(1, 3, '1');
- 		let mut sell_order = Order::new(2, 3, '2');
- 		match_eng.insert(&buy_order);
- 		match_eng.insert(&buy_order_2);
- 		match_eng.insert(&buy_order_3);
- 		match_eng.insert(&buy_order_4);
- 		match_eng.insert(&sell_order);
+ 		let buy_order = Order::new(1, 1, '1');
+ 		let buy_order_2 = Order::new(1, 1, '1');
+ 		let buy_order_3 = Order::new(1, 3, '1');
+ 		let buy_order_4 = Order::new(1, 3, '1');
+ 		let sell_order = Order::new(2, 3, '2');
+ 		match_eng.insert(&buy_order).unwrap();
+ 		match_eng.insert(&buy_order_2).unwrap();
+ 		match_eng.insert(&buy_order_3).unwrap();
+ 		match_eng.insert(&buy_order_4).unwrap();
+ 		match_eng.insert(&sell_order).unwrap();
  		
 		let id = rx_2.recv();
 		match id {
 			Ok(v) => {
 				// check buys at 1 not affected
-				assert_eq!(match_eng.find_order_by_id(&v[0]).get_qty(), 1);
-				assert_eq!(match_eng.find_order_by_id(&v[1]).get_qty(), 1);
+				assert_eq!(match_eng.find_order_by_id(&v[0]).unwrap().get_qty(), 1);
+				assert_eq!(match_eng.find_order_by_id(&v[1]).unwrap().get_qty(), 1);
 				// check buys at 3 got matched (no longer exist)
-				assert_eq!(match_eng.find_order_by_id(&v[2]).get_side(), '*');
-				assert_eq!(match_eng.find_order_by_id(&v[3]).get_side(), '*');
+				assert!(match_eng.find_order_by_id(&v[2]).is_err());
+				assert!(match_eng.find_order_by_id(&v[3]).is_err());
 			}
-			Err(r) => {println!("error");},
+			Err(_) => {println!("error");},
 		}
  	}
 
@@ -385,95 +423,73 @@ mod tests {
  		let (tx_2, rx_2) = channel();
  		let mut match_eng = MatchingEngine::new(&tx);
 		
- 		let thread_2 = thread::spawn(move || {
- 			let mut id_1 = "".to_string(); 	
- 			let mut id_2 = "".to_string();
- 			let mut id_3 = "".to_string();
- 			let mut id_4 = "".to_string();
+ 		thread::spawn(move || {
  			let mut ids: Vec<String> = Vec::new();		
  			loop {
 	 			let data = rx.try_recv();
-	 			match data {
-	 				Ok(v) => {
-	 					let val: IncrementalMessage = ::serde_json::from_str(v.as_str()).unwrap();
-	 					let affected_ords = val.get_orders();
-	 					// if seq num of incremental msg is 1, check buy order was inserted correctly
-	 					if val.get_num() == 1 {
-	 						id_1 = affected_ords[0].get_id();
-	 						ids.push(id_1.clone());
-	 						assert_eq!(affected_ords[0].get_qty(), 1);
-	 						assert_eq!(affected_ords[0].get_price(), 1);
-	 					}
-	 					else if val.get_num() == 2 {
-	 						id_2 = affected_ords[0].get_id();
-	 						ids.push(id_2.clone());
-	 						assert_eq!(affected_ords[0].get_qty(), 1);
-	 						assert_eq!(affected_ords[0].get_price(), 1);
-	 						
-	 					}
-	 					else if val.get_num() == 3 {
-	 						id_3 = affected_ords[0].get_id();
-	 						ids.push(id_3.clone());
-	 						assert_eq!(affected_ords[0].get_qty(), 1);
-	 						assert_eq!(affected_ords[0].get_price(), 3);
-	 					}
-	 					else if val.get_num() == 4 {
-	 						id_4 = affected_ords[0].get_id();
-	 						ids.push(id_4.clone());
-	 						assert_eq!(affected_ords[0].get_qty(), 1);
-	 						assert_eq!(affected_ords[0].get_price(), 3);
-	 					}
-	 					else if val.get_num() == 5 {
-	 						// 1 more buy at 3 to be matched
-	 						assert_eq!(affected_ords.len(), 1);
-	 						assert_eq!(affected_ords[0].get_price(), 3);
-	 					}
-	 					else if val.get_num() == 6 {
-	 						// all buys at 3 matched
-	 						assert_eq!(affected_ords.len(), 0);
-	 					}
-	 					else if val.get_num() == 7 {
-	 						// 1 more buy at 1 to be matched
-	 						assert_eq!(affected_ords.len(), 1);
-	 						assert_eq!(affected_ords[0].get_price(), 1);
-	 					}
-	 					else if val.get_num() == 8 {
-	 						// match completed
-	 						// check order inserted was matched correctly (see below)
-	 						assert_eq!(affected_ords.len(), 0);
-	 						tx_2.send(ids.clone());
-	 					}
-	 					else {
-	 						panic!("Error: incorrect number of increments received");
-	 					}
-
-	 				}
-	 				Err(r) => {},
+	 			if let Ok(v) = data {
+	 				let val: IncrementalMessage = ::serde_json::from_str(v.as_str()).unwrap();
+	 				let affected_ords = val.get_orders();
+	 				// if seq num of incremental msg is 1, check buy order was inserted correctly
+	 				if val.get_num() == 1 || val.get_num() == 2 {
+	 					ids.push(affected_ords[0].get_id());
+	 					assert_eq!(affected_ords[0].get_qty(), 1);
+	 					assert_eq!(affected_ords[0].get_price(), 1);
+	 				}
+	 				else if val.get_num() == 3 || val.get_num() == 4 {
+	 					ids.push(affected_ords[0].get_id());
+	 					assert_eq!(affected_ords[0].get_qty(), 1);
+	 					assert_eq!(affected_ords[0].get_price(), 3);
+	 				}
+	 				else if val.get_num() == 5 {
+	 					// 1 more buy at 3 to be matched
+	 					assert_eq!(affected_ords.len(), 1);
+	 					assert_eq!(affected_ords[0].get_price(), 3);
+	 				}
+	 				else if val.get_num() == 6 {
+	 					// all buys at 3 matched
+	 					assert_eq!(affected_ords.len(), 0);
+	 				}
+	 				else if val.get_num() == 7 {
+	 					// 1 more buy at 1 to be matched
+	 					assert_eq!(affected_ords.len(), 1);
+	 					assert_eq!(affected_ords[0].get_price(), 1);
+	 				}
+	 				else if val.get_num() == 8 {
+	 					// match completed
+	 					// check order inserted was matched correctly (see below)
+	 					assert_eq!(affected_ords.len(), 0);
+	 					tx_2.send(ids.clone()).unwrap();
+	 				}
+	 				else {
+	 					panic!("Error: incorrect number of increments received");
+	 				}
+
 	 			}
 	 		}
  		});
 
- 		let mut buy_order = Order::new(1, 1, '1');
- 		let mut buy_order_2 = Order::new(1, 1, '1');
- 		let mut buy_order_3 = Order::new(1, 3, '1');
- 		let mut buy_order_4 = Order::new(1, 3, '1');
- 		let mut sell_order = Order::new(4, 1, '2');
- 		match_eng.insert(&buy_order);
- 		match_eng.insert(&buy_order_2);
- 		match_eng.insert(&buy_order_3);
- 		match_eng.insert(&buy_order_4);
- 		match_eng.insert(&sell_order);
+ 		let buy_order = Order::new(1, 1, '1');
+ 		let buy_order_2 = Order::new(1, 1, '1');
+ 		let buy_order_3 = Order::new(1, 3, '1');
+ 		let buy_order_4 = Order::new(1, 3, '1');
+ 		let sell_order = Order::new(4, 1, '2');
+ 		match_eng.insert(&buy_order).unwrap();
+ 		match_eng.insert(&buy_order_2).unwrap();
+ 		match_eng.insert(&buy_order_3).unwrap();
+ 		match_eng.insert(&buy_order_4).unwrap();
+ 		match_eng.insert(&sell_order).unwrap();
  		
 		let id = rx_2.recv();
 		match id {
 			Ok(v) => {
 				// check all buys got matched (no longer exist)
-				assert_eq!(match_eng.find_order_by_id(&v[0]).get_side(), '*');
-				assert_eq!(match_eng.find_order_by_id(&v[1]).get_side(), '*');
-				assert_eq!(match_eng.find_order_by_id(&v[2]).get_side(), '*');
-				assert_eq!(match_eng.find_order_by_id(&v[3]).get_side(), '*');
+				assert!(match_eng.find_order_by_id(&v[0]).is_err());
+				assert!(match_eng.find_order_by_id(&v[1]).is_err());
+				assert!(match_eng.find_order_by_id(&v[2]).is_err());
+				assert!(match_eng.find_order_by_id(&v[3]).is_err());
 			}
-			Err(r) => {println!("error");},
+			Err(_) => {println!("error");},
 		}
  	}
 
@@ -485,44 +501,41 @@ mod tests {
  		let (tx_2, rx_2) = channel();
  		let mut match_eng = MatchingEngine::new(&tx);
  		let mut id_1 = "".to_string();
- 		let thread_2 = thread::spawn(move || {
+ 		thread::spawn(move || {
  			loop {
 	 			let data = rx.try_recv();
-	 			match data {
-	 				Ok(v) => {
-	 					let val: IncrementalMessage = ::serde_json::from_str(v.as_str()).unwrap();
-	 					let affected_ords = val.get_orders();
-	 					if val.get_num() == 1 {
-	 						id_1 = affected_ords[0].get_id();
-	 						assert_eq!(affected_ords[0].get_qty(), 1);
-	 						assert_eq!(affected_ords[0].get_price(), 1);
-	 					}
-	 					else if val.get_num() == 2 {
-	 						assert_eq!(affected_ords.len(), 0);
-	 						tx_2.send(id_1.clone());
-	 					}
-	 					else {
-	 						panic!("Error: incorrect number of increments received");
-	 					}
-
-	 				}
-	 				Err(r) => {},
+	 			if let Ok(v) = data {
+	 				let val: IncrementalMessage = ::serde_json::from_str(v.as_str()).unwrap();
+	 				let affected_ords = val.get_orders();
+	 				if val.get_num() == 1 {
+	 					id_1 = affected_ords[0].get_id();
+	 					assert_eq!(affected_ords[0].get_qty(), 1);
+	 					assert_eq!(affected_ords[0].get_price(), 1);
+	 				}
+	 				else if val.get_num() == 2 {
+	 					assert_eq!(affected_ords.len(), 0);
+	 					tx_2.send(id_1.clone()).unwrap();
+	 				}
+	 				else {
+	 					panic!("Error: incorrect number of increments received");
+	 				}
+
 	 			}
 	 		}
  		});
 
- 		let mut buy_order = Order::new(1, 1, '1');
- 		let id = match_eng.insert(&buy_order).get_id();
- 		match_eng.delete(&id);
+ 		let buy_order = Order::new(1, 1, '1');
+ 		let id = match_eng.insert(&buy_order).unwrap().0.get_id();
+ 		match_eng.delete(&id).unwrap();
  	
  		let id = rx_2.recv();
 		match id {
 			Ok(v) => {
 				// check order was deleted
-				assert_eq!(match_eng.find_order_by_id(&v).get_side(), '*');
+				assert!(match_eng.find_order_by_id(&v).is_err());
 				
 			}
-			Err(r) => {println!("error");},
+			Err(_) => {println!("error");},
 		}
 
  		
@@ -536,72 +549,419 @@ mod tests {
  		let (tx_2, rx_2) = channel();
  		let mut match_eng = MatchingEngine::new(&tx);
  		let mut id_1 = "".to_string();
- 		let thread_2 = thread::spawn(move || {
+ 		thread::spawn(move || {
  			loop {
 	 			let data = rx.try_recv();
-	 			match data {
-	 				Ok(v) => {
-	 					let val: IncrementalMessage = ::serde_json::from_str(v.as_str()).unwrap();
-	 					let affected_ords = val.get_orders();
-	 					if val.get_num() == 1 {
-	 						id_1 = affected_ords[0].get_id();
-	 						assert_eq!(affected_ords[0].get_qty(), 2);
-	 						assert_eq!(affected_ords[0].get_price(), 1);
-	 					}
-	 					else if val.get_num() == 2 {
-	 						// check that remaining quantity of buy order is 1 after match
-	 						assert_eq!(affected_ords[0].get_qty(), 1);
-	 					}
-	 					else if val.get_num() == 3 {
-	 						assert_eq!(affected_ords.len(), 0);
-	 						tx_2.send(id_1.clone());
-	 					}
-	 					else {
-	 						panic!("Error: incorrect number of increments received");
-	 					}
-
-	 				}
-	 				Err(r) => {},
+	 			if let Ok(v) = data {
+	 				let val: IncrementalMessage = ::serde_json::from_str(v.as_str()).unwrap();
+	 				let affected_ords = val.get_orders();
+	 				if val.get_num() == 1 {
+	 					id_1 = affected_ords[0].get_id();
+	 					assert_eq!(affected_ords[0].get_qty(), 2);
+	 					assert_eq!(affected_ords[0].get_price(), 1);
+	 				}
+	 				else if val.get_num() == 2 {
+	 					// check that remaining quantity of buy order is 1 after match
+	 					assert_eq!(affected_ords[0].get_qty(), 1);
+	 				}
+	 				else if val.get_num() == 3 {
+	 					assert_eq!(affected_ords.len(), 0);
+	 					tx_2.send(id_1.clone()).unwrap();
+	 				}
+	 				else {
+	 					panic!("Error: incorrect number of increments received");
+	 				}
+
 	 			}
 	 		}
  		});
 
- 		let mut buy_order = Order::new(2, 1, '1');
- 		let mut sell_order = Order::new(1, 1, '2');
- 		let id = match_eng.insert(&buy_order).get_id();
- 		match_eng.insert(&sell_order);
- 		match_eng.delete(&id);
+ 		let buy_order = Order::new(2, 1, '1');
+ 		let sell_order = Order::new(1, 1, '2');
+ 		let id = match_eng.insert(&buy_order).unwrap().0.get_id();
+ 		match_eng.insert(&sell_order).unwrap();
+ 		match_eng.delete(&id).unwrap();
  	
  		let id = rx_2.recv();
 		match id {
 			Ok(v) => {
 				// check buy order was deleted
-				assert_eq!(match_eng.find_order_by_id(&v).get_side(), '*');
+				assert!(match_eng.find_order_by_id(&v).is_err());
 				
 			}
-			Err(r) => {println!("error");},
+			Err(_) => {println!("error");},
 		}
  	}
+ 	#[test]
+ 	fn test_trade_report() {
+ 		// buy 2 at 1, buy 1 at 2, sell 3 at 1 --> trade with buy at 2, then trade with buy at 1
+ 		let (tx, _rx) = channel();
+ 		let mut match_eng = MatchingEngine::new(&tx);
+
+ 		let id_1 = match_eng.insert(&Order::new(2, 1, '1')).unwrap().0.get_id();
+ 		let id_2 = match_eng.insert(&Order::new(1, 2, '1')).unwrap().0.get_id();
+ 		let (sell_order, trades) = match_eng.insert(&Order::new(3, 1, '2')).unwrap();
+
+ 		// sell order fully matched
+ 		assert_eq!(sell_order.get_qty(), 0);
+ 		assert_eq!(trades.len(), 2);
+ 		// best price (buy at 2) traded first, at the price of the resting order
+ 		assert_eq!(trades[0].get_trade_id(), "0");
+ 		assert_eq!(trades[0].get_aggressor_id(), sell_order.get_id());
+ 		assert_eq!(trades[0].get_passive_id(), id_2);
+ 		assert_eq!(trades[0].get_price(), 2);
+ 		assert_eq!(trades[0].get_qty(), 1);
+ 		assert_eq!(trades[0].get_aggressor_side(), '2');
+ 		// then buy at 1
+ 		assert_eq!(trades[1].get_trade_id(), "1");
+ 		assert_eq!(trades[1].get_passive_id(), id_1);
+ 		assert_eq!(trades[1].get_price(), 1);
+ 		assert_eq!(trades[1].get_qty(), 2);
+ 	}
+
+ 	#[test]
+ 	fn test_market_order() {
+ 		// sell 1 at 1, sell 1 at 5, market buy 3 --> match both sells, remaining 1 not rested
+ 		let (tx, _rx) = channel();
+ 		let mut match_eng = MatchingEngine::new(&tx);
+
+ 		let id_1 = match_eng.insert(&Order::new(1, 1, '2')).unwrap().0.get_id();
+ 		let id_2 = match_eng.insert(&Order::new(1, 5, '2')).unwrap().0.get_id();
+ 		let mut market_order = Order::new(3, 0, '1');
+ 		market_order.set_ord_type('1');
+ 		let (buy_order, trades) = match_eng.insert(&market_order).unwrap();
+
+ 		assert_eq!(trades.len(), 2);
+ 		assert_eq!(trades[0].get_price(), 1);
+ 		assert_eq!(trades[1].get_price(), 5);
+ 		assert_eq!(buy_order.get_qty(), 1);
+ 		// both sells matched, market order not rested
+ 		assert!(match_eng.find_order_by_id(&id_1).is_err());
+ 		assert!(match_eng.find_order_by_id(&id_2).is_err());
+ 		assert!(match_eng.find_order_by_id(&buy_order.get_id()).is_err());
+ 	}
+
+ 	#[test]
+ 	fn test_immediate_or_cancel() {
+ 		// sell 1 at 1, IOC buy 2 at 1 --> match sell, remaining 1 cancelled
+ 		let (tx, _rx) = channel();
+ 		let mut match_eng = MatchingEngine::new(&tx);
+
+ 		match_eng.insert(&Order::new(1, 1, '2')).unwrap();
+ 		let mut ioc_order = Order::new(2, 1, '1');
+ 		ioc_order.set_time_in_force('3');
+ 		let (buy_order, trades) = match_eng.insert(&ioc_order).unwrap();
+
+ 		assert_eq!(trades.len(), 1);
+ 		assert_eq!(buy_order.get_qty(), 1);
+ 		assert!(match_eng.find_order_by_id(&buy_order.get_id()).is_err());
+ 	}
+
+ 	#[test]
+ 	fn test_fill_or_kill() {
+ 		// sell 1 at 1, sell 1 at 2, FOK buy 2 at 1 --> killed, book untouched
+ 		// FOK buy 2 at 2 --> match both sells
+ 		let (tx, _rx) = channel();
+ 		let mut match_eng = MatchingEngine::new(&tx);
+
+ 		let id_1 = match_eng.insert(&Order::new(1, 1, '2')).unwrap().0.get_id();
+ 		let id_2 = match_eng.insert(&Order::new(1, 2, '2')).unwrap().0.get_id();
+ 		let mut fok_order = Order::new(2, 1, '1');
+ 		fok_order.set_time_in_force('4');
+ 		let (buy_order, trades) = match_eng.insert(&fok_order).unwrap();
+
+ 		assert_eq!(trades.len(), 0);
+ 		assert_eq!(buy_order.get_qty(), 2);
+ 		assert_eq!(match_eng.find_order_by_id(&id_1).unwrap().get_qty(), 1);
+ 		assert_eq!(match_eng.find_order_by_id(&id_2).unwrap().get_qty(), 1);
+ 		assert!(match_eng.find_order_by_id(&buy_order.get_id()).is_err());
+
+ 		fok_order.set_price(2);
+ 		let (buy_order, trades) = match_eng.insert(&fok_order).unwrap();
+ 		assert_eq!(trades.len(), 2);
+ 		assert_eq!(buy_order.get_qty(), 0);
+ 		assert!(match_eng.find_order_by_id(&id_1).is_err());
+ 		assert!(match_eng.find_order_by_id(&id_2).is_err());
+ 	}
+
+ 	#[test]
+ 	fn test_rejects() {
+ 		// unknown order ID, invalid side, non-positive quantity/price, price off tick --> rejected
+ 		let (tx, _rx) = channel();
+ 		let (report_tx, report_rx) = channel();
+ 		let mut match_eng = MatchingEngine::new(&tx);
+ 		match_eng.set_report_channel(&report_tx);
+ 		match_eng.set_tick_size(5);
+
+ 		let id = match_eng.insert(&Order::new(1, 10, '1')).unwrap().0.get_id();
+ 		// new order acknowledged
+ 		assert_eq!(report_rx.try_recv().unwrap().get_exec_type(), '0');
+
+ 		assert_eq!(match_eng.delete("100").unwrap_err(), ExchangeError::UnknownOrder("100".to_string()));
+ 		assert_eq!(match_eng.update("100", &Order::new(1, 10, '*')).unwrap_err(), ExchangeError::UnknownOrder("100".to_string()));
+ 		assert_eq!(match_eng.insert(&Order::new(1, 10, '3')).unwrap_err(), ExchangeError::InvalidSide('3'));
+ 		assert_eq!(match_eng.insert(&Order::new(0, 10, '1')).unwrap_err(), ExchangeError::NonPositiveQuantity(0));
+ 		assert_eq!(match_eng.insert(&Order::new(1, -5, '2')).unwrap_err(), ExchangeError::NonPositivePrice(-5));
+ 		assert_eq!(match_eng.insert(&Order::new(1, 12, '2')).unwrap_err(), ExchangeError::PriceOffTick(12));
+ 		assert_eq!(match_eng.update(&id, &Order::new(-1, 10, '*')).unwrap_err(), ExchangeError::NonPositiveQuantity(-1));
+
+ 		// every reject is reported
+ 		let reasons: Vec<Option<ExchangeError>> = report_rx.try_iter().map(|report| {
+ 			assert_eq!(report.get_exec_type(), '8');
+ 			report.get_reject_reason()
+ 		}).collect();
+ 		assert_eq!(reasons.len(), 7);
+ 		assert_eq!(reasons[0], Some(ExchangeError::UnknownOrder("100".to_string())));
+ 		assert_eq!(reasons[6], Some(ExchangeError::NonPositiveQuantity(-1)));
+
+ 		// existing order not affected
+ 		assert_eq!(match_eng.find_order_by_id(&id).unwrap().get_qty(), 1);
+ 		assert_eq!(match_eng.find_order_by_id(&id).unwrap().get_price(), 10);
+ 	}
+
+ 	#[test]
+ 	fn test_execution_reports() {
+ 		// buy 2 at 1, sell 1 at 1 --> both orders acknowledged, trade reported to both orders
+ 		let (tx, _rx) = channel();
+ 		let (report_tx, report_rx) = channel();
+ 		let mut match_eng = MatchingEngine::new(&tx);
+ 		match_eng.set_report_channel(&report_tx);
+
+ 		let buy_id = match_eng.insert(&Order::new(2, 1, '1')).unwrap().0.get_id();
+ 		let sell_id = match_eng.insert(&Order::new(1, 1, '2')).unwrap().0.get_id();
+ 		match_eng.delete(&buy_id).unwrap();
+
+ 		let reports: Vec<_> = report_rx.try_iter().collect();
+ 		assert_eq!(reports.len(), 5);
+ 		assert_eq!((reports[0].get_order_id(), reports[0].get_exec_type()), (buy_id.clone(), '0'));
+ 		assert_eq!((reports[1].get_order_id(), reports[1].get_exec_type()), (sell_id.clone(), '0'));
+ 		// trade reported to aggressor (fully matched) then to resting order (1 remaining)
+ 		assert_eq!((reports[2].get_order_id(), reports[2].get_exec_type()), (sell_id, 'F'));
+ 		assert_eq!(reports[2].get_leaves_qty(), 0);
+ 		assert_eq!((reports[3].get_order_id(), reports[3].get_exec_type()), (buy_id.clone(), 'F'));
+ 		assert_eq!(reports[3].get_leaves_qty(), 1);
+ 		assert_eq!(reports[3].get_last_qty(), 1);
+ 		assert_eq!(reports[3].get_last_px(), 1);
+ 		// cancellation of the remaining buy order
+ 		assert_eq!((reports[4].get_order_id(), reports[4].get_exec_type()), (buy_id, '4'));
+ 	}
+
+ 	#[test]
+ 	fn test_multiple_instruments() {
+ 		// buy 1 AAA at 1, sell 1 BBB at 1 --> no match
+ 		// sell 1 AAA at 1 --> match buy AAA at 1
+ 		let (tx, rx) = channel();
+ 		let mut match_eng = MatchingEngine::new(&tx);
+
+ 		let mut buy_order = Order::new(1, 1, '1');
+ 		buy_order.set_symbol("AAA");
+ 		let mut sell_order = Order::new(1, 1, '2');
+ 		sell_order.set_symbol("BBB");
+ 		let buy_id = match_eng.insert(&buy_order).unwrap().0.get_id();
+ 		let (sell_order_bbb, trades) = match_eng.insert(&sell_order).unwrap();
+ 		assert_eq!(trades.len(), 0);
+ 		assert_eq!(sell_order_bbb.get_symbol(), "BBB");
+
+ 		sell_order.set_symbol("AAA");
+ 		let (_, trades) = match_eng.insert(&sell_order).unwrap();
+ 		assert_eq!(trades.len(), 1);
+ 		assert_eq!(trades[0].get_symbol(), "AAA");
+ 		assert_eq!(trades[0].get_passive_id(), buy_id);
+ 		// sell BBB not affected
+ 		assert_eq!(match_eng.find_order_by_id(&sell_order_bbb.get_id()).unwrap().get_qty(), 1);
+
+ 		// incremental messages carry the symbol of the instrument affected
+ 		let symbols: Vec<String> = rx.try_iter().map(|v: String| {
+ 			let val: IncrementalMessage = ::serde_json::from_str(v.as_str()).unwrap();
+ 			val.get_symbol()
+ 		}).collect();
+ 		assert_eq!(symbols, vec!["AAA".to_string(), "BBB".to_string(), "AAA".to_string()]);
+ 	}
+
+ 	#[test]
+ 	fn test_order_entry_gateway() {
+ 		// client A: sell 1 at 1, client B: buy 2 at 1 --> match, fills reported to both clients
+ 		// client A cancels buy order of client B --> rejected, client B cancels its buy order --> cancelled
+ 		let (tx, _rx) = channel();
+ 		let gateway = Arc::new(Mutex::new(OrderEntryGateway::new(MatchingEngine::new(&tx))));
+ 		let server = OrderEntryServer::bind("127.0.0.1:0").unwrap();
+ 		let address = server.local_addr().unwrap();
+ 		server.start(&gateway);
+
+ 		let mut client_a = TcpStream::connect(address).unwrap();
+ 		let mut reader_a = BufReader::new(client_a.try_clone().unwrap());
+ 		let mut client_b = TcpStream::connect(address).unwrap();
+ 		let mut reader_b = BufReader::new(client_b.try_clone().unwrap());
+ 		let read_report = |reader: &mut BufReader<TcpStream>| {
+ 			let mut line = String::new();
+ 			reader.read_line(&mut line).unwrap();
+ 			::serde_json::from_str::<ExecutionReport>(&line).unwrap()
+ 		};
+
+ 		writeln!(client_a, r#"{{"msg_type":"D","symbol":"AAA","side":"2","price":1,"qty":1}}"#).unwrap();
+ 		let sell_ack = read_report(&mut reader_a);
+ 		assert_eq!(sell_ack.get_exec_type(), '0');
+
+ 		writeln!(client_b, r#"{{"msg_type":"D","symbol":"AAA","side":"1","price":1,"qty":2}}"#).unwrap();
+ 		let buy_ack = read_report(&mut reader_b);
+ 		assert_eq!(buy_ack.get_exec_type(), '0');
+ 		let buy_fill = read_report(&mut reader_b);
+ 		assert_eq!((buy_fill.get_exec_type(), buy_fill.get_leaves_qty()), ('F', 1));
+ 		// fill of the resting order is sent to the client which entered it
+ 		let sell_fill = read_report(&mut reader_a);
+ 		assert_eq!((sell_fill.get_order_id(), sell_fill.get_exec_type()), (sell_ack.get_order_id(), 'F'));
+ 		assert_eq!(sell_fill.get_trade_id(), buy_fill.get_trade_id());
+
+ 		// orders of other clients cannot be cancelled
+ 		writeln!(client_a, "{{\"msg_type\":\"F\",\"order_id\":\"{}\"}}", buy_ack.get_order_id()).unwrap();
+ 		let reject = read_report(&mut reader_a);
+ 		assert_eq!(reject.get_exec_type(), '8');
+ 		assert_eq!(reject.get_reject_reason(), Some(ExchangeError::UnknownOrder(buy_ack.get_order_id())));
+
+ 		writeln!(client_b, "{{\"msg_type\":\"F\",\"order_id\":\"{}\"}}", buy_ack.get_order_id()).unwrap();
+ 		let cancel = read_report(&mut reader_b);
+ 		assert_eq!((cancel.get_order_id(), cancel.get_exec_type()), (buy_ack.get_order_id(), '4'));
+
+ 		// malformed requests are rejected
+ 		writeln!(client_b, "not a request").unwrap();
+ 		let reject = read_report(&mut reader_b);
+ 		assert_eq!(reject.get_exec_type(), '8');
+
+ 		// orders with an unknown owner (entered directly on the matching engine) can only be cancelled by the console
+ 		let mut match_eng = MatchingEngine::with_publisher(Box::new(CapturePublisher::new()));
+ 		let order_id = match_eng.insert(&Order::new(1, 1, '2')).unwrap().0.get_id();
+ 		let mut gateway = OrderEntryGateway::new(match_eng);
+ 		let (session_tx, session_rx) = channel();
+ 		let session = gateway.open_session(&session_tx);
+ 		assert_eq!(gateway.handle(session, &OrderEntryRequest::cancel(&order_id)).unwrap_err(), ExchangeError::UnknownOrder(order_id.clone()));
+ 		assert_eq!(session_rx.try_recv().unwrap().get_exec_type(), '8');
+ 		assert!(gateway.handle(session, &OrderEntryRequest::replace(&order_id, 1, 2)).is_err());
+ 		assert!(gateway.handle(CONSOLE_SESSION, &OrderEntryRequest::cancel(&order_id)).is_ok());
+ 	}
+
+ 	#[test]
+ 	fn test_fix_message() {
+ 		let mut message = FixMessage::new("D");
+ 		message.set_field(11, "ORDER-1");
+ 		message.set_field(55, "AAA");
+ 		message.set_field(34, "2");
+ 		// header fields are encoded first
+ 		let encoded = message.encode();
+ 		assert!(encoded.starts_with("8=FIX.4.4\x019=28\x0135=D\x0134=2\x0111=ORDER-1\x0155=AAA\x0110="));
+ 		assert_eq!(FixMessage::decode(&encoded).unwrap().get_field(11), Some("ORDER-1".to_string()));
+
+ 		// messages are extracted from data received in several parts
+ 		let mut buffer = encoded.as_bytes()[..20].to_vec();
+ 		assert!(FixMessage::extract(&mut buffer).is_none());
+ 		buffer.extend_from_slice(&encoded.as_bytes()[20..]);
+ 		buffer.extend_from_slice(&encoded.as_bytes()[..5]);
+ 		assert_eq!(FixMessage::extract(&mut buffer).unwrap().unwrap().get_field(55), Some("AAA".to_string()));
+ 		assert_eq!(buffer, encoded.as_bytes()[..5].to_vec());
+
+ 		// wrong checksum
+ 		let garbled = encoded.replace("AAA", "AAB");
+ 		assert!(FixMessage::decode(&garbled).is_err());
+ 	}
+
+ 	#[test]
+ 	fn test_fix_session() {
+ 		let (tx, _rx) = channel();
+ 		let gateway = Arc::new(Mutex::new(OrderEntryGateway::new(MatchingEngine::new(&tx))));
+ 		let acceptor = FixAcceptor::bind("127.0.0.1:0", "GHEX").unwrap();
+ 		let mut client = TcpStream::connect(acceptor.local_addr().unwrap()).unwrap();
+ 		acceptor.start(&gateway);
+
+ 		let mut buffer: Vec<u8> = Vec::new();
+ 		let mut receive = |client: &mut TcpStream| {
+ 			loop {
+ 				if let Some(message) = FixMessage::extract(&mut buffer) {
+ 					return message.unwrap();
+ 				}
+ 				let mut chunk = [0u8; 1024];
+ 				let size = ::std::io::Read::read(client, &mut chunk).unwrap();
+ 				assert!(size > 0, "connection closed");
+ 				buffer.extend_from_slice(&chunk[..size]);
+ 			}
+ 		};
+ 		let send = |client: &mut TcpStream, seq: i64, msg_type: &str, fields: &[(u32, &str)]| {
+ 			let mut message = FixMessage::new(msg_type);
+ 			message.set_field(49, "CLIENT");
+ 			message.set_field(56, "GHEX");
+ 			message.set_field(34, &seq.to_string());
+ 			message.set_field(52, "20180101-00:00:00.000");
+ 			for &(tag, value) in fields {
+ 				message.set_field(tag, value);
+ 			}
+ 			client.write_all(message.encode().as_bytes()).unwrap();
+ 		};
+
+ 		send(&mut client, 1, "A", &[(98, "0"), (108, "30")]);
+ 		let logon = receive(&mut client);
+ 		assert_eq!(logon.get_msg_type(), "A");
+ 		assert_eq!(logon.get_field(56), Some("CLIENT".to_string()));
+
+ 		send(&mut client, 2, "D", &[(11, "S1"), (55, "AAA"), (54, "2"), (38, "1"), (40, "2"), (44, "1")]);
+ 		let sell_ack = receive(&mut client);
+ 		assert_eq!((sell_ack.get_msg_type(), sell_ack.get_field(150), sell_ack.get_field(11)), ("8".to_string(), Some("0".to_string()), Some("S1".to_string())));
+
+ 		send(&mut client, 3, "D", &[(11, "B1"), (55, "AAA"), (54, "1"), (38, "2"), (40, "2"), (44, "1")]);
+ 		let buy_ack = receive(&mut client);
+ 		assert_eq!((buy_ack.get_field(150), buy_ack.get_field(11)), (Some("0".to_string()), Some("B1".to_string())));
+ 		let buy_fill = receive(&mut client);
+ 		assert_eq!((buy_fill.get_field(150), buy_fill.get_field(39)), (Some("F".to_string()), Some("1".to_string())));
+ 		assert_eq!((buy_fill.get_field(14), buy_fill.get_field(151)), (Some("1".to_string()), Some("1".to_string())));
+ 		let sell_fill = receive(&mut client);
+ 		assert_eq!((sell_fill.get_field(11), sell_fill.get_field(39)), (Some("S1".to_string()), Some("2".to_string())));
+
+ 		// cancel of an unknown order
+ 		send(&mut client, 4, "F", &[(11, "C1"), (41, "UNKNOWN"), (55, "AAA"), (54, "1")]);
+ 		let cancel_reject = receive(&mut client);
+ 		assert_eq!(cancel_reject.get_msg_type(), "9");
+ 		assert_eq!((cancel_reject.get_field(434), cancel_reject.get_field(102)), (Some("1".to_string()), Some("1".to_string())));
+
+ 		// replace of the remaining buy order, then the order is identified by the new ClOrdID
+ 		send(&mut client, 5, "G", &[(11, "B2"), (41, "B1"), (55, "AAA"), (54, "1"), (38, "3"), (40, "2"), (44, "1")]);
+ 		let replaced = receive(&mut client);
+ 		assert_eq!((replaced.get_field(150), replaced.get_field(11), replaced.get_field(41)), (Some("5".to_string()), Some("B2".to_string()), Some("B1".to_string())));
+ 		assert_eq!(replaced.get_field(37), buy_ack.get_field(37));
+
+ 		// resend request: Logon replaced by a gap fill, application messages sent again
+ 		send(&mut client, 6, "2", &[(7, "1"), (16, "2")]);
+ 		let gap_fill = receive(&mut client);
+ 		assert_eq!((gap_fill.get_msg_type(), gap_fill.get_field(123), gap_fill.get_field(36)), ("4".to_string(), Some("Y".to_string()), Some("2".to_string())));
+ 		let duplicate = receive(&mut client);
+ 		assert_eq!((duplicate.get_field(34), duplicate.get_field(43), duplicate.get_field(11)), (Some("2".to_string()), Some("Y".to_string()), Some("S1".to_string())));
+
+ 		// sequence gap: exchange asks for the missing messages
+ 		send(&mut client, 9, "0", &[]);
+ 		let resend_request = receive(&mut client);
+ 		assert_eq!((resend_request.get_msg_type(), resend_request.get_field(7)), ("2".to_string(), Some("7".to_string())));
+ 		send(&mut client, 7, "4", &[(123, "Y"), (36, "10")]);
+
+ 		send(&mut client, 10, "5", &[]);
+ 		assert_eq!(receive(&mut client).get_msg_type(), "5");
+ 	}
+
  	// unit tests
  	//#[test]
  	// fn test_find_order_by_id() {
  	// 	let mut match_eng = MatchingEngine::new();
  	// 	let mut first_order = Order::new(100, 100, '1');
  	// 	// first_order: ID = 0
- 	// 	match_eng.insert(&first_order);
+ 	// 	match_eng.insert(&first_order).unwrap();
  	// 	first_order.set_id(&'0'.to_string());
  	// 	assert_eq!(match_eng.find_order_by_id(&first_order.get_id()), first_order);
 
  	// 	let mut second_order = Order::new(50, 10, '1');
  	// 	// second order: ID = 1
- 	// 	match_eng.insert(&second_order);
+ 	// 	match_eng.insert(&second_order).unwrap();
  	// 	second_order.set_id(&'1'.to_string());
  	// 	assert_eq!(match_eng.find_order_by_id(&second_order.get_id()), second_order);
 
  	// 	let mut third_order = Order::new(125, 1, '2');
  	// 	// third order: ID = 2
  	// 	// Matching happens hear
- 	// 	match_eng.insert(&third_order);
+ 	// 	match_eng.insert(&third_order).unwrap();
 
  	// 	third_order.set_id(&'2'.to_string());
  	// 	assert_eq!(match_eng.find_order_by_id(&third_order.get_id()), Order::new(-1, -1, '*'));
@@ -620,9 +980,9 @@ mod tests {
  	// 	let another_order = Order::new(10, 1000, '2');
  	// 	// 1st insertion (id = 0): Insert old_order (no matching happens)
  	// 	// old_order_traded: return order of first insertion (copy of old_order with id = 0)
- 	// 	let old_order_traded = match_eng.insert(&old_order);
+ 	// 	let old_order_traded = match_eng.insert(&old_order).unwrap();
  	// 	// 2nd insertion (id = 1): Insert another_order (no matching happens)
- 	// 	match_eng.insert(&another_order);
+ 	// 	match_eng.insert(&another_order).unwrap();
 
  	// 	// old_id = 0 (id of 1st order inserted)
  	// 	let old_id = old_order_traded.get_id();
@@ -635,7 +995,7 @@ mod tests {
  	// 	// After update, order with ID = 0 is moved to the back of the queue
 
  	// 	// Check quantity of updated order
- 	// 	assert_eq!(match_eng.find_order_by_id(&old_id).get_qty(), 150);
+ 	// 	assert_eq!(match_eng.find_order_by_id(&old_id).unwrap().get_qty(), 150);
 
  	// 	// yet_another_order: quantity = 11, price = 1000, side = buy
  	// 	let yet_another_order = Order::new(11, 1000, '1');
@@ -643,9 +1003,9 @@ mod tests {
  	// 	// - 1st match: with another_order (id = 1, quantity = 10) -> 10 matched, 1 remaining
  	// 	// - 2nd match: with old_order (id = 0, quantity = 150) -> 1 matched, 0 remaining
  	// 	// => old_order: quantity = 149
- 	// 	match_eng.insert(&yet_another_order);
+ 	// 	match_eng.insert(&yet_another_order).unwrap();
  	// 	// Check quantity of old_order (id = 0) after matching
- 	// 	assert_eq!(match_eng.find_order_by_id(&old_id).get_qty(), 149);
+ 	// 	assert_eq!(match_eng.find_order_by_id(&old_id).unwrap().get_qty(), 149);
  	// }
  	
  	// #[test]
@@ -654,8 +1014,8 @@ mod tests {
  	// 	let mut match_eng = MatchingEngine::new();
  	// 	let old_order = Order::new(100, 1000, '2');
  	// 	let another_order = Order::new(10, 1000, '2');
- 	// 	let old_order_after = match_eng.insert(&old_order);
- 	// 	match_eng.insert(&another_order);
+ 	// 	let old_order_after = match_eng.insert(&old_order).unwrap();
+ 	// 	match_eng.insert(&another_order).unwrap();
 
  	// 	// update old_order with new quantity 95
  	// 	let old_id = old_order_after.get_id();
@@ -664,24 +1024,1110 @@ mod tests {
  	// 	match_eng.update(&old_id, &new_order);
 
  	// 	// check that order quantity was updated correctly
- 	// 	assert_eq!(match_eng.find_order_by_id(&old_id).get_qty(), 95);
+ 	// 	assert_eq!(match_eng.find_order_by_id(&old_id).unwrap().get_qty(), 95);
 
  	// 	// insert buy order to trigger trade
  	// 	let yet_another_order = Order::new(11, 1000, '1');
- 	// 	match_eng.insert(&yet_another_order);
+ 	// 	match_eng.insert(&yet_another_order).unwrap();
 
  	// 	// check that order position is correct after update
  	// 	// since qty decreased, old_order keeps its place in queue
  	// 	// so quantity traded should be 95 - 11 = 84
- 	// 	assert_eq!(match_eng.find_order_by_id(&old_id).get_qty(), 84);
+ 	// 	assert_eq!(match_eng.find_order_by_id(&old_id).unwrap().get_qty(), 84);
  	// }
  	
+ 	#[test]
+ 	fn test_config() {
+ 		// missing settings take their default values
+ 		let mut config = Config::from_toml("recovery_period = 10\nmulticast_ttl = 4\norder_entry_address = \"127.0.0.1:22005\"").unwrap();
+ 		assert_eq!(config.get_recovery_period(), 10);
+ 		assert_eq!(config.get_multicast_ttl(), 4);
+ 		assert_eq!(config.get_order_entry_address(), "127.0.0.1:22005");
+ 		assert_eq!(config.get_fix_comp_id(), Config::default().get_fix_comp_id());
+
+ 		// environment variables override the file (other variables are ignored)
+ 		let vars = vec![("GHEX_RECOVERY_PERIOD".to_string(), "20".to_string()), ("HOME".to_string(), "/root".to_string())];
+ 		config.apply_env(vars.into_iter()).unwrap();
+ 		assert_eq!(config.get_recovery_period(), 20);
+
+ 		// command-line flags override the environment variables
+ 		let args: Vec<String> = ["--recovery-period", "30", "--multicast-interface", "10.0.0.5"].iter().map(|arg| arg.to_string()).collect();
+ 		config.apply_args(&args).unwrap();
+ 		assert_eq!(config.get_recovery_period(), 30);
+ 		assert_eq!(config.multicast_bind_address("0.0.0.0:21001"), "10.0.0.5:21001");
+
+ 		// invalid settings are rejected
+ 		assert!(Config::from_toml("unknown_setting = 1").is_err());
+ 		assert!(config.set("recovery_period", "soon").is_err());
+ 		assert!(config.apply_args(&["--fix-comp-id".to_string()]).is_err());
+ 		assert!(config.apply_env(vec![("GHEX_PORT".to_string(), "1".to_string())].into_iter()).is_err());
+
+ 		// values out of range are rejected (file, environment variables and flags), the setting is NOT changed
+ 		assert!(Config::from_toml("recovery_period = 0").is_err());
+ 		assert!(Config::from_toml("snapshot_max_payload = 0").is_err());
+ 		assert!(Config::from_toml("multicast_ttl = 256").is_err());
+ 		assert!(config.set("recovery_period", "0").is_err());
+ 		assert!(config.set("snapshot_max_payload", "0").is_err());
+ 		assert!(config.set("multicast_ttl", "256").is_err());
+ 		assert_eq!((config.get_recovery_period(), config.get_multicast_ttl()), (30, 4));
+ 		assert_eq!(config.get_snapshot_max_payload(), 1400);
+ 	}
+
+ 	#[test]
+ 	fn test_market_data_publishers() {
+ 		// sell 1 at 1, buy 2 at 1 --> events published to EVERY publisher, in the order they happen
+ 		let capture = CapturePublisher::new();
+ 		let path = ::std::env::temp_dir().join("ghex_test_market_data.log");
+ 		let mut match_eng = MatchingEngine::with_publisher(Box::new(capture.clone()));
+ 		match_eng.add_publisher(Box::new(NullPublisher));
+ 		match_eng.add_publisher(Box::new(FilePublisher::create(path.to_str().unwrap()).unwrap()));
+
+ 		let sell_id = match_eng.insert(&Order::new(1, 1, '2')).unwrap().0.get_id();
+ 		let buy_id = match_eng.insert(&Order::new(2, 1, '1')).unwrap().0.get_id();
+
+ 		let events = capture.get_events();
+ 		// Incremental Feed and Trade Feed (the MBP and depth feeds are checked by test_price_level_feed)
+ 		let feed_events: Vec<&MarketDataEvent> = events.iter()
+ 			.filter(|event| matches!(event, MarketDataEvent::Incremental(_) | MarketDataEvent::Trade(_)))
+ 			.collect();
+ 		assert_eq!(feed_events.len(), 4);
+ 		match (feed_events[0], feed_events[1], feed_events[2], feed_events[3]) {
+ 			(MarketDataEvent::Incremental(rested), MarketDataEvent::Incremental(matched),
+ 				MarketDataEvent::Incremental(buy_rested), MarketDataEvent::Trade(trade)) => {
+ 				assert_eq!(rested.get_orders()[0].get_id(), sell_id);
+ 				assert_eq!(matched.get_orders().len(), 0);
+ 				assert_eq!(buy_rested.get_orders()[0].get_id(), buy_id);
+ 				assert_eq!(buy_rested.get_num(), 3);
+ 				assert_eq!(trade.get_passive_id(), sell_id);
+ 				assert_eq!(trade.get_qty(), 1);
+ 			}
+ 			_ => panic!("unexpected events {:?}", events),
+ 		}
+
+ 		// the file contains the same events, ONE per line
+ 		let recorded = ::std::fs::read_to_string(&path).unwrap();
+ 		let recorded: Vec<MarketDataEvent> = recorded.lines().map(|line| ::serde_json::from_str(line).unwrap()).collect();
+ 		assert_eq!(recorded, events);
+ 		let _ = ::std::fs::remove_file(&path);
+ 	}
+
+ 	#[test]
+ 	fn test_feed_codec() {
+ 		// buy AAA 1x2, buy AAA 1x1, sell AAA 3x1 --> incremental messages with 1 and 2 orders
+ 		let capture = CapturePublisher::new();
+ 		let mut match_eng = MatchingEngine::with_publisher(Box::new(capture.clone()));
+ 		let mut buy = Order::new(2, 1, '1');
+ 		buy.set_symbol("AAA");
+ 		match_eng.insert(&buy).unwrap();
+ 		buy.set_qty(1);
+ 		match_eng.insert(&buy).unwrap();
+ 		let mut sell = Order::new(1, 3, '2');
+ 		sell.set_symbol("AAA");
+ 		sell.set_time_in_force('1');
+ 		match_eng.insert(&sell).unwrap();
+
+ 		let messages: Vec<IncrementalMessage> = capture.get_events().into_iter().filter_map(|event| match event {
+ 			MarketDataEvent::Incremental(message) => Some(message),
+ 			_ => None,
+ 		}).collect();
+ 		assert_eq!(messages[1].get_orders().len(), 2);
+ 		for message in &messages {
+ 			for encoding in &[FeedEncoding::Json, FeedEncoding::Binary] {
+ 				let data = encode_incremental(message, *encoding);
+ 				assert_eq!(&decode_incremental(&data, *encoding).unwrap(), message);
+ 			}
+ 		}
+
+ 		// recovery feed: buy level at 1 (2 orders), sell level at 3
+ 		let state = vec![messages[1].get_orders(), messages[2].get_orders()];
+ 		let feed = RecoveryFeed::new("AAA", 3, state);
+ 		let json = encode_recovery(&feed, FeedEncoding::Json);
+ 		let binary = encode_recovery(&feed, FeedEncoding::Binary);
+ 		assert!(binary.len() < json.len());
+ 		assert_eq!(decode_recovery(&json, FeedEncoding::Json).unwrap(), feed);
+ 		assert_eq!(decode_recovery(&binary, FeedEncoding::Binary).unwrap(), feed);
+
+ 		// non-ASCII symbol --> same symbol decoded, symbol longer than 255 bytes --> truncated on a character boundary
+ 		let feed = RecoveryFeed::new("ÉCU-€", 3, Vec::new());
+ 		assert_eq!(decode_recovery(&encode_recovery(&feed, FeedEncoding::Binary), FeedEncoding::Binary).unwrap(), feed);
+ 		let long = RecoveryFeed::new(&"é".repeat(128), 3, Vec::new());
+ 		let decoded = decode_recovery(&encode_recovery(&long, FeedEncoding::Binary), FeedEncoding::Binary).unwrap();
+ 		assert_eq!(decoded.get_symbol(), "é".repeat(127));
+
+ 		// truncated message, wrong template, unknown encoding --> rejected
+ 		assert!(decode_recovery(&binary[..binary.len() - 1], FeedEncoding::Binary).is_err());
+ 		assert!(decode_incremental(&binary, FeedEncoding::Binary).is_err());
+ 		assert!(FeedEncoding::parse("xml").is_err());
+ 		assert_eq!(Config::from_toml("feed_encoding = \"binary\"").unwrap().get_feed_encoding(), FeedEncoding::Binary);
+ 	}
+
+ 	#[test]
+ 	fn test_snapshot_fragments() {
+ 		// recovery feed with 50 levels, larger than the maximum payload
+ 		let state: Vec<Vec<Order>> = (1..51).map(|price| {
+ 			let mut order = Order::new(price, price, '1');
+ 			order.set_id(&price.to_string());
+ 			order.set_symbol("AAA");
+ 			vec![order]
+ 		}).collect();
+ 		let feed = RecoveryFeed::new("AAA", 50, state);
+ 		for encoding in &[FeedEncoding::Json, FeedEncoding::Binary] {
+ 			let data = encode_recovery(&feed, *encoding);
+ 			let fragments = SnapshotFragment::split(7, 50, &data, 500);
+ 			assert_eq!(fragments.len(), data.len().div_ceil(500));
+ 			assert!(fragments.iter().all(|fragment| fragment.get_payload().len() <= 500 && fragment.get_fragments() == fragments.len() as u32));
+
+ 			// fragments received out of order, one of them twice
+ 			let mut assembler = SnapshotAssembler::new(*encoding);
+ 			let mut datagrams: Vec<Vec<u8>> = fragments.iter().map(encode_fragment).collect();
+ 			datagrams.reverse();
+ 			let last = datagrams.pop().unwrap();
+ 			assert_eq!(assembler.add_datagram(&datagrams[0]).unwrap(), None);
+ 			for datagram in &datagrams {
+ 				assert_eq!(assembler.add_datagram(datagram).unwrap(), None);
+ 			}
+ 			assert_eq!(assembler.add_datagram(&last).unwrap(), Some(feed.clone()));
+ 			// duplicate of a snapshot already reassembled
+ 			assert_eq!(assembler.add_datagram(&last).unwrap(), None);
+ 		}
+
+ 		// first fragment of snapshot 8 lost, snapshot 9 received (then a late fragment of snapshot 8)
+ 		let data = encode_recovery(&feed, FeedEncoding::Binary);
+ 		let mut assembler = SnapshotAssembler::new(FeedEncoding::Binary);
+ 		let lost = SnapshotFragment::split(8, 50, &data, 500);
+ 		assert_eq!(assembler.add_fragment(&lost[1]).unwrap(), None);
+ 		let mut result = None;
+ 		for fragment in SnapshotFragment::split(9, 50, &data, 500) {
+ 			result = assembler.add_fragment(&fragment).unwrap();
+ 		}
+ 		assert_eq!(result, Some(feed));
+ 		assert_eq!(assembler.add_fragment(&lost[0]).unwrap(), None);
+
+ 		// empty recovery feed --> ONE fragment, not a fragment --> rejected
+ 		assert_eq!(SnapshotFragment::split(10, 0, &[], 500).len(), 1);
+ 		// payload length encoded on 16 bits --> larger maximum payloads clamped, rejected by the configuration
+ 		let large = vec![0u8; 70_000];
+ 		assert_eq!(SnapshotFragment::split(11, 0, &large, 100_000).len(), 2);
+ 		assert!(Config::from_toml("snapshot_max_payload = 65536").is_err());
+ 		assert!(Config::default().set("snapshot_max_payload", "65536").is_err());
+ 		assert!(Config::default().set("snapshot_max_payload", "65535").is_ok());
+ 		// fragment count read off the wire --> capped
+ 		let fragment = SnapshotFragment::new(12, 0, 1, u32::MAX, &[1]);
+ 		assert!(SnapshotAssembler::new(FeedEncoding::Binary).add_fragment(&fragment).is_err());
+ 		let fragment = SnapshotFragment::new(12, 0, 1, MAX_FRAGMENTS as u32 + 1, &[1]);
+ 		assert!(SnapshotAssembler::new(FeedEncoding::Binary).add_datagram(&encode_fragment(&fragment)).is_err());
+ 		assert!(assembler.add_datagram(&data).is_err());
+ 	}
+
+ 	#[test]
+ 	fn test_price_level_feed() {
+ 		let capture = CapturePublisher::new();
+ 		let mut match_eng = MatchingEngine::with_publisher(Box::new(capture.clone()));
+ 		match_eng.set_depth_levels(2);
+ 		for &(price, qty) in &[(10, 1), (10, 2), (9, 1), (8, 1)] {
+ 			match_eng.insert(&Order::new(qty, price, '1')).unwrap();
+ 		}
+ 		match_eng.insert(&Order::new(3, 10, '2')).unwrap();
+
+ 		// (action, side, price, quantity, number of orders) of every MBP message,
+ 		// number of MBP messages published before every depth message
+ 		let mut levels = Vec::new();
+ 		let mut depths = Vec::new();
+ 		for event in capture.get_events() {
+ 			match event {
+ 				MarketDataEvent::PriceLevel(message) => {
+ 					assert_eq!(message.get_num(), levels.len() as i64 + 1);
+ 					let level = message.get_level();
+ 					levels.push((message.get_action(), message.get_side(), level.get_price(), level.get_qty(), level.get_order_count()));
+ 				}
+ 				MarketDataEvent::Depth(message) => depths.push(message),
+ 				_ => {}
+ 			}
+ 		}
+ 		assert_eq!(levels, vec![('0', '1', 10, 1, 1), ('1', '1', 10, 3, 2), ('0', '1', 9, 1, 1), ('0', '1', 8, 1, 1),
+ 			('1', '1', 10, 2, 1), ('2', '1', 10, 0, 0)]);
+ 		// NO depth message for the buy at 8 (NOT one of the 2 best levels)
+ 		let last_msgs: Vec<i64> = depths.iter().map(|depth| depth.get_last_msg()).collect();
+ 		assert_eq!(last_msgs, vec![1, 2, 3, 5, 6]);
+ 		let last = depths.last().unwrap();
+ 		assert_eq!(last.get_buys(), vec![PriceLevel::new(9, 1, 1), PriceLevel::new(8, 1, 1)]);
+ 		assert_eq!(last.get_sells().len(), 0);
+ 	}
+
+ 	#[test]
+ 	fn test_order_actions() {
+ 		let capture = CapturePublisher::new();
+ 		let mut match_eng = MatchingEngine::with_publisher(Box::new(capture.clone()));
+ 		let first = match_eng.insert(&Order::new(1, 10, '1')).unwrap().0.get_id();
+ 		let second = match_eng.insert(&Order::new(2, 10, '1')).unwrap().0.get_id();
+ 		// Quantity decreases: keeps its priority, quantity increases: moves to the rear of the queue
+ 		match_eng.update(&second, &Order::new(1, 10, '1')).unwrap();
+ 		match_eng.update(&first, &Order::new(5, 10, '1')).unwrap();
+ 		match_eng.insert(&Order::new(2, 10, '2')).unwrap();
+ 		match_eng.delete(&first).unwrap();
+ 		let third = match_eng.insert(&Order::new(1, 9, '1')).unwrap().0.get_id();
+ 		match_eng.update(&third, &Order::new(1, 8, '1')).unwrap();
+
+ 		// (action, order ID, side, price, quantity, position) of every incremental message
+ 		let mut actions = Vec::new();
+ 		for event in capture.get_events() {
+ 			if let MarketDataEvent::Incremental(message) = event {
+ 				assert_eq!(message.get_num(), actions.len() as i64 + 1);
+ 				actions.push((message.get_action(), message.get_order_id(), message.get_side(), message.get_price(), message.get_qty(), message.get_position()));
+ 			}
+ 		}
+ 		assert_eq!(actions, vec![
+ 			('A', first.clone(), '1', 10, 1, 0), ('A', second.clone(), '1', 10, 2, 1),
+ 			('M', second.clone(), '1', 10, 1, 1), ('M', first.clone(), '1', 10, 5, 1),
+ 			('E', second.clone(), '1', 10, 1, 0), ('E', first.clone(), '1', 10, 1, 0),
+ 			('C', first.clone(), '1', 10, 4, 0),
+ 			('A', third.clone(), '1', 9, 1, 0), ('C', third.clone(), '1', 9, 1, 0), ('A', third.clone(), '1', 8, 1, 0)]);
+ 	}
+
+ 	#[test]
+ 	fn test_quote_feed() {
+ 		let capture = CapturePublisher::new();
+ 		let mut match_eng = MatchingEngine::with_publisher(Box::new(capture.clone()));
+ 		match_eng.insert(&Order::new(1, 10, '1')).unwrap();
+ 		let behind = match_eng.insert(&Order::new(1, 9, '1')).unwrap().0.get_id();
+ 		match_eng.insert(&Order::new(2, 10, '1')).unwrap();
+ 		let offer = match_eng.insert(&Order::new(1, 11, '2')).unwrap().0.get_id();
+ 		match_eng.update(&behind, &Order::new(5, 9, '1')).unwrap();
+ 		match_eng.insert(&Order::new(3, 10, '2')).unwrap();
+ 		match_eng.delete(&offer).unwrap();
+
+ 		// (bid price, bid quantity, ask price, ask quantity) of every quote
+ 		let mut quotes = Vec::new();
+ 		for event in capture.get_events() {
+ 			if let MarketDataEvent::Quote(message) = event {
+ 				assert_eq!(message.get_symbol(), "");
+ 				assert_eq!(message.get_num(), quotes.len() as i64 + 1);
+ 				quotes.push((message.get_bid_price(), message.get_bid_qty(), message.get_ask_price(), message.get_ask_qty()));
+ 			}
+ 		}
+ 		assert_eq!(quotes, vec![(10, 1, 0, 0), (10, 3, 0, 0), (10, 3, 11, 1), (9, 5, 11, 1), (9, 5, 0, 0)]);
+ 	}
+
+ 	#[test]
+ 	fn test_bar_aggregator() {
+ 		let mut bars = BarAggregator::new(&[60, 300], 100);
+ 		let mut completed = Vec::new();
+ 		for &(time, price, qty) in &[("20240102-12:00:10.500", 10, 1), ("20240102-12:00:50.000", 12, 2),
+ 			("20240102-12:01:05.000", 9, 1), ("20240102-12:05:00.000", 11, 4)] {
+ 			let mut trade = Trade::new("1", "AAA", "2", "3", price, qty, '1');
+ 			trade.set_transact_time(time);
+ 			completed.push(bars.add_trade(&trade));
+ 		}
+ 		// Bars completed by every trade
+ 		let mut minute = Bar::new("AAA", 60, "20240102-12:00:00.000", 10, 1);
+ 		minute.add_trade(12, 2);
+ 		let mut five_minutes = Bar::new("AAA", 300, "20240102-12:00:00.000", 10, 1);
+ 		five_minutes.add_trade(12, 2);
+ 		five_minutes.add_trade(9, 1);
+ 		assert_eq!(completed, vec![vec![], vec![], vec![minute],
+ 			vec![Bar::new("AAA", 60, "20240102-12:01:00.000", 9, 1), five_minutes.clone()]]);
+ 		assert_eq!((five_minutes.get_high(), five_minutes.get_low(), five_minutes.get_close()), (12, 9, 9));
+ 		assert_eq!((five_minutes.get_volume(), five_minutes.get_trade_count()), (4, 3));
+ 		assert_eq!(bars.get_bars("AAA", 60).len(), 2);
+ 		assert_eq!(bars.get_current_bar("AAA", 300).unwrap().get_open(), 11);
+ 		assert_eq!(bars.get_current_bar("BBB", 300), None);
+
+ 		// NO later trade: bars completed by flush once their interval is over
+ 		// (time in milliseconds of 2024-01-02 12:00:00.000 UTC plus a number of minutes)
+ 		let minute_ms = |minutes: i64| 1704196800000 + minutes * 60_000;
+ 		assert!(bars.flush(minute_ms(6) - 1).is_empty());
+ 		let flushed = bars.flush(minute_ms(6));
+ 		assert_eq!(flushed, vec![Bar::new("AAA", 60, "20240102-12:05:00.000", 11, 4)]);
+ 		assert_eq!(bars.get_current_bar("AAA", 60), None);
+ 		assert_eq!(bars.flush(minute_ms(10)), vec![Bar::new("AAA", 300, "20240102-12:05:00.000", 11, 4)]);
+ 		assert_eq!(bars.get_bars("AAA", 60).len(), 3);
+
+ 		// Only the 2 most recent completed bars kept
+ 		let mut bars = BarAggregator::new(&[60], 2);
+ 		for minute in 0..4 {
+ 			let mut trade = Trade::new("1", "AAA", "2", "3", 10 + minute, 1, '1');
+ 			trade.set_transact_time(&format!("20240102-12:0{}:00.000", minute));
+ 			bars.add_trade(&trade);
+ 		}
+ 		let opens: Vec<i64> = bars.get_bars("AAA", 60).iter().map(|bar| bar.get_open()).collect();
+ 		assert_eq!(opens, vec![11, 12]);
+
+ 		// Bars of the matching engine: ONE trade, hence NO completed bar yet
+ 		let capture = CapturePublisher::new();
+ 		let mut match_eng = MatchingEngine::with_publisher(Box::new(capture.clone()));
+ 		match_eng.insert(&Order::new(2, 10, '1')).unwrap();
+ 		match_eng.insert(&Order::new(1, 10, '2')).unwrap();
+ 		let bar = match_eng.get_current_bar("", 60).unwrap();
+ 		assert_eq!((bar.get_open(), bar.get_volume(), bar.get_trade_count()), (10, 1, 1));
+ 		assert!(match_eng.get_bars("", 60).is_empty());
+ 		// Bar of a quiet instrument published by flush_bars once its interval is over (clock of the engine)
+ 		let clock = SimulatedClock::new(minute_ms(0) + 10_000);
+ 		let capture = CapturePublisher::new();
+ 		let mut match_eng = MatchingEngine::with_publisher(Box::new(capture.clone()));
+ 		match_eng.set_clock(Box::new(clock.clone()));
+ 		match_eng.insert(&Order::new(2, 10, '1')).unwrap();
+ 		match_eng.insert(&Order::new(1, 10, '2')).unwrap();
+ 		match_eng.flush_bars();
+ 		clock.advance(50_000);
+ 		match_eng.flush_bars();
+ 		let published: Vec<Bar> = capture.get_events().into_iter().filter_map(|event| match event {
+ 			MarketDataEvent::Bar(bar) => Some(bar),
+ 			_ => None,
+ 		}).collect();
+ 		assert_eq!(published, vec![Bar::new("", 60, "20240102-12:00:00.000", 10, 1)]);
+ 		assert_eq!(match_eng.get_bars("", 60), published);
+ 		assert_eq!(Config::from_toml("bar_intervals = [60, 300]").unwrap().get_bar_intervals(), vec![60, 300]);
+ 		let mut config = Config::default();
+ 		config.set("bar_intervals", "1,5").unwrap();
+ 		assert_eq!(config.get_bar_intervals(), vec![1, 5]);
+ 		assert!(config.set("bar_intervals", "1,x").is_err());
+ 		assert_eq!(Config::default().get_bar_history(), 100);
+ 		config.set("bar_history", "10").unwrap();
+ 		assert_eq!(config.get_bar_history(), 10);
+ 	}
+
+ 	// Listener recording the notifications of a feed handler
+ 	struct RecordListener {
+ 		events: Arc<Mutex<Vec<String>>>,
+ 	}
+
+ 	impl FeedListener for RecordListener {
+ 		fn on_update(&mut self, book: &LocalBook, message: &IncrementalMessage) {
+ 			self.events.lock().unwrap().push(format!("update {} #{}", book.get_symbol(), message.get_num()));
+ 		}
+
+ 		fn on_gap(&mut self, first_missing: i64, last_missing: i64) {
+ 			self.events.lock().unwrap().push(format!("gap {}-{}", first_missing, last_missing));
+ 		}
+
+ 		fn on_recovery(&mut self, book: &LocalBook, last_msg: i64) {
+ 			self.events.lock().unwrap().push(format!("recovery {} #{}", book.get_symbol(), last_msg));
+ 		}
+ 	}
+
+ 	#[test]
+ 	fn test_feed_handler() {
+ 		let capture = CapturePublisher::new();
+ 		let mut match_eng = MatchingEngine::with_publisher(Box::new(capture.clone()));
+ 		for &(symbol, qty, price, side) in &[("AAA", 1, 10, '1'), ("BBB", 1, 20, '2'), ("AAA", 1, 11, '1'), ("AAA", 2, 12, '2')] {
+ 			let mut order = Order::new(qty, price, side);
+ 			order.set_symbol(symbol);
+ 			match_eng.insert(&order).unwrap();
+ 		}
+ 		let messages: Vec<IncrementalMessage> = capture.get_events().into_iter().filter_map(|event| match event {
+ 			MarketDataEvent::Incremental(message) => Some(message),
+ 			_ => None,
+ 		}).collect();
+ 		assert_eq!(messages.len(), 4);
+
+ 		// Books rebuilt without any loss
+ 		let mut reference = FeedHandler::new(FeedEncoding::Json);
+ 		for message in &messages {
+ 			reference.on_incremental(message.clone());
+ 		}
+ 		assert_eq!(reference.get_book("AAA").unwrap().best_bid(), Some((11, 1)));
+ 		assert_eq!(reference.get_book("AAA").unwrap().best_ask(), Some((12, 2)));
+
+ 		// Message 3 lost, message 1 received twice
+ 		let events = Arc::new(Mutex::new(Vec::new()));
+ 		let mut handler = FeedHandler::new(FeedEncoding::Json);
+ 		handler.add_listener(Box::new(RecordListener { events: events.clone() }));
+ 		for index in &[0, 0, 1, 3] {
+ 			let data = encode_incremental(&messages[*index], FeedEncoding::Json);
+ 			handler.on_datagram(&data).unwrap();
+ 		}
+ 		assert!(handler.in_gap());
+ 		assert_eq!(handler.get_last_seq(), 2);
+ 		assert_eq!(handler.get_book("AAA").unwrap().best_ask(), None);
+
+ 		// Recovery feed older than the gap --> ignored, recovery feeds covering the gap --> books rebuilt
+ 		handler.on_recovery(&RecoveryFeed::new("AAA", 2, vec![messages[0].get_orders()]));
+ 		assert!(handler.in_gap());
+ 		handler.on_recovery(&RecoveryFeed::new("AAA", 3, vec![messages[0].get_orders(), messages[2].get_orders()]));
+ 		assert!(!handler.in_gap());
+ 		assert_eq!(handler.get_last_seq(), 4);
+ 		assert!(!handler.is_stale("AAA"));
+ 		assert!(handler.is_stale("BBB"));
+ 		let feed = RecoveryFeed::new("BBB", 3, vec![messages[1].get_orders()]);
+ 		let fragment = SnapshotFragment::split(1, 3, &encode_recovery(&feed, FeedEncoding::Json), 1400);
+ 		handler.on_datagram(&encode_fragment(&fragment[0])).unwrap();
+ 		assert!(!handler.is_stale("BBB"));
+ 		for symbol in &["AAA", "BBB"] {
+ 			assert_eq!(handler.get_book(symbol), reference.get_book(symbol));
+ 		}
+ 		assert_eq!(*events.lock().unwrap(), vec!["update AAA #1", "update BBB #2", "gap 3-3", "recovery AAA #3",
+ 			"update AAA #4", "recovery BBB #3"]);
+ 		assert!(handler.on_datagram(b"garbage").is_err());
+
+ 		// Default instrument (empty symbol): message 2 lost --> book rebuilt by its recovery feed
+ 		let capture = CapturePublisher::new();
+ 		let mut match_eng = MatchingEngine::with_publisher(Box::new(capture.clone()));
+ 		for &(qty, price, side) in &[(1, 10, '1'), (2, 12, '2'), (1, 9, '1')] {
+ 			match_eng.insert(&Order::new(qty, price, side)).unwrap();
+ 		}
+ 		let messages: Vec<IncrementalMessage> = capture.get_events().into_iter().filter_map(|event| match event {
+ 			MarketDataEvent::Incremental(message) => Some(message),
+ 			_ => None,
+ 		}).collect();
+ 		let mut reference = FeedHandler::new(FeedEncoding::Json);
+ 		let mut handler = FeedHandler::new(FeedEncoding::Json);
+ 		for (index, message) in messages.iter().enumerate() {
+ 			reference.on_incremental(message.clone());
+ 			if index != 1 {
+ 				handler.on_incremental(message.clone());
+ 			}
+ 		}
+ 		assert!(handler.in_gap());
+ 		handler.on_recovery(&RecoveryFeed::new("", 2, vec![messages[0].get_orders(), messages[1].get_orders()]));
+ 		assert!(!handler.in_gap());
+ 		assert_eq!(handler.get_last_seq(), 3);
+ 		assert!(!handler.is_stale(""));
+ 		assert_eq!(handler.get_book(""), reference.get_book(""));
+ 		assert_eq!(handler.get_book("").unwrap().best_ask(), Some((12, 2)));
+
+ 		// Empty recovery feed (sent while there is NO instrument) --> NO book created
+ 		let mut handler = FeedHandler::new(FeedEncoding::Json);
+ 		handler.on_recovery(&RecoveryFeed::new("", 0, Vec::new()));
+ 		assert!(handler.get_symbols().is_empty());
+ 	}
+
+ 	#[test]
+ 	fn test_feed_lines() {
+ 		// UDP publisher sending the Incremental Feed on 2 lines (local sockets instead of multicast groups)
+ 		let line_a = UdpSocket::bind("127.0.0.1:0").unwrap();
+ 		let line_b = UdpSocket::bind("127.0.0.1:0").unwrap();
+ 		let mut config = Config::default();
+ 		config.set("feed_bind_address", "127.0.0.1:0").unwrap();
+ 		config.set("incremental_feed_group", &line_a.local_addr().unwrap().to_string()).unwrap();
+ 		config.set("incremental_feed_group_b", &line_b.local_addr().unwrap().to_string()).unwrap();
+ 		let mut publisher = UdpPublisher::new(&config).unwrap();
+
+ 		let capture = CapturePublisher::new();
+ 		let mut match_eng = MatchingEngine::with_publisher(Box::new(capture.clone()));
+ 		for &(qty, price, side) in &[(1, 10, '1'), (2, 11, '1'), (1, 13, '2'), (3, 11, '2'), (1, 12, '2')] {
+ 			match_eng.insert(&Order::new(qty, price, side)).unwrap();
+ 		}
+ 		let mut reference = FeedHandler::new(FeedEncoding::Json);
+ 		let mut handler = FeedHandler::new(FeedEncoding::Json);
+ 		let mut received = Vec::new();
+ 		let mut buffer = [0u8; 65536];
+ 		for event in capture.get_events() {
+ 			if let MarketDataEvent::Incremental(ref message) = event {
+ 				reference.on_incremental(message.clone());
+ 				publisher.publish(&event);
+ 				for line in &[&line_a, &line_b] {
+ 					let size = line.recv(&mut buffer).unwrap();
+ 					received.push(buffer[..size].to_vec());
+ 				}
+ 			}
+ 		}
+ 		// (line A, line B) datagrams of every message: message 2 lost on A, message 4 lost on B,
+ 		// B is ONE message late
+ 		let messages = received.len() / 2;
+ 		assert_eq!(messages, 6);
+ 		assert_eq!(received[0], received[1]);
+ 		let mut arrivals: Vec<&Vec<u8>> = Vec::new();
+ 		for index in 0..messages {
+ 			if index != 1 {
+ 				arrivals.push(&received[2 * index]);
+ 			}
+ 			if index > 0 && index != 4 {
+ 				arrivals.push(&received[2 * index - 1]);
+ 			}
+ 		}
+ 		arrivals.push(&received[2 * messages - 1]);
+ 		for data in arrivals {
+ 			handler.on_datagram(data).unwrap();
+ 		}
+ 		assert!(!handler.in_gap());
+ 		assert_eq!(handler.get_last_seq(), 6);
+ 		assert_eq!(handler.get_duplicates(), 4);
+ 		assert_eq!(handler.get_book(""), reference.get_book(""));
+ 	}
+
+ 	#[test]
+ 	fn test_fault_injection() {
+ 		// Datagrams 0 to 199 pushed ONE per millisecond, ALL datagrams released at the end
+ 		let run = |faults: &FaultConfig, seed: u64| {
+ 			let mut injector = FaultInjector::new(faults, seed);
+ 			let mut sent = Vec::new();
+ 			for index in 0..200u8 {
+ 				injector.push(vec![index], u64::from(index));
+ 				sent.extend(injector.pop_ready(u64::from(index)).into_iter().map(|datagram| datagram[0]));
+ 			}
+ 			sent.extend(injector.pop_ready(10_000).into_iter().map(|datagram| datagram[0]));
+ 			assert!(injector.is_empty());
+ 			sent
+ 		};
+ 		assert_eq!(run(&FaultConfig::default(), 1), (0..200u8).collect::<Vec<u8>>());
+
+ 		// Losses in bursts of 3, same seed -> same losses, other seed -> other losses
+ 		let mut faults = FaultConfig::default();
+ 		faults.set("drop_probability", "0.1").unwrap();
+ 		faults.set("burst_length", "3").unwrap();
+ 		let sent = run(&faults, 7);
+ 		assert_eq!(sent, run(&faults, 7));
+ 		assert!(sent != run(&faults, 8));
+ 		let lost: Vec<u8> = (0..200u8).filter(|index| !sent.contains(index)).collect();
+ 		assert!(!lost.is_empty());
+ 		// Runs of consecutive datagrams lost (the last burst may be cut by the end of the datagrams)
+ 		let mut runs: Vec<Vec<u8>> = Vec::new();
+ 		for index in lost {
+ 			match runs.last_mut() {
+ 				Some(ref mut run) if *run.last().unwrap() + 1 == index => run.push(index),
+ 				_ => runs.push(vec![index]),
+ 			}
+ 		}
+ 		for run in runs.iter().filter(|run| *run.last().unwrap() != 199) {
+ 			assert_eq!(run.len() % 3, 0);
+ 		}
+ 		// Duplicates: ALL datagrams sent twice
+ 		let mut faults = FaultConfig::default();
+ 		faults.set("duplicate_probability", "1").unwrap();
+ 		assert_eq!(run(&faults, 7), (0..200u8).flat_map(|index| vec![index, index]).collect::<Vec<u8>>());
+ 		// Reordering: ALL datagrams sent, overtaken by at most 3 later datagrams
+ 		let mut faults = FaultConfig::default();
+ 		faults.set("reorder_window", "3").unwrap();
+ 		let sent = run(&faults, 7);
+ 		assert!(sent != (0..200u8).collect::<Vec<u8>>());
+ 		let mut sorted = sent.clone();
+ 		sorted.sort();
+ 		assert_eq!(sorted, (0..200u8).collect::<Vec<u8>>());
+ 		for (position, index) in sent.iter().enumerate() {
+ 			assert!(sent[..position].iter().filter(|earlier| *earlier > index).count() <= 3);
+ 		}
+ 		// Delay: released 10 milliseconds after being pushed
+ 		let mut faults = FaultConfig::default();
+ 		faults.set("delay_ms", "10").unwrap();
+ 		let mut injector = FaultInjector::new(&faults, 7);
+ 		injector.push(vec![1], 0);
+ 		assert!(injector.pop_ready(9).is_empty());
+ 		assert_eq!(injector.pop_ready(10), vec![vec![1]]);
+
+ 		// Faults configured per feed
+ 		let mut config = Config::from_toml("fault_seed = 3\n[faults.trade]\nreorder_window = 2").unwrap();
+ 		config.set("faults_incremental_b_drop_probability", "0.5").unwrap();
+ 		config.set("faults_incremental_burst_length", "4").unwrap();
+ 		assert_eq!(config.get_fault_seed(), 3);
+ 		assert_eq!(config.get_faults("trade").get_reorder_window(), 2);
+ 		assert_eq!(config.get_faults("incremental_b").get_drop_probability(), 0.5);
+ 		assert_eq!(config.get_faults("incremental").get_burst_length(), 4);
+ 		assert!(!config.get_faults("recovery").is_active());
+ 		assert!(config.set("faults_incremental_drop_probability", "2").is_err());
+ 		assert!(config.set("faults_unknown_delay_ms", "2").is_err());
+ 		assert!(Config::from_toml("[faults.incremental]\nlatency = 2").is_err());
+ 		// Faults read from the configuration file are checked too
+ 		assert!(Config::from_toml("[faults.incremental]\ndrop_probability = 3.0").is_err());
+ 		assert!(Config::from_toml("[faults.quote]\nduplicate_probability = -0.5").is_err());
+ 		assert!(Config::from_toml("[faults.bar]\ndelay_ms = -1").is_err());
+ 		assert!(Config::from_toml("[faults.bar]\nduplicate_probability = 0.5").is_ok());
+
+ 		// 2 feeds on the same group: ALL datagrams of the trade feed dropped, NONE of the mbp feed
+ 		let receiver = UdpSocket::bind("127.0.0.1:0").unwrap();
+ 		receiver.set_read_timeout(Some(::std::time::Duration::from_millis(500))).unwrap();
+ 		let group = receiver.local_addr().unwrap().to_string();
+ 		let mut faults = FaultConfig::default();
+ 		faults.set("drop_probability", "1").unwrap();
+ 		let feeds = vec![("trade".to_string(), group.clone(), faults), ("mbp".to_string(), group.clone(), FaultConfig::default())];
+ 		let socket = FaultySocket::new(UdpSocket::bind("127.0.0.1:0").unwrap(), &feeds, 7).unwrap();
+ 		socket.send_to(vec![1], "trade", &group);
+ 		socket.send_to(vec![2], "mbp", &group);
+ 		let mut buffer = [0; 8];
+ 		assert_eq!(receiver.recv(&mut buffer).unwrap(), 1);
+ 		assert_eq!(buffer[0], 2);
+
+ 		// Datagram held for reordering (quiet feed): NOT released by the wall clock, released once the clock is advanced
+ 		let mut faults = FaultConfig::default();
+ 		faults.set("reorder_window", "3").unwrap();
+ 		let feeds = vec![("trade".to_string(), group.clone(), faults)];
+ 		let socket = FaultySocket::new(UdpSocket::bind("127.0.0.1:0").unwrap(), &feeds, 7).unwrap();
+ 		let clock = SimulatedClock::new(1_000);
+ 		socket.set_clock(Box::new(clock.clone()));
+ 		socket.send_to(vec![3], "trade", &group);
+ 		receiver.set_read_timeout(Some(::std::time::Duration::from_millis(2 * MAX_HOLD))).unwrap();
+ 		assert!(receiver.recv(&mut buffer).is_err());
+ 		clock.advance(MAX_HOLD as i64);
+ 		receiver.set_read_timeout(Some(::std::time::Duration::from_millis(500))).unwrap();
+ 		assert_eq!(receiver.recv(&mut buffer).unwrap(), 1);
+ 		assert_eq!(buffer[0], 3);
+ 	}
+
+ 	#[test]
+ 	fn test_replay() {
+ 		// Replay buffer keeping the 4 most recent messages, 6 messages published
+ 		let capture = CapturePublisher::new();
+ 		let buffer = ReplayBuffer::new(4);
+ 		let mut match_eng = MatchingEngine::with_publisher(Box::new(capture.clone()));
+ 		match_eng.add_publisher(Box::new(buffer.clone()));
+ 		for &(qty, price, side) in &[(1, 10, '1'), (2, 9, '1'), (1, 8, '1'), (1, 12, '2'), (3, 13, '2'), (1, 14, '2')] {
+ 			match_eng.insert(&Order::new(qty, price, side)).unwrap();
+ 		}
+ 		let mut messages = Vec::new();
+ 		for event in capture.get_events() {
+ 			if let MarketDataEvent::Incremental(message) = event {
+ 				messages.push(message);
+ 			}
+ 		}
+ 		assert_eq!(messages.len(), 6);
+ 		assert_eq!(buffer.get_capacity(), 4);
+
+ 		let server = ReplayServer::bind("127.0.0.1:0").unwrap();
+ 		let address = server.local_addr().unwrap().to_string();
+ 		server.start(&buffer);
+ 		let mut client = ReplayClient::connect(&address).unwrap();
+
+ 		// Range replayed, same messages as published
+ 		assert_eq!(client.request(4, 5).unwrap(), messages[3..5].to_vec());
+ 		assert_eq!(client.request(3, 6).unwrap(), messages[2..6].to_vec());
+ 		// Aged out, NOT published yet, invalid range -> rejected (connection still usable)
+ 		assert!(client.request(1, 3).unwrap_err().contains("aged out"));
+ 		assert!(client.request(5, 9).unwrap_err().contains("not published"));
+ 		assert!(client.request(5, 4).unwrap_err().contains("invalid range"));
+ 		assert_eq!(client.request(6, 6).unwrap(), messages[5..6].to_vec());
+
+ 		// Invalid request -> rejected
+ 		let mut stream = TcpStream::connect(&address).unwrap();
+ 		stream.write_all(b"{\"first\":1}\n").unwrap();
+ 		let mut line = String::new();
+ 		BufReader::new(stream).read_line(&mut line).unwrap();
+ 		let response: ::serde_json::Value = ::serde_json::from_str(&line).unwrap();
+ 		assert_eq!(response["status"], "8");
+
+ 		// Messages 3 to 5 lost -> gap filled by a replay instead of a recovery feed
+ 		let mut reference = FeedHandler::new(FeedEncoding::Json);
+ 		let mut handler = FeedHandler::new(FeedEncoding::Json);
+ 		for (index, message) in messages.iter().enumerate() {
+ 			reference.on_incremental(message.clone());
+ 			if index < 2 || index == 5 {
+ 				handler.on_incremental(message.clone());
+ 			}
+ 		}
+ 		assert!(handler.in_gap());
+ 		assert_eq!(handler.get_missing(), Some((3, 5)));
+ 		for message in client.request(3, 5).unwrap() {
+ 			handler.on_incremental(message);
+ 		}
+ 		assert!(!handler.in_gap());
+ 		assert_eq!(handler.get_missing(), None);
+ 		assert_eq!(handler.get_last_seq(), 6);
+ 		assert_eq!(handler.get_book(""), reference.get_book(""));
+ 	}
+
+ 	#[test]
+ 	fn test_simulated_clock() {
+ 		// 2026-01-02 12:00:00.000 UTC
+ 		let start = 1767355200000;
+ 		let run = || {
+ 			let clock = SimulatedClock::new(start);
+ 			let capture = CapturePublisher::new();
+ 			let (report_tx, report_rx) = channel();
+ 			let mut match_eng = MatchingEngine::with_publisher(Box::new(capture.clone()));
+ 			match_eng.set_clock(Box::new(clock.clone()));
+ 			match_eng.set_report_channel(&report_tx);
+ 			match_eng.insert(&Order::new(2, 10, '1')).unwrap();
+ 			clock.advance(1500);
+ 			match_eng.insert(&Order::new(1, 10, '2')).unwrap();
+ 			clock.advance(60000);
+ 			match_eng.insert(&Order::new(1, 10, '2')).unwrap();
+ 			(capture.get_events(), report_rx.try_iter().collect::<Vec<ExecutionReport>>())
+ 		};
+ 		let (events, reports) = run();
+ 		// Same events and Execution Reports (with the same time stamps) in every run
+ 		assert_eq!(run(), (events.clone(), reports.clone()));
+
+ 		let mut trade_times = Vec::new();
+ 		let mut bars = Vec::new();
+ 		for event in &events {
+ 			match event {
+ 				MarketDataEvent::Incremental(message) if message.get_action() == 'A' => {
+ 					assert_eq!(message.get_orders()[0].get_transact_time(), "20260102-12:00:00.000");
+ 				}
+ 				MarketDataEvent::Trade(trade) => trade_times.push(trade.get_transact_time()),
+ 				MarketDataEvent::Bar(bar) => bars.push(bar.clone()),
+ 				_ => {}
+ 			}
+ 		}
+ 		assert_eq!(trade_times, vec!["20260102-12:00:01.500", "20260102-12:01:01.500"]);
+ 		assert_eq!(bars, vec![Bar::new("", 60, "20260102-12:00:00.000", 10, 1)]);
+ 		let report_times: Vec<String> = reports.iter().map(|report| report.get_transact_time()).collect();
+ 		assert_eq!(report_times, vec!["20260102-12:00:00.000", "20260102-12:00:01.500", "20260102-12:00:01.500",
+ 			"20260102-12:00:01.500", "20260102-12:01:01.500", "20260102-12:01:01.500", "20260102-12:01:01.500"]);
+
+ 		// FIX messages stamped (SendingTime) with the clock of the matching engine
+ 		let clock = SimulatedClock::new(start);
+ 		let (tx, _rx) = channel();
+ 		let mut match_eng = MatchingEngine::new(&tx);
+ 		match_eng.set_clock(Box::new(clock.clone()));
+ 		let mut session = FixSession::new("GHEX", &Arc::new(Mutex::new(OrderEntryGateway::new(match_eng))));
+ 		clock.advance(2000);
+ 		let mut logon = FixMessage::new("A");
+ 		for &(tag, value) in &[(49, "CLIENT"), (56, "GHEX"), (34, "1"), (52, "20260102-12:00:02.000"), (98, "0"), (108, "30")] {
+ 			logon.set_field(tag, value);
+ 		}
+ 		let reply = FixMessage::decode(&session.on_message(logon)[0]).unwrap();
+ 		assert_eq!((reply.get_msg_type(), reply.get_field(52)), ("A".to_string(), Some("20260102-12:00:02.000".to_string())));
+
+ 		let clock = SimulatedClock::new(start);
+ 		clock.set(999);
+ 		assert_eq!(clock.now_ms(), 999);
+ 		assert_eq!(clock.transact_time(), "19700101-00:00:00.999");
+ 		assert_eq!(utc_timestamp(start + 43199999), "20260102-23:59:59.999");
+ 	}
+
+ 	#[test]
+ 	fn test_priority_sequence() {
+ 		// ALL orders stamped at the same time: ONLY the priority sequence ranks them
+ 		let clock = SimulatedClock::new(0);
+ 		let capture = CapturePublisher::new();
+ 		let mut match_eng = MatchingEngine::with_publisher(Box::new(capture.clone()));
+ 		match_eng.set_clock(Box::new(clock.clone()));
+ 		let (first, _) = match_eng.insert(&Order::new(1, 10, '1')).unwrap();
+ 		let (second, _) = match_eng.insert(&Order::new(2, 10, '1')).unwrap();
+ 		let (third, _) = match_eng.insert(&Order::new(1, 10, '1')).unwrap();
+ 		assert_eq!((first.get_priority(), second.get_priority(), third.get_priority()), (1, 2, 3));
+ 		assert!(second.get_transact_time() == first.get_transact_time() && first > second && second > third);
+
+ 		// Quantity increased -> priority lost (new sequence), quantity decreased -> priority kept
+ 		match_eng.update(&first.get_id(), &Order::new(3, 10, '1')).unwrap();
+ 		match_eng.update(&third.get_id(), &Order::new(1, 10, '1')).unwrap();
+ 		// (orders at the price, as published after the last update)
+ 		let last_message = || capture.get_events().into_iter().rev().find_map(|event| match event {
+ 			MarketDataEvent::Incremental(message) => Some(message),
+ 			_ => None,
+ 		}).unwrap();
+ 		let orders = last_message().get_orders();
+ 		let ids: Vec<String> = orders.iter().map(|order| order.get_id()).collect();
+ 		assert_eq!(ids, vec![second.get_id(), third.get_id(), first.get_id()]);
+ 		let priorities: Vec<i64> = orders.iter().map(|order| order.get_priority()).collect();
+ 		assert_eq!(priorities, vec![2, 3, 4]);
+ 		// Ord ranks the orders as the order book does
+ 		let mut sorted = orders.clone();
+ 		sorted.reverse();
+ 		sorted.sort_by(|a, b| b.cmp(a));
+ 		assert_eq!(sorted, orders);
+
+ 		// Sell 10x2 -> second buy (HIGHEST priority) matched first
+ 		let (_, trades) = match_eng.insert(&Order::new(2, 10, '2')).unwrap();
+ 		assert_eq!(trades.len(), 1);
+ 		assert_eq!(trades[0].get_passive_id(), second.get_id());
+
+ 		// Priority sequences carried by the feeds (JSON and binary)
+ 		let last = last_message();
+ 		let priorities: Vec<i64> = last.get_orders().iter().map(|order| order.get_priority()).collect();
+ 		assert_eq!(priorities, vec![3, 4]);
+ 		for encoding in &[FeedEncoding::Json, FeedEncoding::Binary] {
+ 			let decoded = decode_incremental(&encode_incremental(&last, *encoding), *encoding).unwrap();
+ 			assert_eq!(decoded.get_orders(), last.get_orders());
+ 		}
+ 		// Orders without priority sequence (older feeds) -> NOT assigned
+ 		let order: Order = ::serde_json::from_str("{\"id\":\"1\",\"symbol\":\"\",\"order_qty\":1,\"price\":10,\"side\":\"1\",\
+ 			\"transact_time\":\"\",\"ord_type\":\"2\",\"time_in_force\":\"0\"}").unwrap();
+ 		assert_eq!(order.get_priority(), 0);
+ 	}
+
+ 	#[test]
+ 	fn test_stop_orders() {
+ 		let capture = CapturePublisher::new();
+ 		let (report_tx, report_rx) = channel();
+ 		let mut match_eng = MatchingEngine::with_publisher(Box::new(capture.clone()));
+ 		match_eng.set_report_channel(&report_tx);
+ 		let stop = |qty: i64, price: i64, side: char, ord_type: char, stop_px: i64| {
+ 			let mut order = Order::new(qty, price, side);
+ 			order.set_ord_type(ord_type);
+ 			order.set_stop_px(stop_px);
+ 			order
+ 		};
+ 		// (exec type, order ID) of the reports received so far
+ 		let reports = || report_rx.try_iter().map(|report| (report.get_exec_type(), report.get_order_id())).collect::<Vec<(char, String)>>();
+ 		for &(qty, price) in &[(1, 10), (1, 11), (2, 12)] {
+ 			match_eng.insert(&Order::new(qty, price, '2')).unwrap();
+ 		}
+ 		let events = capture.get_events().len();
+
+ 		// Stop orders wait in the trigger book, NOT on the market data feeds
+ 		let (buy_stop, trades) = match_eng.insert(&stop(1, 0, '1', '3', 11)).unwrap();
+ 		assert!(trades.is_empty());
+ 		let (buy_stop_limit, _) = match_eng.insert(&stop(1, 11, '1', '4', 10)).unwrap();
+ 		let (sell_stop, _) = match_eng.insert(&stop(1, 0, '2', '3', 5)).unwrap();
+ 		assert_eq!(capture.get_events().len(), events);
+ 		assert_eq!(match_eng.get_stop_orders("").len(), 3);
+ 		assert_eq!(match_eng.find_order_by_id(&buy_stop.get_id()).unwrap().get_stop_px(), 11);
+ 		reports();
+
+ 		// Buy 10x1 trades at 10 -> STOP LIMIT elected (buys at 11) -> STOP elected (buys at 12)
+ 		let (buy, trades) = match_eng.insert(&Order::new(1, 10, '1')).unwrap();
+ 		let fills: Vec<(String, i64)> = trades.iter().map(|trade| (trade.get_aggressor_id(), trade.get_price())).collect();
+ 		assert_eq!(fills, vec![(buy.get_id(), 10), (buy_stop_limit.get_id(), 11), (buy_stop.get_id(), 12)]);
+ 		let triggered: Vec<String> = reports().into_iter().filter(|report| report.0 == 'L').map(|report| report.1).collect();
+ 		assert_eq!(triggered, vec![buy_stop_limit.get_id(), buy_stop.get_id()]);
+ 		assert_eq!(match_eng.get_stop_orders(""), vec![sell_stop.clone()]);
+
+ 		// Cancel the stop order waiting
+ 		match_eng.delete(&sell_stop.get_id()).unwrap();
+ 		assert_eq!(reports(), vec![('4', sell_stop.get_id())]);
+ 		assert!(match_eng.get_stop_orders("").is_empty());
+
+ 		// 2 stops elected by ONE trade -> elected in priority order (whatever their stop price)
+ 		match_eng.insert(&Order::new(3, 14, '2')).unwrap();
+ 		let (first, _) = match_eng.insert(&stop(1, 14, '1', '4', 14)).unwrap();
+ 		let (second, _) = match_eng.insert(&stop(1, 0, '1', '3', 13)).unwrap();
+ 		reports();
+ 		let (_, trades) = match_eng.insert(&Order::new(2, 14, '1')).unwrap();
+ 		let fills: Vec<i64> = trades.iter().map(|trade| trade.get_price()).collect();
+ 		assert_eq!(fills, vec![12, 14, 14, 14]);
+ 		assert_eq!((trades[2].get_aggressor_id(), trades[3].get_aggressor_id()), (first.get_id(), second.get_id()));
+ 		let triggered: Vec<String> = reports().into_iter().filter(|report| report.0 == 'L').map(|report| report.1).collect();
+ 		assert_eq!(triggered, vec![first.get_id(), second.get_id()]);
+
+ 		// Stop price already reached (last trade at 14) -> elected at once, on entry or on replace
+ 		let (sell_stop_limit, trades) = match_eng.insert(&stop(1, 20, '2', '4', 15)).unwrap();
+ 		assert!(trades.is_empty());
+ 		assert_eq!((sell_stop_limit.get_ord_type(), sell_stop_limit.get_qty()), ('2', 1));
+ 		assert_eq!(reports(), vec![('0', sell_stop_limit.get_id()), ('L', sell_stop_limit.get_id())]);
+ 		let (late_stop, _) = match_eng.insert(&stop(1, 0, '1', '3', 30)).unwrap();
+ 		let mut replace = Order::new(1, 0, '1');
+ 		replace.set_stop_px(14);
+ 		let trades = match_eng.update(&late_stop.get_id(), &replace).unwrap();
+ 		assert_eq!((trades[0].get_aggressor_id(), trades[0].get_passive_id()), (late_stop.get_id(), sell_stop_limit.get_id()));
+
+ 		// Invalid stops -> rejected
+ 		assert_eq!(match_eng.insert(&stop(1, 0, '1', '3', 0)), Err(ExchangeError::NonPositiveStopPrice(0)));
+ 		assert_eq!(match_eng.insert(&stop(1, 0, '1', '4', 10)), Err(ExchangeError::NonPositivePrice(0)));
+ 		assert_eq!(match_eng.insert(&stop(1, 10, '1', '5', 10)), Err(ExchangeError::InvalidOrderType('5')));
+ 		let request: OrderEntryRequest = ::serde_json::from_str("{\"msg_type\":\"D\",\"symbol\":\"AAA\",\"side\":\"1\",\
+ 			\"ord_type\":\"4\",\"price\":12,\"stop_px\":11,\"qty\":5}").unwrap();
+ 		assert_eq!(request.to_order().get_stop_px(), 11);
+ 	}
+
+ 	#[test]
+ 	fn test_iceberg_orders() {
+ 		let capture = CapturePublisher::new();
+ 		let mut match_eng = MatchingEngine::with_publisher(Box::new(capture.clone()));
+ 		let iceberg = |qty: i64, price: i64, display_qty: i64| {
+ 			let mut order = Order::new(qty, price, '2');
+ 			order.set_display_qty(display_qty);
+ 			order
+ 		};
+ 		let (hidden, _) = match_eng.insert(&iceberg(10, 10, 3)).unwrap();
+ 		let (plain, _) = match_eng.insert(&Order::new(2, 10, '2')).unwrap();
+ 		assert_eq!((hidden.get_qty(), hidden.get_visible_qty()), (10, 3));
+ 
+ 		// Only the displayed slice is on the feeds (MBO and MBP)
+ 		let level_qty = |capture: &CapturePublisher| capture.get_events().iter().rev().find_map(|event| match *event {
+ 			MarketDataEvent::PriceLevel(ref message) => Some(message.get_level().get_qty()),
+ 			_ => None,
+ 		});
+ 		assert_eq!(level_qty(&capture), Some(5));
+ 		let incrementals = |capture: &CapturePublisher, from: usize| capture.get_events()[from..].iter().filter_map(|event| match *event {
+ 			MarketDataEvent::Incremental(ref message) => Some(message.clone()),
+ 			_ => None,
+ 		}).collect::<Vec<IncrementalMessage>>();
+ 		for message in incrementals(&capture, 0) {
+ 			for order in message.get_orders() {
+ 				assert_eq!(order.get_display_qty(), 0);
+ 				assert!(order.get_id() != hidden.get_id() || order.get_qty() == 3);
+ 			}
+ 		}
+ 
+ 		// Buy 10x4 -> slice exhausted (3), replenished at the rear of the queue, then the next order matched (1)
+ 		let events = capture.get_events().len();
+ 		let (_, trades) = match_eng.insert(&Order::new(4, 10, '1')).unwrap();
+ 		let fills: Vec<(String, i64)> = trades.iter().map(|trade| (trade.get_passive_id(), trade.get_qty())).collect();
+ 		assert_eq!(fills, vec![(hidden.get_id(), 3), (plain.get_id(), 1)]);
+ 		let actions: Vec<(char, String, i64)> = incrementals(&capture, events).iter()
+ 			.map(|message| (message.get_action(), message.get_order_id(), message.get_qty())).collect();
+ 		assert_eq!(actions, vec![('E', hidden.get_id(), 3), ('A', hidden.get_id(), 3), ('E', plain.get_id(), 1)]);
+ 		let resting = match_eng.find_order_by_id(&hidden.get_id()).unwrap();
+ 		assert_eq!((resting.get_qty(), resting.get_visible_qty()), (7, 3));
+ 		assert!(resting.get_priority() > plain.get_priority());
+ 		assert_eq!(level_qty(&capture), Some(4));
+ 
+ 		// FOK buy 10x8 -> matched through the hidden reserve (1 + 3 + 3 + 1)
+ 		let mut fok = Order::new(8, 10, '1');
+ 		fok.set_time_in_force('4');
+ 		let (_, trades) = match_eng.insert(&fok).unwrap();
+ 		let fills: Vec<i64> = trades.iter().map(|trade| trade.get_qty()).collect();
+ 		assert_eq!(fills, vec![1, 3, 3, 1]);
+ 		assert!(match_eng.find_order_by_id(&hidden.get_id()).is_err());
+ 		assert_eq!(level_qty(&capture), Some(0));
+ 
+ 		// Decrease qty below the displayed slice -> slice reduced as well
+ 		let (other, _) = match_eng.insert(&iceberg(10, 11, 4)).unwrap();
+ 		match_eng.update(&other.get_id(), &Order::new(3, 11, '2')).unwrap();
+ 		let resting = match_eng.find_order_by_id(&other.get_id()).unwrap();
+ 		assert_eq!((resting.get_qty(), resting.get_visible_qty(), resting.get_display_qty()), (3, 3, 4));
+ 
+ 		// Invalid display quantities -> rejected
+ 		assert_eq!(match_eng.insert(&iceberg(10, 10, 11)), Err(ExchangeError::InvalidDisplayQuantity(11)));
+ 		assert_eq!(match_eng.insert(&iceberg(10, 10, -1)), Err(ExchangeError::InvalidDisplayQuantity(-1)));
+ 		let request: OrderEntryRequest = ::serde_json::from_str("{\"msg_type\":\"D\",\"symbol\":\"AAA\",\"side\":\"2\",\
+ 			\"price\":10,\"qty\":100,\"display_qty\":10}").unwrap();
+ 		assert_eq!(request.to_order().get_display_qty(), 10);
+ 	}
+ 
+ 	#[test]
+ 	fn test_post_only_and_hidden_orders() {
+ 		let capture = CapturePublisher::new();
+ 		let mut match_eng = MatchingEngine::with_publisher(Box::new(capture.clone()));
+ 		let limit = |qty: i64, price: i64, side: char, post_only: char, hidden: bool| {
+ 			let mut order = Order::new(qty, price, side);
+ 			order.set_post_only(post_only);
+ 			order.set_hidden(hidden);
+ 			order
+ 		};
+ 		let (hidden, _) = match_eng.insert(&limit(5, 10, '2', '0', true)).unwrap();
+ 		let (shown, _) = match_eng.insert(&limit(2, 10, '2', '0', false)).unwrap();
+ 
+ 		// The hidden order never appears on the feeds, the best offer only shows the displayed order
+ 		let ask = |capture: &CapturePublisher| capture.get_events().iter().rev().find_map(|event| match *event {
+ 			MarketDataEvent::Quote(ref message) => Some((message.get_ask_price(), message.get_ask_qty())),
+ 			_ => None,
+ 		});
+ 		assert_eq!(ask(&capture), Some((10, 2)));
+ 
+ 		// Buy 10x3 -> displayed order matched first (although it was entered later), then the hidden order
+ 		let (_, trades) = match_eng.insert(&Order::new(3, 10, '1')).unwrap();
+ 		let fills: Vec<(String, i64)> = trades.iter().map(|trade| (trade.get_passive_id(), trade.get_qty())).collect();
+ 		assert_eq!(fills, vec![(shown.get_id(), 2), (hidden.get_id(), 1)]);
+ 		assert_eq!(match_eng.find_order_by_id(&hidden.get_id()).unwrap().get_qty(), 4);
+ 		assert_eq!(ask(&capture), Some((0, 0)));
+ 		for event in capture.get_events() {
+ 			if let MarketDataEvent::Incremental(ref message) = event {
+ 				assert!(message.get_order_id() != hidden.get_id());
+ 				assert!(message.get_orders().iter().all(|order| order.get_id() != hidden.get_id()));
+ 			}
+ 		}
+ 
+ 		// Post-only buy crossing the hidden order -> rejected, or repriced one tick below it
+ 		assert_eq!(match_eng.insert(&limit(1, 10, '1', '1', false)), Err(ExchangeError::PostOnlyWouldCross(10)));
+ 		let (repriced, trades) = match_eng.insert(&limit(1, 12, '1', '2', false)).unwrap();
+ 		assert!(trades.is_empty());
+ 		assert_eq!(match_eng.find_order_by_id(&repriced.get_id()).unwrap().get_price(), 9);
+ 
+ 		// Post-only buy NOT crossing -> rests, replaced to a crossing price -> rejected (NOT touched)
+ 		let (maker, _) = match_eng.insert(&limit(1, 8, '1', '1', false)).unwrap();
+ 		assert_eq!(match_eng.update(&maker.get_id(), &Order::new(1, 11, '1')), Err(ExchangeError::PostOnlyWouldCross(11)));
+ 		assert_eq!(match_eng.find_order_by_id(&maker.get_id()).unwrap().get_price(), 8);
+ 		assert_eq!(match_eng.find_order_by_id(&hidden.get_id()).unwrap().get_qty(), 4);
+ 
+ 		// Invalid post-only/hidden orders -> rejected
+ 		let mut market = limit(1, 0, '1', '1', false);
+ 		market.set_ord_type('1');
+ 		assert_eq!(match_eng.insert(&market), Err(ExchangeError::InvalidPostOnly('1')));
+ 		assert_eq!(match_eng.insert(&limit(1, 5, '1', 'x', false)), Err(ExchangeError::InvalidPostOnly('x')));
+ 		let mut hidden_iceberg = limit(5, 5, '1', '0', true);
+ 		hidden_iceberg.set_display_qty(2);
+ 		assert_eq!(match_eng.insert(&hidden_iceberg), Err(ExchangeError::InvalidDisplayQuantity(2)));
+ 		let request: OrderEntryRequest = ::serde_json::from_str("{\"msg_type\":\"D\",\"symbol\":\"AAA\",\"side\":\"1\",\
+ 			\"price\":10,\"qty\":5,\"post_only\":\"2\",\"hidden\":true}").unwrap();
+ 		assert_eq!((request.to_order().get_post_only(), request.to_order().is_hidden()), ('2', true));
+ 	}
+ 
+ 	#[test]
+ 	fn test_self_trade_prevention() {
+ 		let order = |qty: i64, side: char, account: &str| {
+ 			let mut order = Order::new(qty, 10, side);
+ 			order.set_account(account);
+ 			order
+ 		};
+ 		// Sell 10x2 (account A), sell 10x1 (account B), then buy 10xqty (account A)
+ 		let scenario = |mode: SelfTradePrevention, qty: i64| {
+ 			let (report_tx, report_rx) = channel();
+ 			let mut match_eng = MatchingEngine::with_publisher(Box::new(CapturePublisher::new()));
+ 			match_eng.set_report_channel(&report_tx);
+ 			match_eng.set_self_trade_prevention(mode);
+ 			let (own, _) = match_eng.insert(&order(2, '2', "A")).unwrap();
+ 			let (other, _) = match_eng.insert(&order(1, '2', "B")).unwrap();
+ 			report_rx.try_iter().count();
+ 			let (buy, trades) = match_eng.insert(&order(qty, '1', "A")).unwrap();
+ 			let fills: Vec<String> = trades.iter().map(|trade| trade.get_passive_id()).collect();
+ 			// (exec type, order ID, leaves qty) of the reports other than NEW and TRADE reports
+ 			let reports: Vec<(char, String, i64)> = report_rx.try_iter()
+ 				.filter(|report| report.get_exec_type() != '0' && report.get_exec_type() != 'F')
+ 				.map(|report| (report.get_exec_type(), report.get_order_id(), report.get_leaves_qty())).collect();
+ 			let resting = |order: &Order| match_eng.find_order_by_id(&order.get_id()).map(|order| order.get_qty()).unwrap_or(0);
+ 			assert!(fills.iter().all(|id| *id != own.get_id() || mode == SelfTradePrevention::None));
+ 			(fills.len(), reports, (resting(&own), resting(&other), resting(&buy)), (own.get_id(), buy.get_id()))
+ 		};
+ 
+ 		// None -> the orders of the same account trade
+ 		let (fills, reports, resting, _) = scenario(SelfTradePrevention::None, 3);
+ 		assert_eq!((fills, reports, resting), (2, vec![], (0, 0, 0)));
+ 
+ 		// Cancel newest -> the buy is cancelled (the sell of the other account is NOT reached)
+ 		let (fills, reports, resting, (_, buy)) = scenario(SelfTradePrevention::CancelNewest, 3);
+ 		assert_eq!((fills, reports, resting), (0, vec![('4', buy, 0)], (2, 1, 0)));
+ 
+ 		// Cancel oldest -> the sell of the same account is cancelled, the buy trades with the other account and rests
+ 		let (fills, reports, resting, (own, _)) = scenario(SelfTradePrevention::CancelOldest, 3);
+ 		assert_eq!((fills, reports, resting), (1, vec![('4', own, 0)], (0, 0, 2)));
+ 
+ 		// Cancel both -> both orders cancelled
+ 		let (fills, reports, resting, (own, buy)) = scenario(SelfTradePrevention::CancelBoth, 3);
+ 		assert_eq!((fills, reports, resting), (0, vec![('4', own, 0), ('4', buy, 0)], (0, 1, 0)));
+ 
+ 		// Decrement and cancel -> smaller order cancelled, larger one restated with the difference
+ 		let (fills, reports, resting, (own, buy)) = scenario(SelfTradePrevention::DecrementAndCancel, 3);
+ 		assert_eq!((fills, reports, resting), (1, vec![('4', own, 0), ('D', buy, 1)], (0, 0, 0)));
+ 		let (fills, reports, resting, (own, buy)) = scenario(SelfTradePrevention::DecrementAndCancel, 1);
+ 		assert_eq!((fills, reports, resting), (0, vec![('D', own, 1), ('4', buy, 0)], (1, 1, 0)));
+ 
+ 			// FOK buy covered only with the liquidity of its own account -> killed without any trade
+ 			for &mode in &[SelfTradePrevention::CancelOldest, SelfTradePrevention::CancelNewest, SelfTradePrevention::DecrementAndCancel] {
+ 				let mut match_eng = MatchingEngine::with_publisher(Box::new(CapturePublisher::new()));
+ 				match_eng.set_self_trade_prevention(mode);
+ 				match_eng.insert(&order(1, '2', "B")).unwrap();
+ 				let (own, _) = match_eng.insert(&order(2, '2', "A")).unwrap();
+ 				match_eng.insert(&order(1, '2', "B")).unwrap();
+ 				let mut fok = order(3, '1', "A");
+ 				fok.set_time_in_force('4');
+ 				let (fok, trades) = match_eng.insert(&fok).unwrap();
+ 				assert!(trades.is_empty());
+ 				assert_eq!(fok.get_qty(), 3);
+ 				assert_eq!(match_eng.find_order_by_id(&own.get_id()).unwrap().get_qty(), 2);
+ 			}
+ 			// Enough liquidity of other accounts -> fully matched (the order of its own account is cancelled)
+ 			let mut match_eng = MatchingEngine::with_publisher(Box::new(CapturePublisher::new()));
+ 			match_eng.set_self_trade_prevention(SelfTradePrevention::CancelOldest);
+ 			match_eng.insert(&order(2, '2', "A")).unwrap();
+ 			match_eng.insert(&order(3, '2', "B")).unwrap();
+ 			let mut fok = order(3, '1', "A");
+ 			fok.set_time_in_force('4');
+ 			assert_eq!(match_eng.insert(&fok).unwrap().1.iter().map(|trade| trade.get_qty()).sum::<i64>(), 3);
+ 
+ 		// Orders without account never trade against themselves
+ 		let mut match_eng = MatchingEngine::with_publisher(Box::new(CapturePublisher::new()));
+ 		match_eng.set_self_trade_prevention(SelfTradePrevention::CancelBoth);
+ 		match_eng.insert(&order(1, '2', "")).unwrap();
+ 		assert_eq!(match_eng.insert(&order(1, '1', "")).unwrap().1.len(), 1);
+ 
+ 		// The cancellation of the resting order is reported to the session which entered it
+ 		let mut match_eng = MatchingEngine::with_publisher(Box::new(CapturePublisher::new()));
+ 		match_eng.set_self_trade_prevention(SelfTradePrevention::CancelOldest);
+ 		let mut gateway = OrderEntryGateway::new(match_eng);
+ 		let (first_tx, first_rx) = channel();
+ 		let (second_tx, second_rx) = channel();
+ 		let first = gateway.open_session(&first_tx);
+ 		let second = gateway.open_session(&second_tx);
+ 		gateway.handle(first, &OrderEntryRequest::new_order(&order(2, '2', "A"))).unwrap();
+ 		let sell_id = first_rx.try_recv().unwrap().get_order_id();
+ 		gateway.handle(second, &OrderEntryRequest::new_order(&order(3, '1', "A"))).unwrap();
+ 		let cancelled = first_rx.try_recv().unwrap();
+ 		assert_eq!((cancelled.get_exec_type(), cancelled.get_order_id()), ('4', sell_id));
+ 		assert!(second_rx.try_iter().all(|report| report.get_exec_type() == '0'));
+ 
+ 		// Self-trade prevention setting
+ 		assert_eq!(Config::from_toml("self_trade_prevention = \"cancel_both\"").unwrap().get_self_trade_prevention(), SelfTradePrevention::CancelBoth);
+ 		assert!(Config::from_toml("self_trade_prevention = \"cancel\"").is_err());
+ 		assert_eq!(Config::default().get_self_trade_prevention(), SelfTradePrevention::None);
+ 	}
+ 
  	// #[test]
  	// fn test_update_price() {
  	// 	// insert order
  	// 	let mut match_eng = MatchingEngine::new();
  	// 	let old_order = Order::new(100, 1000, '2');
- 	// 	let old_order_after = match_eng.insert(&old_order);
+ 	// 	let old_order_after = match_eng.insert(&old_order).unwrap();
 
  	// 	// update price of inserted order
  	// 	let old_id = old_order_after.get_id();
@@ -701,7 +2147,7 @@ mod tests {
 
  	// 	// check that order was deleted
  	// 	// if order not found, find_order_by_id() returns dummy order with qty = -1
- 	// 	assert_eq!(match_eng.find_order_by_id(&order_id).get_qty(), -1);
+ 	// 	assert_eq!(match_eng.find_order_by_id(&order_id).unwrap().get_qty(), -1);
  	// }
  	
 }
\ No newline at end of file
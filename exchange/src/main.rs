@@ -1,229 +1,394 @@
-/**
-	MAIN
+/*!
+    MAIN
 
-	This is a main program that runs the exchange. This program contains 2 threads:
-		- 1 thread to multicast recovery feed
-		- 1 (main) thread to allow users to perform operations on the exchange (INSERT, DELETE, UPDATE)
+    This is a main program that runs the exchange. This program contains 3 threads (at least):
+        - 1 thread to multicast recovery feed
+        - 1 thread to accept connections of order entry clients (plus 1 thread per client connected)
+        - 1 thread to accept connections of FIX clients (plus 1 thread per FIX session)
+        - 1 (main) thread to allow users to perform operations on the exchange (INSERT, DELETE, UPDATE)
 */
 
-#[macro_use]
-extern crate serde_derive;
-extern crate serde;
+extern crate exchange;
 extern crate serde_json;
 
-use std::{io, thread, time};
+use std::{env, io, process, thread};
+use std::sync::{Arc, Mutex};
 use std::sync::mpsc::channel;
 use std::net::UdpSocket;
-use std::collections::HashMap;
-use objects::Order;
-use std::time::{Duration, SystemTime};
-mod objects;
-mod matching_engine;
+use std::time::Duration;
+use std::collections::{BTreeMap, HashMap};
+use exchange::objects;
+use exchange::objects::{Order, Trade, ExchangeError, OrderEntryRequest, RecoveryFeed, SnapshotFragment};
+use exchange::matching_engine::MatchingEngine;
+use exchange::gateway::{OrderEntryGateway, OrderEntryServer, CONSOLE_SESSION};
+use exchange::fix::FixAcceptor;
+use exchange::config::Config;
+use exchange::clock::{Clock, SystemClock};
+use exchange::market_data::{UdpPublisher, ChannelPublisher, FilePublisher, FaultySocket, ReplayBuffer, ReplayServer, encode_recovery, encode_fragment};
+
+/**
+    This function asks users to enter an input and returns it (without leading/trailing whitespaces)
+
+    @params
+        prompt: the message displayed to users
+*/
+fn read_input(prompt: &str) -> String {
+	println!("{}", prompt);
+	let mut input = String::new();
+	let _ = io::stdin().read_line(&mut input);
+	input.trim().to_string()
+}
+
+/**
+    This function asks users to enter a single character (e.g, side of an order)
+    and returns it ('*' if nothing was entered)
+
+    @params
+        prompt: the message displayed to users
+*/
+fn read_char(prompt: &str) -> char {
+	read_input(prompt).chars().next().unwrap_or('*')
+}
+
+/**
+    This function asks users to enter a number (e.g, price of an order) and returns it
 
-// IP address of the computer running main.rs (MUST specify the PORT)
-const SERVER_ADDRESS: &str ="0.0.0.0:21001";
-// IP address of the multicast group for Recovery Feed (MUST specify the PORT)
-const RECOVERY_MULTICAST_GROUP_ADDRESS: &str = "239.255.255.255:21003";
-// Standard period for a Recovery Feed to be sent (e.g, after (at least) 5 seconds since the last Recovery Feed, a new feed will be sent)
-const RECOVERY_PERIOD: u64 = 5;
+    @params
+        prompt: the message displayed to users
+    @return
+        - The number entered
+        - None if the input is not a number
+*/
+fn read_number(prompt: &str) -> Option<i64> {
+	let input = read_input(prompt);
+	match input.parse::<i64>() {
+		Ok(number) => Some(number),
+		Err(_) => {
+			println!("Invalid number '{}'!", input);
+			None
+		}
+	}
+}
 
 /**
-	This function asks users to enter neccessary information in an insertion request:
-		- Side of the order ('1' = BUY, '2' = SELL)
-		- Price of the order 
-		- Quantity of the order
+    This function asks users to enter neccessary information in an insertion request:
+        - Symbol of the instrument
+        - Account of the order
+        - Side of the order ('1' = BUY, '2' = SELL)
+        - Type of the order ('1' = MARKET, '2' = LIMIT, '3' = STOP, '4' = STOP LIMIT)
+        - Price of the order (LIMIT and STOP LIMIT orders only)
+        - Stop price of the order (STOP and STOP LIMIT orders only)
+        - Quantity of the order
+        - Display quantity of the order (LIMIT and STOP LIMIT orders only, 0 = NOT an ICEBERG order)
+        - Post-only instruction and hidden attribute of the order (LIMIT orders only)
+        - Time in force of the order ('0' = DAY, '1' = GTC, '3' = IOC, '4' = FOK)
 
-	* NOTE: User inputs are assumed to be in correct format/types.
+    * NOTE: Inputs which are not numbers cancel the request,
+      other invalid inputs are rejected by the Matching Engine.
 */
-fn insert_new_order(match_eng: &mut matching_engine::MatchingEngine) {
+fn insert_new_order(gateway: &Mutex<OrderEntryGateway>) {
 	// Ask user to enter new order
 	println!("Insert new order: ");
+	// Process user input for symbol of the instrument
+	let m_symbol = read_input("Symbol: ");
+	// Process user input for account of the order
+	let m_account = read_input("Account: (empty = none)");
 	// Process user input for side of the order
-	println!("Side: (1 = buy, 2 = sell)");
-	let mut m_side = String::new();
-	io::stdin().read_line(&mut m_side);
-	let m_side: char = m_side.chars().nth(0).unwrap();
-	// Process user input for price of the order
-	println!("Price: ");
-	let mut m_price = String::new();
-	io::stdin().read_line(&mut m_price);
-	let m_price = m_price.trim().parse::<i64>().unwrap();
+	let m_side = read_char("Side: (1 = buy, 2 = sell)");
+	// Process user input for type of the order
+	let m_ord_type = read_char("Order type: (1 = market, 2 = limit, 3 = stop, 4 = stop limit)");
+	// Process user input for price of the order (MARKET and STOP orders have no price)
+	let mut m_price: i64 = 0;
+	if m_ord_type == '2' || m_ord_type == '4' {
+		m_price = match read_number("Price: ") {
+			Some(price) => price,
+			None => return,
+		};
+	}
+	// Process user input for stop price of the order (STOP and STOP LIMIT orders only)
+	let mut m_stop_px: i64 = 0;
+	if m_ord_type == '3' || m_ord_type == '4' {
+		m_stop_px = match read_number("Stop price: ") {
+			Some(stop_px) => stop_px,
+			None => return,
+		};
+	}
 	// Process user input for quantity of the order
-	println!("Quantity: ");
-	let mut m_qty = String::new();
-	io::stdin().read_line(&mut m_qty);
-	let m_qty = m_qty.trim().parse::<i64>().unwrap();
-	// Call INSERT function in Matching Engine 
-	match_eng.insert(&objects::Order::new(m_qty, m_price, m_side));
+	let m_qty = match read_number("Quantity: ") {
+		Some(qty) => qty,
+		None => return,
+	};
+	// Process user input for display quantity of the order (only orders with a limit price can rest as ICEBERG orders)
+	let mut m_display_qty: i64 = 0;
+	if m_ord_type == '2' || m_ord_type == '4' {
+		m_display_qty = match read_number("Display quantity: (0 = all)") {
+			Some(display_qty) => display_qty,
+			None => return,
+		};
+	}
+	// Process user input for post-only instruction and hidden attribute of the order (LIMIT orders only)
+	let mut m_post_only = '0';
+	let mut m_hidden = false;
+	if m_ord_type == '2' {
+		m_post_only = read_char("Post only: (0 = no, 1 = reject, 2 = reprice)");
+		m_hidden = read_char("Hidden: (0 = no, 1 = yes)") == '1';
+	}
+	// Process user input for time in force of the order
+	let m_time_in_force = read_char("Time in force: (0 = day, 1 = GTC, 3 = IOC, 4 = FOK)");
+	// Create the new order
+	let mut new_order = Order::new(m_qty, m_price, m_side);
+	new_order.set_symbol(&m_symbol);
+	new_order.set_account(&m_account);
+	new_order.set_ord_type(m_ord_type);
+	new_order.set_stop_px(m_stop_px);
+	new_order.set_display_qty(m_display_qty);
+	new_order.set_post_only(m_post_only);
+	new_order.set_hidden(m_hidden);
+	new_order.set_time_in_force(m_time_in_force);
+	// Send INSERT request to Matching Engine
+	print_result(gateway.lock().unwrap().handle(CONSOLE_SESSION, &OrderEntryRequest::new_order(&new_order)));
 }
 
 /**
-	This function asks users to enter neccessary information in a deletion request:
-		- ID of the order to be deleted
-
-	* NOTE: User inputs are assumed to be in correct format/types.
+    This function asks users to enter neccessary information in a deletion request:
+        - ID of the order to be deleted
 */
-fn delete_existing_order(match_eng: &mut matching_engine::MatchingEngine) {
-	// Ask user to input order ID
-	println!("Enter order ID:");
+fn delete_existing_order(gateway: &Mutex<OrderEntryGateway>) {
 	// Process user input for ID of the order
-	let mut m_id = String::new();
-	io::stdin().read_line(&mut m_id);
-	let m_id = m_id.trim().to_string();
-	// Call DELETE function in Matching Engine
-	match_eng.delete(&m_id);
+	let m_id = read_input("Enter order ID:");
+	// Send DELETE request to Matching Engine
+	print_result(gateway.lock().unwrap().handle(CONSOLE_SESSION, &OrderEntryRequest::cancel(&m_id)));
 }
 
 /**
-	This function asks users to enter neccessary information in an update request:
-		- ID of the order to be updated
-		- New price for the order
-		- New quantity for the order
+    This function asks users to enter neccessary information in an update request:
+        - ID of the order to be updated
+        - New price for the order
+        - New quantity for the order
 
-	* NOTE: User inputs are assumed to be in correct format/types.
+    * NOTE: Inputs which are not numbers cancel the request,
+      other invalid inputs are rejected by the Matching Engine.
 */
-fn update_existing_order(match_eng: &mut matching_engine::MatchingEngine) {
+fn update_existing_order(gateway: &Mutex<OrderEntryGateway>) {
 	// Process user input for ID of the order
-	println!("Enter order ID:");
-	let mut m_id = String::new();
-	io::stdin().read_line(&mut m_id);
-	let m_id = m_id.trim().to_string();
+	let m_id = read_input("Enter order ID:");
 	// Process user input for price of the order
-	println!("Price: ");
-	let mut m_price = String::new();
-	io::stdin().read_line(&mut m_price);
-	let m_price = m_price.trim().parse::<i64>().unwrap();
+	let m_price = match read_number("Price: ") {
+		Some(price) => price,
+		None => return,
+	};
 	// Process user input for quantity of the order
-	println!("Quantity: ");
-	let mut m_qty = String::new();
-	io::stdin().read_line(&mut m_qty);
-	let m_qty = m_qty.trim().parse::<i64>().unwrap();
-	// Create a new order with quantity and price entered by user, 
-	// "DUMMY" value for side (which will be determined later by Matching Engine using order ID)
-	let mut new_order = objects::Order::new(m_qty, m_price, '*');
-	// Call UPDATE function in Matching Engine
-	match_eng.update(&m_id, &new_order);
+	let m_qty = match read_number("Quantity: ") {
+		Some(qty) => qty,
+		None => return,
+	};
+	// Send UPDATE request (with quantity and price entered by user) to Matching Engine
+	print_result(gateway.lock().unwrap().handle(CONSOLE_SESSION, &OrderEntryRequest::replace(&m_id, m_price, m_qty)));
 }
 
 /**
-	This function multicasts a recovery feed to RECOVERY_MULTICAST_GROUP_ADDRESS
+    This function displays the result of an operation
 
-	@params
-		state: the state to be published
-		socket: the socket for multicasting
+    @params
+        result: list of trades resulting from the operation (if any) or the reason the operation was rejected
 */
-fn publish_snaphot(state: String, socket: &UdpSocket) {
-	// Recovery feed must be converted to bytes for multicasting
-	socket.send_to(&state.into_bytes(), RECOVERY_MULTICAST_GROUP_ADDRESS);
+fn print_result(result: Result<Vec<Trade>, ExchangeError>) {
+	match result {
+		Ok(trades) => {
+			// Display the trades (if any)
+			for trade in trades {
+				println!("TRADE (ID: {}): {} at {} (aggressor ID: {}, passive ID: {})",
+					trade.get_trade_id(), trade.get_qty(), trade.get_price(), trade.get_aggressor_id(), trade.get_passive_id());
+			}
+		}
+		Err(reason) => println!("REJECTED: {}", reason),
+	}
+}
+
+/**
+    This function multicasts a recovery feed to the Recovery Feed multicast group,
+    in as many fragments (ONE per datagram) as needed
+
+    @params
+        rec_feed: the recovery feed to be published
+        snapshot_id: ID of the snapshot (ONE per recovery feed published)
+        socket: the socket for multicasting (injecting the faults of the Recovery Feed)
+        config: configuration of the exchange (multicast group, encoding and maximum payload of the Recovery Feed)
+*/
+fn publish_snaphot(rec_feed: &RecoveryFeed, snapshot_id: i64, socket: &FaultySocket, config: &Config) {
+	let state = encode_recovery(rec_feed, config.get_feed_encoding());
+	for fragment in SnapshotFragment::split(snapshot_id, rec_feed.get_last_msg(), &state, config.get_snapshot_max_payload()) {
+		socket.send_to(encode_fragment(&fragment), "recovery", &config.get_recovery_feed_group());
+	}
 }
 
 fn main() {
+	// Load the configuration (defaults, overridden by the configuration file, environment variables and command-line flags)
+	let args: Vec<String> = env::args().skip(1).collect();
+	let config = match Config::load(&args) {
+		Ok(config) => config,
+		Err(reason) => {
+			println!("Invalid configuration: {}", reason);
+			println!("Usage: exchange [--config <file>] [--<setting> <value>]...");
+			process::exit(1);
+		}
+	};
+
 	// Create channel for Matching Engine to communicate with Recovery Thread
 	// to receive Incremental Feed from Matching Engine to build up the latest state of Market Data
 	let (tx, rx) = channel();
 
-	// Start the Matching Engine
-	let mut match_eng = matching_engine::MatchingEngine::new(&tx);
+	// Start the Matching Engine, behind the order entry gateway shared by ALL sessions (console and network clients)
+	// Market data is multicasted (Incremental Feed, Trade Feed), sent to the Recovery Thread, kept for the replay server
+	// and recorded (if configured)
+	let mut match_eng = MatchingEngine::with_publisher(Box::new(UdpPublisher::new(&config).unwrap()));
+	match_eng.add_publisher(Box::new(ChannelPublisher::new(&tx)));
+	let replay_buffer = ReplayBuffer::new(config.get_replay_buffer_size());
+	match_eng.add_publisher(Box::new(replay_buffer.clone()));
+	match_eng.set_depth_levels(config.get_depth_levels());
+	match_eng.set_bar_intervals(&config.get_bar_intervals(), config.get_bar_history());
+	match_eng.set_self_trade_prevention(config.get_self_trade_prevention());
+	if !config.get_market_data_file().is_empty() {
+		match_eng.add_publisher(Box::new(FilePublisher::create(&config.get_market_data_file()).unwrap()));
+	}
+	let gateway = Arc::new(Mutex::new(OrderEntryGateway::new(match_eng)));
 	
-	/**
+	/*
 		Create and run a Recovery Thread
 		This thread receives Incremental Feed from Matching Engine to build up the latest state
 		of the Market Data and multicasts Recovery Feed (in a constant period of time)
 	*/
-	let snapshot_thread = thread::spawn(move || {
+	let recovery_config = config.clone();
+	thread::spawn(move || {
 		// Create UDP socket for mulcasting
-		let sock = UdpSocket::bind(SERVER_ADDRESS).unwrap();
-		// Create hashmaps (for SELL and BUY) to maintain current state of the Market Data
-		// (key = price, value = list of all orders at a specific price)
-		let mut sells_by_price: HashMap<i64, Vec<Order>> = HashMap::new();
-    	let mut buys_by_price: HashMap<i64, Vec<Order>> = HashMap::new();
-		// Combine ALL orders in one state vector to represent current Market Data by price
-		// (State vector sorted by increasing price)
-		let mut cur_state: Vec<Vec<Order>> = Vec::new();
+		let sock = UdpSocket::bind(recovery_config.multicast_bind_address(&recovery_config.get_recovery_bind_address())).unwrap();
+		sock.set_multicast_ttl_v4(recovery_config.get_multicast_ttl()).unwrap();
+		let faults = vec![("recovery".to_string(), recovery_config.get_recovery_feed_group(), recovery_config.get_faults("recovery"))];
+		let sock = FaultySocket::new(sock, &faults, recovery_config.get_fault_seed()).unwrap();
+		// snapshot_id: ID of the last recovery feed published
+		let mut snapshot_id: i64 = 0;
+		// Create ordered maps (for SELL and BUY) to maintain current state of the Market Data of every instrument
+		// (key = symbol, value = ordered map with key = price, value = list of all orders at a specific price)
+		let mut sells_by_price: HashMap<String, BTreeMap<i64, Vec<Order>>> = HashMap::new();
+		let mut buys_by_price: HashMap<String, BTreeMap<i64, Vec<Order>>> = HashMap::new();
 		// last_msg_index: Index of the last Incremental Feed received from Matching Engine (to be sent in Recovery Feed) 
 		let mut last_msg_index: i64 = 0;
 		// timer: Keep track of the time passed since the last Recovery Feed was multicasted
 		// (to ensure that after a constant period of time, a new Recovery Feed will be multicasted)
-		let mut timer = SystemTime::now();
+		let clock = SystemClock;
+		let mut timer = clock.now_ms();
 
-		/**
+		/*
 			Receive Incremental Feed from Matching Engine, build up the latest state and multicast
 		*/
 		loop {
-			/**
+			/*
 				MULTICAST (RECOVERY FEED)
 			*/
 			// Calculate the time from the last Recovery Feed till current time
-			let cur_time = SystemTime::now();
-			if cur_time.duration_since(timer).unwrap() >= Duration::new(RECOVERY_PERIOD, 0) {
-				// If the period of time is more than the Standard RECOVERY_PERIOD set, create a new Recovery Feed
-				let rec_feed = objects::RecoveryFeed::new(last_msg_index, cur_state.clone());
-				// Multicast the Recovery Feed (latest state of Market Data)
-				publish_snaphot(serde_json::to_string(&rec_feed).unwrap(), &sock);
+			if clock.now_ms() - timer >= recovery_config.get_recovery_period() as i64 * 1000 {
+				// If the period of time is more than the recovery period configured, create a new Recovery Feed
+				// for every instrument (an empty state is sent while there is no instrument)
+				if buys_by_price.is_empty() {
+					let rec_feed = RecoveryFeed::new("", last_msg_index, Vec::new());
+					snapshot_id += 1;
+					publish_snaphot(&rec_feed, snapshot_id, &sock, &recovery_config);
+				}
+				for (symbol, buys) in &buys_by_price {
+					// Combine ALL orders of the instrument in one state vector to represent current Market Data
+					// (BUY orders then SELL orders, with the prices increasing, HIGHEST priority first at every price)
+					let state: Vec<Vec<Order>> = buys.values().chain(sells_by_price[symbol].values()).map(|orders| {
+						let mut orders = orders.clone();
+						orders.sort_by(|a, b| b.cmp(a));
+						orders
+					}).collect();
+					let rec_feed = RecoveryFeed::new(symbol, last_msg_index, state);
+					// Multicast the Recovery Feed (latest state of Market Data)
+					snapshot_id += 1;
+					publish_snaphot(&rec_feed, snapshot_id, &sock, &recovery_config);
+				}
 				// Reset the timer (mark current time as when the last Recovery Feed was sent)
-				timer = SystemTime::now();
+				timer = clock.now_ms();
 			}
 
-			/**
+			/*
 				RECEIVE INCREMENTAL FEED
 				Wait to receive Incremental Feed from Matching Engine
 			*/
 			let msg = rx.try_recv();
-			match msg {
-				Ok(v) => {
-					/**
-						BUILD UP THE STATE
-						When receving Incremental Feed, build up the latest state
-					*/
-					// Convert JSON object received from Matching Engine to Incremental Message object
-					let val: objects::IncrementalMessage = serde_json::from_str(v.as_str()).unwrap();
-					
-					// Update state of Market Data
-					// * NOTE: After an operation, only a list of orders at ONE price is modified.
-					
-					// After the operation, list of orders at the price affected is non-empty
-					if (val.get_orders().len() > 0) {
-						// Determine list of orders from which side (BUY or SELL) was modified
-						let side = val.get_orders()[0].get_side();
-						// Overwrite list of orders at that price with updated list of orders received from Incremental Feed
-						if side == '1' {
-							// BUY side
-							buys_by_price.insert(val.get_price(), val.get_orders());
-						} else if side == '2' {
-							// SELL side
-							sells_by_price.insert(val.get_price(), val.get_orders());
-						}
-					} else {
-						// After the operation, list of orders at the price affected is empty
-						// Then remove list of orders at that price from BUY and SELL sides (if any)
-						buys_by_price.remove(&val.get_price());
-						sells_by_price.remove(&val.get_price());
-					}
-					// Update index of the last Incremental Feed received from Matching Engine
-					last_msg_index = val.get_num();
-
-					// Build up the state of ALL orders with the prices increasing
-					let mut state: Vec<Vec<Order>> = Vec::new();
-					let mut state_sells: Vec<Vec<Order>> = Vec::new();
-					for vector in sells_by_price.values() {
-						state_sells.push(vector.clone());
+			if let Ok(v) = msg {
+				/*
+					BUILD UP THE STATE
+					When receving Incremental Feed, build up the latest state
+				*/
+				// Convert JSON object received from Matching Engine to Incremental Message object
+				let val: objects::IncrementalMessage = serde_json::from_str(v.as_str()).unwrap();
+				
+				// Update state of Market Data of the instrument affected
+				// * NOTE: After an operation, only a list of orders at ONE price is modified.
+				let symbol = val.get_symbol();
+				let sells_by_price = sells_by_price.entry(symbol.clone()).or_default();
+				let buys_by_price = buys_by_price.entry(symbol).or_default();
+				
+				// After the operation, list of orders at the price affected is non-empty
+				if !val.get_orders().is_empty() {
+					// Determine list of orders from which side (BUY or SELL) was modified
+					let side = val.get_orders()[0].get_side();
+					// Overwrite list of orders at that price with updated list of orders received from Incremental Feed
+					if side == '1' {
+						// BUY side
+						buys_by_price.insert(val.get_price(), val.get_orders());
+					} else if side == '2' {
+						// SELL side
+						sells_by_price.insert(val.get_price(), val.get_orders());
 					}
-					state_sells.sort();
-					state_sells.reverse();
-					for vector in buys_by_price.values() {
-						state.push(vector.clone());
-					}
-					state.sort();
-					state.append(&mut state_sells);
-					// Update latest state of Market Data with the state built
-					cur_state = state;
+				} else {
+					// After the operation, list of orders at the price affected is empty
+					// Then remove list of orders at that price from BUY and SELL sides (if any)
+					buys_by_price.remove(&val.get_price());
+					sells_by_price.remove(&val.get_price());
 				}
-				Err(r) => {},
+				// Update index of the last Incremental Feed received from Matching Engine
+				last_msg_index = val.get_num();
 			}
 		}
 	});
 
-	/** 
+	/*
+		Create and run a Bar Thread
+		This thread publishes the OHLCV bars whose interval is over (every second), even if NO later trade happens
+	*/
+	let bar_gateway = gateway.clone();
+	thread::spawn(move || {
+		loop {
+			thread::sleep(Duration::from_secs(1));
+			bar_gateway.lock().unwrap().flush_bars();
+		}
+	});
+
+	/*
+		Start the order entry server
+		Clients connect to the order entry address (TCP) to send requests and receive Execution Reports
+	*/
+	let order_entry_server = OrderEntryServer::bind(&config.get_order_entry_address()).unwrap();
+	order_entry_server.start(&gateway);
+
+	/*
+		Start the replay server
+		Clients connect to the replay address (TCP) to get the recent Incremental messages they missed
+	*/
+	let replay_server = ReplayServer::bind(&config.get_replay_address()).unwrap();
+	replay_server.start(&replay_buffer);
+
+	/*
+		Start the FIX acceptor
+		FIX 4.4 clients connect to the FIX address (TCP) to send orders (NewOrderSingle, OrderCancelRequest,
+		OrderCancelReplaceRequest) and receive ExecutionReport/OrderCancelReject
+	*/
+	let fix_acceptor = FixAcceptor::bind(&config.get_fix_address(), &config.get_fix_comp_id()).unwrap();
+	fix_acceptor.start(&gateway);
+
+	/* 
 		User interface for users to send request (INSERT, DELETE, UPDATE)
 		and enter relevant information needed for the request
 
@@ -234,9 +399,9 @@ fn main() {
 		// Ask user when to stop
 		println!("Continue? (y/n) ");
 		let mut continue_cmd = String::new();
-		io::stdin().read_line(&mut continue_cmd);
+		let _ = io::stdin().read_line(&mut continue_cmd);
 		// The program will stop when user enters "n"
-		match &continue_cmd.trim() as &str {
+		match continue_cmd.trim() {
 			"y" => { },
 			"n" => break,
 			_ 	=> {
@@ -252,18 +417,18 @@ fn main() {
 		println!("Enter 1 option (1 or 2 or 3)");
 		// Receive option entered by user
 		let mut option_cmd = String::new();
-		io::stdin().read_line(&mut option_cmd);
+		let _ = io::stdin().read_line(&mut option_cmd);
 		// Call appropriate function with the option entered 
-		match &option_cmd.trim() as &str {
-			"1" => insert_new_order(&mut match_eng),
-			"2" => delete_existing_order(&mut match_eng),
-			"3" => update_existing_order(&mut match_eng),
+		match option_cmd.trim() {
+			"1" => insert_new_order(&gateway),
+			"2" => delete_existing_order(&gateway),
+			"3" => update_existing_order(&gateway),
 			_	=> {
 				println!("Invalid option!");
 				continue;
 			}
 		}
 		// Print out market status after every operation (for DEBUGGING)
-		match_eng.print_status();
+		gateway.lock().unwrap().print_status();
 	}
 }
\ No newline at end of file
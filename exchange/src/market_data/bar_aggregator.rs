@@ -0,0 +1,187 @@
+/*!
+    BAR AGGREGATOR
+
+    This contains all relevant properties and functions of the aggregator rolling trades into OHLCV bars,
+    ONE series of bars per instrument and interval.
+    Intervals are aligned on the UTC clock (e.g, a 60 second bar starts at the beginning of a minute),
+    a bar is completed by the first trade of a later interval, or by flush once its interval is over
+    (intervals without trades have NO bar). Only the most recent completed bars are kept (see history).
+*/
+extern crate time;
+
+use std::collections::{HashMap, VecDeque};
+use objects::{Bar, Trade};
+
+/**
+    A bar aggregator has the following properties:
+        - intervals:
+            lengths (in seconds) of the bars
+        - history:
+            maximum number of completed bars kept per instrument and interval (the oldest ones are dropped)
+        - current:
+            bar of the current interval of ALL instruments and intervals (NOT completed yet)
+        - completed:
+            most recent completed bars of ALL instruments and intervals (oldest first)
+*/
+#[derive(Clone, Debug, Default)]
+pub struct BarAggregator {
+    intervals: Vec<u64>,
+    history: usize,
+    /**
+        Key = (symbol, interval) -> value = (start of the interval in seconds, bar of the interval)
+    */
+    current: HashMap<(String, u64), (i64, Bar)>,
+    /**
+        Key = (symbol, interval) -> value = completed bars (at most history bars)
+    */
+    completed: HashMap<(String, u64), VecDeque<Bar>>,
+}
+
+impl BarAggregator {
+    /**
+        Constructor
+        @params
+            m_intervals: lengths (in seconds) of the bars (intervals of 0 seconds are ignored)
+            m_history: maximum number of completed bars kept per instrument and interval
+        @return
+            New bar aggregator without any bar
+    */
+    pub fn new(m_intervals: &[u64], m_history: usize) -> BarAggregator {
+        BarAggregator {
+            intervals: m_intervals.iter().cloned().filter(|&interval| interval > 0).collect(),
+            history: m_history,
+            current: HashMap::new(),
+            completed: HashMap::new(),
+        }
+    }
+
+    // Return lengths (in seconds) of the bars
+    pub fn get_intervals(&self) -> Vec<u64> {
+        self.intervals.clone()
+    }
+
+    /**
+        This function adds a trade to the bar of its interval, for ALL intervals
+
+        @params
+            trade: the trade (time stamped by the exchange)
+        @return
+            The bars completed by the trade (the trade is in a later interval than the current bar)
+    */
+    pub fn add_trade(&mut self, trade: &Trade) -> Vec<Bar> {
+        let seconds = match seconds_of(&trade.get_transact_time()) {
+            Some(seconds) => seconds,
+            None => return Vec::new(),
+        };
+        let mut bars = Vec::new();
+        for interval in self.intervals.clone() {
+            let start = seconds - seconds.rem_euclid(interval as i64);
+            let key = (trade.get_symbol(), interval);
+            // Trade in the current interval (or time stamped earlier)
+            if let Some(current) = self.current.get_mut(&key) {
+                if current.0 >= start {
+                    current.1.add_trade(trade.get_price(), trade.get_qty());
+                    continue;
+                }
+            }
+            // Trade in a later interval: the current bar is completed
+            let bar = Bar::new(&key.0, interval, &time_of(start), trade.get_price(), trade.get_qty());
+            if let Some((_, completed)) = self.current.insert(key.clone(), (start, bar)) {
+                self.complete(key, &completed);
+                bars.push(completed);
+            }
+        }
+        bars
+    }
+
+    /**
+        This function completes the bars whose interval is over, even if NO later trade happened
+        (e.g, called periodically, so that the last bar of a quiet instrument is published)
+
+        @params
+            now_ms: current time, in milliseconds since the epoch
+        @return
+            The bars completed (oldest first)
+    */
+    pub fn flush(&mut self, now_ms: i64) -> Vec<Bar> {
+        let seconds = now_ms.div_euclid(1000);
+        let mut over: Vec<(String, u64)> = self.current.iter()
+            .filter(|&(&(_, interval), &(start, _))| start + interval as i64 <= seconds)
+            .map(|(key, _)| key.clone())
+            .collect();
+        over.sort_by_key(|key| (self.current[key].0, key.1, key.0.clone()));
+        let mut bars = Vec::new();
+        for key in over {
+            let (_, completed) = self.current.remove(&key).unwrap();
+            self.complete(key, &completed);
+            bars.push(completed);
+        }
+        bars
+    }
+
+    /**
+        This function keeps a completed bar (dropping the oldest bar if history bars are kept already)
+
+        @params
+            key: (symbol, interval) of the bar
+            bar: the completed bar
+    */
+    fn complete(&mut self, key: (String, u64), bar: &Bar) {
+        if self.history == 0 {
+            return;
+        }
+        let completed = self.completed.entry(key).or_default();
+        if completed.len() == self.history {
+            completed.pop_front();
+        }
+        completed.push_back(bar.clone());
+    }
+
+    /**
+        This function gets the completed bars of an instrument
+
+        @params
+            symbol: symbol of the instrument
+            interval: length (in seconds) of the bars
+        @return
+            - The most recent completed bars (oldest first, at most history bars)
+            - Empty list if NO bar was completed yet
+    */
+    pub fn get_bars(&self, symbol: &str, interval: u64) -> Vec<Bar> {
+        self.completed.get(&(symbol.to_string(), interval)).map(|bars| bars.iter().cloned().collect()).unwrap_or_default()
+    }
+
+    /**
+        This function gets the bar of the current interval of an instrument
+
+        @params
+            symbol: symbol of the instrument
+            interval: length (in seconds) of the bar
+        @return
+            - The bar (NOT completed yet)
+            - None if there was NO trade of the instrument yet
+    */
+    pub fn get_current_bar(&self, symbol: &str, interval: u64) -> Option<Bar> {
+        self.current.get(&(symbol.to_string(), interval)).map(|current| current.1.clone())
+    }
+}
+
+/**
+    This function converts a time stamp (UTC format: YYYYMMDD-HH:MM:SS.sss) to seconds since the epoch
+
+    @params
+        transact_time: the time stamp (milliseconds are ignored)
+    @return
+        - Number of seconds
+        - None if the time stamp is invalid
+*/
+fn seconds_of(transact_time: &str) -> Option<i64> {
+    let seconds = transact_time.get(..17)?;
+    time::strptime(seconds, "%Y%m%d-%H:%M:%S").ok().map(|tm| tm.to_timespec().sec)
+}
+
+// Return time stamp (UTC format: YYYYMMDD-HH:MM:SS.sss) of a number of seconds since the epoch
+fn time_of(seconds: i64) -> String {
+    let tm = time::at_utc(time::Timespec::new(seconds, 0));
+    format!("{}.000", tm.strftime("%Y%m%d-%H:%M:%S").unwrap())
+}
@@ -0,0 +1,48 @@
+/*!
+    CAPTURE PUBLISHER
+
+    This contains all relevant properties and functions of the publisher keeping ALL events in memory
+    (e.g, for tests to check the market data of the matching engine)
+*/
+
+use std::sync::{Arc, Mutex};
+use market_data::MarketDataPublisher;
+use objects::MarketDataEvent;
+
+/**
+    A capture publisher has the following properties:
+        - events:
+            ALL events published, in the order they were published
+            (shared by ALL clones of the publisher, so that the events can be read while the matching engine owns it)
+*/
+#[derive(Clone, Default)]
+pub struct CapturePublisher {
+    events: Arc<Mutex<Vec<MarketDataEvent>>>,
+}
+
+impl CapturePublisher {
+    /**
+        Constructor
+        @return
+            New capture publisher without any event
+    */
+    pub fn new() -> CapturePublisher {
+        CapturePublisher::default()
+    }
+
+    // Return ALL events published
+    pub fn get_events(&self) -> Vec<MarketDataEvent> {
+        self.events.lock().unwrap().clone()
+    }
+
+    // Remove ALL events published so far
+    pub fn clear(&self) {
+        self.events.lock().unwrap().clear();
+    }
+}
+
+impl MarketDataPublisher for CapturePublisher {
+    fn publish(&mut self, event: &MarketDataEvent) {
+        self.events.lock().unwrap().push(event.clone());
+    }
+}
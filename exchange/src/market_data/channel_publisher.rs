@@ -0,0 +1,46 @@
+/*!
+    CHANNEL PUBLISHER
+
+    This contains all relevant properties and functions of the publisher sending the Incremental Feed
+    to a channel (JSON format, the same messages as the multicast Incremental Feed)
+*/
+
+extern crate serde_json;
+
+use std::sync::mpsc;
+use market_data::MarketDataPublisher;
+use objects::MarketDataEvent;
+
+/**
+    A channel publisher has the following properties:
+        - sender:
+            the channel the Incremental Feed is sent to (e.g, to the Recovery thread in main)
+*/
+pub struct ChannelPublisher {
+    sender: mpsc::Sender<String>,
+}
+
+impl ChannelPublisher {
+    /**
+        Constructor
+        @params
+            m_sender: the channel the Incremental Feed is sent to
+        @return
+            New channel publisher
+    */
+    pub fn new(m_sender: &mpsc::Sender<String>) -> ChannelPublisher {
+        ChannelPublisher {
+            sender: m_sender.clone(),
+        }
+    }
+}
+
+impl MarketDataPublisher for ChannelPublisher {
+    fn publish(&mut self, event: &MarketDataEvent) {
+        // Trades are NOT part of the Incremental Feed
+        if let MarketDataEvent::Incremental(ref message) = *event {
+            // The receiver may be gone (e.g, the Recovery thread stopped), the event is then dropped
+            let _ = self.sender.send(serde_json::to_string(message).unwrap());
+        }
+    }
+}
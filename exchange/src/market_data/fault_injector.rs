@@ -0,0 +1,300 @@
+/*!
+    FAULT INJECTOR
+
+    This contains all relevant properties and functions of the fault injection on ONE market data feed:
+    the datagrams of the feed go through the injector, which drops, duplicates, reorders and delays them.
+    ALL decisions are drawn from a pseudo-random generator with a seed, so the same seed and the same datagrams
+    always give the same faults. Datagrams go through 2 stages:
+        1. reordering: a datagram is held until up to reorder_window later datagrams were pushed
+           (or at most MAX_HOLD milliseconds, measured with the times given by the caller)
+        2. delay: a datagram is released delay_ms (plus up to jitter_ms) after it left the first stage
+*/
+
+// Maximum time (in milliseconds) a datagram is held for reordering (e.g, when the feed is quiet)
+pub const MAX_HOLD: u64 = 100;
+
+/**
+    The faults of a feed are:
+        - drop_probability:
+            probability (0 to 1) that a datagram starts a loss
+        - burst_length:
+            number of consecutive datagrams dropped by ONE loss
+        - duplicate_probability:
+            probability (0 to 1) that a datagram is sent twice
+        - reorder_window:
+            maximum number of later datagrams which can be sent before a datagram (0 = NO reordering)
+        - delay_ms:
+            latency (in milliseconds) added to EVERY datagram
+        - jitter_ms:
+            maximum random latency (in milliseconds) added to the delay of a datagram
+*/
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FaultConfig {
+    drop_probability: f64,
+    burst_length: u32,
+    duplicate_probability: f64,
+    reorder_window: u32,
+    delay_ms: u64,
+    jitter_ms: u64,
+}
+
+impl Default for FaultConfig {
+    fn default() -> FaultConfig {
+        FaultConfig {
+            drop_probability: 0.0,
+            burst_length: 1,
+            duplicate_probability: 0.0,
+            reorder_window: 0,
+            delay_ms: 0,
+            jitter_ms: 0,
+        }
+    }
+}
+
+impl FaultConfig {
+    /**
+        This function changes ONE fault
+
+        @params
+            name: name of the fault (e.g, "drop_probability")
+            value: new value of the fault
+        @return
+            - Nothing if the fault was changed
+            - Or the reason the fault is invalid (unknown fault, value which is not a number or NOT a probability)
+    */
+    pub fn set(&mut self, name: &str, value: &str) -> Result<(), String> {
+        let invalid_number = |_| format!("invalid value '{}' for {} (number expected)", value, name);
+        let parse_probability = |value: &str| value.parse::<f64>()
+            .map_err(|_| format!("invalid value '{}' for {} (probability from 0 to 1 expected)", value, name))
+            .and_then(|value| probability(name, value));
+        match name {
+            "drop_probability" => self.drop_probability = parse_probability(value)?,
+            "burst_length" => self.burst_length = value.parse().map_err(invalid_number)?,
+            "duplicate_probability" => self.duplicate_probability = parse_probability(value)?,
+            "reorder_window" => self.reorder_window = value.parse().map_err(invalid_number)?,
+            "delay_ms" => self.delay_ms = value.parse().map_err(invalid_number)?,
+            "jitter_ms" => self.jitter_ms = value.parse().map_err(invalid_number)?,
+            _ => return Err(format!("unknown fault '{}'", name)),
+        }
+        Ok(())
+    }
+
+    /**
+        This function checks the faults which were NOT changed by set (e.g, read from a configuration file)
+
+        @return
+            - Nothing if the faults are valid
+            - Or the reason the faults are invalid (probability NOT from 0 to 1)
+    */
+    pub fn validate(&self) -> Result<(), String> {
+        probability("drop_probability", self.drop_probability)?;
+        probability("duplicate_probability", self.duplicate_probability)?;
+        Ok(())
+    }
+
+    // Return true if at least ONE fault is injected
+    pub fn is_active(&self) -> bool {
+        *self != FaultConfig::default()
+    }
+
+    // Return probability that a datagram starts a loss
+    pub fn get_drop_probability(&self) -> f64 {
+        self.drop_probability
+    }
+
+    // Return number of consecutive datagrams dropped by ONE loss
+    pub fn get_burst_length(&self) -> u32 {
+        self.burst_length
+    }
+
+    // Return probability that a datagram is sent twice
+    pub fn get_duplicate_probability(&self) -> f64 {
+        self.duplicate_probability
+    }
+
+    // Return maximum number of later datagrams which can be sent before a datagram
+    pub fn get_reorder_window(&self) -> u32 {
+        self.reorder_window
+    }
+
+    // Return latency (in milliseconds) added to EVERY datagram
+    pub fn get_delay_ms(&self) -> u64 {
+        self.delay_ms
+    }
+
+    // Return maximum random latency (in milliseconds) added to the delay
+    pub fn get_jitter_ms(&self) -> u64 {
+        self.jitter_ms
+    }
+}
+
+// Return the probability, or the reason it is NOT from 0 to 1
+fn probability(name: &str, value: f64) -> Result<f64, String> {
+    if (0.0..=1.0).contains(&value) {
+        Ok(value)
+    } else {
+        Err(format!("invalid value '{}' for {} (probability from 0 to 1 expected)", value, name))
+    }
+}
+
+/**
+    A datagram held by the injector has the following properties:
+        - data:
+            the datagram
+        - index:
+            number of datagrams pushed before it
+        - release_after:
+            index of the datagram after which it leaves the reordering stage
+        - time:
+            time (in milliseconds) it was pushed (reordering stage), or it is released (delay stage)
+*/
+struct Held {
+    data: Vec<u8>,
+    index: u64,
+    release_after: u64,
+    time: u64,
+}
+
+/**
+    A fault injector has the following properties:
+        - faults:
+            the faults injected
+        - state:
+            state of the pseudo-random generator
+        - burst_left:
+            number of datagrams still to be dropped by the current loss
+        - pushed:
+            number of datagrams pushed (dropped ones excluded, duplicates included)
+        - reordering:
+            datagrams in the reordering stage
+        - delayed:
+            datagrams in the delay stage (in the order they left the reordering stage)
+*/
+pub struct FaultInjector {
+    faults: FaultConfig,
+    state: u64,
+    burst_left: u32,
+    pushed: u64,
+    reordering: Vec<Held>,
+    delayed: Vec<Held>,
+}
+
+impl FaultInjector {
+    /**
+        Constructor
+        @params
+            m_faults: the faults injected
+            seed: seed of the pseudo-random generator
+        @return
+            New fault injector without any datagram
+    */
+    pub fn new(m_faults: &FaultConfig, seed: u64) -> FaultInjector {
+        FaultInjector {
+            faults: m_faults.clone(),
+            state: seed,
+            burst_left: 0,
+            pushed: 0,
+            reordering: Vec::new(),
+            delayed: Vec::new(),
+        }
+    }
+
+    /**
+        This function gives a datagram to the injector (the datagram may be dropped or duplicated)
+
+        @params
+            data: the datagram
+            now: current time (in milliseconds, from any origin)
+    */
+    pub fn push(&mut self, data: Vec<u8>, now: u64) {
+        // Loss (ONE datagram, or the following ones of a burst)
+        if self.burst_left > 0 {
+            self.burst_left -= 1;
+            return;
+        }
+        if self.chance(self.faults.drop_probability) {
+            self.burst_left = self.faults.burst_length.saturating_sub(1);
+            return;
+        }
+        let copies = if self.chance(self.faults.duplicate_probability) { 2 } else { 1 };
+        for _ in 0..copies {
+            let index = self.pushed;
+            let hold = self.below(u64::from(self.faults.reorder_window) + 1);
+            self.pushed += 1;
+            self.reordering.push(Held { data: data.clone(), index, release_after: index + hold, time: now });
+        }
+        self.reorder(now);
+    }
+
+    /**
+        This function takes the datagrams to be sent
+
+        @params
+            now: current time (in milliseconds, same origin as push)
+        @return
+            The datagrams released, in the order they must be sent
+    */
+    pub fn pop_ready(&mut self, now: u64) -> Vec<Vec<u8>> {
+        self.reorder(now);
+        let (ready, delayed): (Vec<Held>, Vec<Held>) = self.delayed.drain(..).partition(|held| held.time <= now);
+        self.delayed = delayed;
+        let mut ready = ready;
+        // Stable sort: datagrams released at the same time keep their order
+        ready.sort_by_key(|held| held.time);
+        ready.into_iter().map(|held| held.data).collect()
+    }
+
+    // Return true if NO datagram is held by the injector
+    pub fn is_empty(&self) -> bool {
+        self.reordering.is_empty() && self.delayed.is_empty()
+    }
+
+    /**
+        This function moves the datagrams which leave the reordering stage to the delay stage:
+        a datagram leaves after the datagram release_after was pushed (it is then sent right after it),
+        or after MAX_HOLD milliseconds
+
+        @params
+            now: current time (in milliseconds)
+    */
+    fn reorder(&mut self, now: u64) {
+        let pushed = self.pushed;
+        let (mut leaving, staying): (Vec<Held>, Vec<Held>) = self.reordering.drain(..)
+            .partition(|held| held.release_after < pushed || now >= held.time + MAX_HOLD);
+        self.reordering = staying;
+        leaving.sort_by_key(|held| (held.release_after.min(pushed), held.release_after - held.index));
+        for mut held in leaving {
+            let jitter = self.below(self.faults.jitter_ms + 1);
+            held.time = now + self.faults.delay_ms + jitter;
+            self.delayed.push(held);
+        }
+    }
+
+    // Return true with a probability
+    fn chance(&mut self, probability: f64) -> bool {
+        probability > 0.0 && self.next_f64() < probability
+    }
+
+    // Return a pseudo-random number from 0 to bound - 1 (0 if bound is 0 or 1)
+    fn below(&mut self, bound: u64) -> u64 {
+        if bound <= 1 {
+            return 0;
+        }
+        self.next_u64() % bound
+    }
+
+    // Return a pseudo-random number from 0 to 1 (excluded)
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    // Return the next pseudo-random number (SplitMix64)
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
@@ -0,0 +1,137 @@
+/*!
+    FAULTY SOCKET
+
+    This contains all relevant properties and functions of the socket used to multicast the feeds,
+    injecting the faults configured for each feed (see FaultInjector), even when several feeds share a multicast group.
+    Datagrams delayed or held for reordering are sent by a background thread, which stops with the socket.
+    The faults are timed with the clock of the socket (wall clock by default), so that a SimulatedClock gives
+    the same releases in every run.
+*/
+
+use std::collections::HashMap;
+use std::io;
+use std::net::UdpSocket;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use clock::{Clock, SystemClock};
+use market_data::{FaultConfig, FaultInjector};
+
+// Period (in milliseconds) of the background thread sending the datagrams released
+const FLUSH_INTERVAL: u64 = 1;
+
+/**
+    A faulty socket has the following properties:
+        - socket:
+            the socket
+        - injectors:
+            ONE fault injector per feed with faults (NO fault on the other feeds)
+        - clock:
+            time source of the injectors (time of the pushes and releases of the datagrams)
+*/
+pub struct FaultySocket {
+    socket: UdpSocket,
+    /**
+        Key = name of the feed -> value = (multicast group, fault injector) of the feed
+    */
+    injectors: Arc<Mutex<HashMap<String, (String, FaultInjector)>>>,
+    clock: Arc<Mutex<Box<dyn Clock>>>,
+}
+
+impl FaultySocket {
+    /**
+        Constructor
+        @params
+            m_socket: the socket (bound)
+            faults: the faults of the feeds, (name, multicast group, faults) (feeds without any fault are ignored)
+            seed: seed of the fault injection (ONE pseudo-random generator per feed)
+        @return
+            - New faulty socket (with a background thread if there is at least ONE fault)
+            - Or the error if the socket cannot be cloned for the background thread
+    */
+    pub fn new(m_socket: UdpSocket, faults: &[(String, String, FaultConfig)], seed: u64) -> io::Result<FaultySocket> {
+        let mut injectors = HashMap::new();
+        for (feed, group, faults) in faults.iter().filter(|&(_, _, faults)| faults.is_active()) {
+            injectors.insert(feed.clone(), (group.clone(), FaultInjector::new(faults, seed ^ hash(feed))));
+        }
+        let socket = FaultySocket {
+            socket: m_socket,
+            injectors: Arc::new(Mutex::new(injectors)),
+            clock: Arc::new(Mutex::new(Box::new(SystemClock))),
+        };
+        if !socket.injectors.lock().unwrap().is_empty() {
+            socket.start_flusher()?;
+        }
+        Ok(socket)
+    }
+
+    /**
+        Set the clock timing the faults
+        (e.g, a SimulatedClock so that scenario runs and tests are reproducible)
+
+        @params
+            clock: the new clock
+    */
+    pub fn set_clock(&self, clock: Box<dyn Clock>) {
+        *self.clock.lock().unwrap() = clock;
+    }
+
+    /**
+        This function sends a datagram of a feed to its multicast group (through the fault injector of the feed, if any)
+
+        @params
+            contents: the datagram
+            feed: name of the feed (e.g, "incremental")
+            group_address: the multicast group of the feed
+    */
+    pub fn send_to(&self, contents: Vec<u8>, feed: &str, group_address: &str) {
+        let mut injectors = self.injectors.lock().unwrap();
+        match injectors.get_mut(feed) {
+            Some(&mut (_, ref mut injector)) => {
+                let now = now_ms(&self.clock);
+                injector.push(contents, now);
+                for datagram in injector.pop_ready(now) {
+                    let _ = self.socket.send_to(&datagram, group_address);
+                }
+            }
+            None => { let _ = self.socket.send_to(&contents, group_address); }
+        }
+    }
+
+    /**
+        This function starts the background thread sending the datagrams released by the injectors
+        (the thread stops when the faulty socket is dropped)
+
+        @return
+            - Nothing if the thread was started
+            - Or the error if the socket cannot be cloned
+    */
+    fn start_flusher(&self) -> io::Result<()> {
+        let socket = self.socket.try_clone()?;
+        let injectors = Arc::downgrade(&self.injectors);
+        let clock = self.clock.clone();
+        thread::spawn(move || {
+            while let Some(injectors) = injectors.upgrade() {
+                let now = now_ms(&clock);
+                for &mut (ref group_address, ref mut injector) in injectors.lock().unwrap().values_mut() {
+                    for datagram in injector.pop_ready(now) {
+                        let _ = socket.send_to(&datagram, group_address);
+                    }
+                }
+                drop(injectors);
+                thread::sleep(Duration::from_millis(FLUSH_INTERVAL));
+            }
+        });
+        Ok(())
+    }
+}
+
+// Return current time of a clock, in milliseconds since the epoch
+fn now_ms(clock: &Mutex<Box<dyn Clock>>) -> u64 {
+    clock.lock().unwrap().now_ms().max(0) as u64
+}
+
+// Return a hash of a string (FNV-1a), so that every feed has its own pseudo-random numbers
+fn hash(value: &str) -> u64 {
+    value.bytes().fold(0xCBF2_9CE4_8422_2325, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01B3))
+}
@@ -0,0 +1,470 @@
+/*!
+    FEED CODEC
+
+    This contains the encodings of the Incremental Feed and the Recovery Feed:
+        - JSON: text, ONE JSON object per message
+        - BINARY: compact fixed-layout messages (SBE style, little-endian), described below
+
+    Every binary message starts with a header (8 bytes):
+        block_length (u16) | template_id (u16) | schema_id (u16) | version (u16)
+    followed by the root block (block_length bytes), the repeating groups and the variable-length data.
+    A repeating group starts with its own header (6 bytes):
+        block_length (u16) | num_in_group (u32)
+    followed by num_in_group entries (block_length bytes each, then their nested groups).
+    Variable-length data (e.g, symbol) is a length (u8) followed by the bytes.
+    Decoders skip the fields they do not know (block lengths larger than expected), so that fields
+    can be appended to a block in a later version of the schema.
+
+    Incremental message (template 1):
+        root block: seq_number (i64) | price (i64)
+                    | order_id (i64) | qty (i64) | position (i64) | action (u8) | side (u8)   (since version 2)
+        group orders: order entry (see below)
+        data: symbol
+    Recovery feed (template 2):
+        root block: last_msg (i64)
+        group levels: price (i64), then nested group orders: order entry (see below)
+        data: symbol
+    Snapshot fragment (template 3), sent on the Recovery Feed whatever the encoding of the feed:
+        root block: snapshot_id (i64) | last_msg (i64) | fragment (u32) | fragments (u32)
+        data: payload (length u16, then the bytes: part of the encoded recovery feed)
+    Order entry (61 bytes):
+        id (i64) | qty (i64) | price (i64) | side (u8) | ord_type (u8) | time_in_force (u8) | transact_time (char[21])
+        | priority (i64)   (since version 3)
+        * NOTE: order IDs are numbers (assigned by the exchange), the symbol of an order is the symbol of the message
+*/
+
+extern crate serde_json;
+
+use objects::{IncrementalMessage, Order, RecoveryFeed, SnapshotFragment};
+
+// ID of the schema of the binary messages
+pub const SCHEMA_ID: u16 = 0x4748;
+// Version of the schema of the binary messages
+pub const SCHEMA_VERSION: u16 = 3;
+// Template ID of the Incremental message
+pub const INCREMENTAL_TEMPLATE_ID: u16 = 1;
+// Template ID of the Recovery feed
+pub const RECOVERY_TEMPLATE_ID: u16 = 2;
+// Template ID of the Snapshot fragment
+pub const FRAGMENT_TEMPLATE_ID: u16 = 3;
+// Length of the transaction time of an order (UTC format: YYYYMMDD-HH:MM:SS.sss)
+const TRANSACT_TIME_LENGTH: usize = 21;
+// Length of the root block of an Incremental message: seq_number, price, order_id, qty, position (i64), action, side (u8)
+const INCREMENTAL_BLOCK_LENGTH: u16 = 8 + 8 + 8 + 8 + 8 + 1 + 1;
+// Length of an order entry
+const ORDER_BLOCK_LENGTH: u16 = 8 + 8 + 8 + 1 + 1 + 1 + TRANSACT_TIME_LENGTH as u16 + 8;
+// Length of an order entry of versions 1 and 2 (without priority sequence)
+const ORDER_BLOCK_LENGTH_V2: u16 = ORDER_BLOCK_LENGTH - 8;
+
+/**
+    Encoding of the Incremental Feed and the Recovery Feed:
+        - Json: text (default)
+        - Binary: compact fixed-layout messages
+*/
+#[derive(PartialEq, Clone, Copy, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FeedEncoding {
+    #[default]
+    Json,
+    Binary,
+}
+
+impl FeedEncoding {
+    /**
+        This function gets the encoding from its name
+
+        @params
+            name: "json" or "binary"
+        @return
+            - The encoding
+            - Or the reason the name is invalid
+    */
+    pub fn parse(name: &str) -> Result<FeedEncoding, String> {
+        match name {
+            "json" => Ok(FeedEncoding::Json),
+            "binary" => Ok(FeedEncoding::Binary),
+            _ => Err(format!("unknown feed encoding '{}' (json or binary expected)", name)),
+        }
+    }
+}
+
+/**
+    This function encodes an Incremental message
+
+    @params
+        message: the message to be encoded
+        encoding: the encoding of the feed
+    @return
+        The message ready to be sent
+*/
+pub fn encode_incremental(message: &IncrementalMessage, encoding: FeedEncoding) -> Vec<u8> {
+    match encoding {
+        FeedEncoding::Json => serde_json::to_string(message).unwrap().into_bytes(),
+        FeedEncoding::Binary => {
+            let mut buffer = Vec::new();
+            put_header(&mut buffer, INCREMENTAL_BLOCK_LENGTH, INCREMENTAL_TEMPLATE_ID);
+            put_i64(&mut buffer, message.get_num());
+            put_i64(&mut buffer, message.get_price());
+            put_i64(&mut buffer, message.get_order_id().parse().unwrap_or(-1));
+            put_i64(&mut buffer, message.get_qty());
+            put_i64(&mut buffer, message.get_position());
+            buffer.push(message.get_action() as u8);
+            buffer.push(message.get_side() as u8);
+            put_orders(&mut buffer, &message.get_orders());
+            put_string(&mut buffer, &message.get_symbol());
+            buffer
+        }
+    }
+}
+
+/**
+    This function decodes an Incremental message
+
+    @params
+        data: the message received
+        encoding: the encoding of the feed
+    @return
+        - The message decoded
+        - Or the reason the message is invalid (e.g, truncated, wrong schema or template)
+*/
+pub fn decode_incremental(data: &[u8], encoding: FeedEncoding) -> Result<IncrementalMessage, String> {
+    match encoding {
+        FeedEncoding::Json => serde_json::from_slice(data).map_err(|e| format!("invalid message: {}", e)),
+        FeedEncoding::Binary => {
+            let mut reader = Reader::new(data);
+            let block_length = reader.header(INCREMENTAL_TEMPLATE_ID)?;
+            let block = reader.block(block_length, 16)?;
+            let seq_number = block.i64_at(0);
+            let price = block.i64_at(8);
+            let mut orders = reader.orders()?;
+            let symbol = reader.string()?;
+            for order in &mut orders {
+                order.set_symbol(&symbol);
+            }
+            let mut message = IncrementalMessage::new(&symbol, price, seq_number, orders);
+            // Messages of version 1 do NOT describe the order action
+            if block.data.len() >= INCREMENTAL_BLOCK_LENGTH as usize {
+                let order_id = block.i64_at(16).to_string();
+                message.set_order_action(block.data[40] as char, &order_id, block.data[41] as char, block.i64_at(24), block.i64_at(32));
+            }
+            Ok(message)
+        }
+    }
+}
+
+/**
+    This function encodes a Recovery feed
+
+    @params
+        feed: the feed to be encoded
+        encoding: the encoding of the feed
+    @return
+        The feed ready to be sent
+*/
+pub fn encode_recovery(feed: &RecoveryFeed, encoding: FeedEncoding) -> Vec<u8> {
+    match encoding {
+        FeedEncoding::Json => serde_json::to_string(feed).unwrap().into_bytes(),
+        FeedEncoding::Binary => {
+            let mut buffer = Vec::new();
+            put_header(&mut buffer, 8, RECOVERY_TEMPLATE_ID);
+            put_i64(&mut buffer, feed.get_last_msg());
+            let state = feed.get_state();
+            put_group_header(&mut buffer, 8, state.len());
+            for level in &state {
+                // Price of the level (0 if the level is empty)
+                put_i64(&mut buffer, level.first().map(|order| order.get_price()).unwrap_or(0));
+                put_orders(&mut buffer, level);
+            }
+            put_string(&mut buffer, &feed.get_symbol());
+            buffer
+        }
+    }
+}
+
+/**
+    This function decodes a Recovery feed
+
+    @params
+        data: the feed received
+        encoding: the encoding of the feed
+    @return
+        - The feed decoded
+        - Or the reason the feed is invalid (e.g, truncated, wrong schema or template)
+*/
+pub fn decode_recovery(data: &[u8], encoding: FeedEncoding) -> Result<RecoveryFeed, String> {
+    match encoding {
+        FeedEncoding::Json => serde_json::from_slice(data).map_err(|e| format!("invalid message: {}", e)),
+        FeedEncoding::Binary => {
+            let mut reader = Reader::new(data);
+            let block_length = reader.header(RECOVERY_TEMPLATE_ID)?;
+            let last_msg = reader.block(block_length, 8)?.i64_at(0);
+            let (level_length, num_levels) = reader.group_header()?;
+            let mut state = Vec::new();
+            for _ in 0..num_levels {
+                // The price of the level is the price of its orders
+                reader.block(level_length, 8)?;
+                state.push(reader.orders()?);
+            }
+            let symbol = reader.string()?;
+            for order in state.iter_mut().flat_map(|level| level.iter_mut()) {
+                order.set_symbol(&symbol);
+            }
+            Ok(RecoveryFeed::new(&symbol, last_msg, state))
+        }
+    }
+}
+
+/**
+    This function encodes a Snapshot fragment (binary, whatever the encoding of the feed)
+
+    @params
+        fragment: the fragment to be encoded (payload of at most 65535 bytes)
+    @return
+        The fragment ready to be sent
+*/
+pub fn encode_fragment(fragment: &SnapshotFragment) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    put_header(&mut buffer, 24, FRAGMENT_TEMPLATE_ID);
+    put_i64(&mut buffer, fragment.get_snapshot_id());
+    put_i64(&mut buffer, fragment.get_last_msg());
+    buffer.extend_from_slice(&fragment.get_fragment().to_le_bytes());
+    buffer.extend_from_slice(&fragment.get_fragments().to_le_bytes());
+    let payload = fragment.get_payload();
+    buffer.extend_from_slice(&(payload.len() as u16).to_le_bytes());
+    buffer.extend_from_slice(&payload);
+    buffer
+}
+
+/**
+    This function decodes a Snapshot fragment
+
+    @params
+        data: the fragment received
+    @return
+        - The fragment decoded
+        - Or the reason the fragment is invalid (e.g, truncated, wrong schema or template)
+*/
+pub fn decode_fragment(data: &[u8]) -> Result<SnapshotFragment, String> {
+    let mut reader = Reader::new(data);
+    let block_length = reader.header(FRAGMENT_TEMPLATE_ID)?;
+    let block = reader.block(block_length, 24)?;
+    let length = reader.u16()? as usize;
+    let payload = reader.take(length)?;
+    Ok(SnapshotFragment::new(block.i64_at(0), block.i64_at(8), block.u32_at(16), block.u32_at(20), payload))
+}
+
+/**
+    This function appends the message header
+
+    @params
+        buffer: the message being encoded
+        block_length: length of the root block
+        template_id: template of the message
+*/
+fn put_header(buffer: &mut Vec<u8>, block_length: u16, template_id: u16) {
+    for value in &[block_length, template_id, SCHEMA_ID, SCHEMA_VERSION] {
+        buffer.extend_from_slice(&value.to_le_bytes());
+    }
+}
+
+/**
+    This function appends the header of a repeating group
+
+    @params
+        buffer: the message being encoded
+        block_length: length of an entry of the group
+        num_in_group: number of entries in the group
+*/
+fn put_group_header(buffer: &mut Vec<u8>, block_length: u16, num_in_group: usize) {
+    buffer.extend_from_slice(&block_length.to_le_bytes());
+    buffer.extend_from_slice(&(num_in_group as u32).to_le_bytes());
+}
+
+// Append a number (little-endian)
+fn put_i64(buffer: &mut Vec<u8>, value: i64) {
+    buffer.extend_from_slice(&value.to_le_bytes());
+}
+
+/**
+    This function appends a variable-length string (at most 255 bytes, longer strings are truncated
+    on a character boundary, so that the string decoded is still valid UTF-8)
+
+    @params
+        buffer: the message being encoded
+        value: the string
+*/
+fn put_string(buffer: &mut Vec<u8>, value: &str) {
+    let mut length = value.len().min(255);
+    while !value.is_char_boundary(length) {
+        length -= 1;
+    }
+    let bytes = &value.as_bytes()[..length];
+    buffer.push(bytes.len() as u8);
+    buffer.extend_from_slice(bytes);
+}
+
+/**
+    This function appends a group of orders
+
+    @params
+        buffer: the message being encoded
+        orders: the orders
+*/
+fn put_orders(buffer: &mut Vec<u8>, orders: &[Order]) {
+    put_group_header(buffer, ORDER_BLOCK_LENGTH, orders.len());
+    for order in orders {
+        put_i64(buffer, order.get_id().parse().unwrap_or(-1));
+        put_i64(buffer, order.get_qty());
+        put_i64(buffer, order.get_price());
+        buffer.push(order.get_side() as u8);
+        buffer.push(order.get_ord_type() as u8);
+        buffer.push(order.get_time_in_force() as u8);
+        // Fixed-length field, padded with NUL characters
+        let mut transact_time = order.get_transact_time().into_bytes();
+        transact_time.resize(TRANSACT_TIME_LENGTH, 0);
+        buffer.extend_from_slice(&transact_time);
+        put_i64(buffer, order.get_priority());
+    }
+}
+
+/**
+    A reader has the following properties:
+        - data:
+            the message being decoded
+        - position:
+            index of the next byte to be read
+*/
+struct Reader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+/**
+    A block is a fixed-length part of a message (root block or entry of a group)
+*/
+struct Block<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Block<'a> {
+    fn i64_at(&self, offset: usize) -> i64 {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&self.data[offset..offset + 8]);
+        i64::from_le_bytes(bytes)
+    }
+
+    fn u32_at(&self, offset: usize) -> u32 {
+        let mut bytes = [0u8; 4];
+        bytes.copy_from_slice(&self.data[offset..offset + 4]);
+        u32::from_le_bytes(bytes)
+    }
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Reader<'a> {
+        Reader { data, position: 0 }
+    }
+
+    /**
+        This function reads the next bytes
+
+        @params
+            length: number of bytes
+        @return
+            - The bytes
+            - Or an error if the message is truncated
+    */
+    fn take(&mut self, length: usize) -> Result<&'a [u8], String> {
+        if self.data.len() < self.position + length {
+            return Err("truncated message".to_string());
+        }
+        let bytes = &self.data[self.position..self.position + length];
+        self.position += length;
+        Ok(bytes)
+    }
+
+    fn u16(&mut self) -> Result<u16, String> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /**
+        This function reads the message header
+
+        @params
+            template_id: the template expected
+        @return
+            - Length of the root block
+            - Or the reason the header is invalid
+    */
+    fn header(&mut self, template_id: u16) -> Result<u16, String> {
+        let block_length = self.u16()?;
+        let (template, schema, version) = (self.u16()?, self.u16()?, self.u16()?);
+        if schema != SCHEMA_ID {
+            return Err(format!("unknown schema {}", schema));
+        }
+        if version == 0 || version > SCHEMA_VERSION {
+            return Err(format!("unsupported schema version {}", version));
+        }
+        if template != template_id {
+            return Err(format!("unexpected template {}", template));
+        }
+        Ok(block_length)
+    }
+
+    /**
+        This function reads a block (the fields unknown to this version are skipped)
+
+        @params
+            block_length: length of the block in the message
+            known_length: length of the fields known to this version
+        @return
+            - The block
+            - Or an error if the block is too short or truncated
+    */
+    fn block(&mut self, block_length: u16, known_length: usize) -> Result<Block<'a>, String> {
+        if (block_length as usize) < known_length {
+            return Err(format!("block too short ({} bytes)", block_length));
+        }
+        Ok(Block { data: self.take(block_length as usize)? })
+    }
+
+    // Return block length and number of entries of a repeating group
+    fn group_header(&mut self) -> Result<(u16, u32), String> {
+        Ok((self.u16()?, self.u32()?))
+    }
+
+    // Return a variable-length string
+    fn string(&mut self) -> Result<String, String> {
+        let length = self.take(1)?[0] as usize;
+        let bytes = self.take(length)?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| "invalid string".to_string())
+    }
+
+    // Return a group of orders
+    fn orders(&mut self) -> Result<Vec<Order>, String> {
+        let (block_length, num_in_group) = self.group_header()?;
+        let mut orders = Vec::new();
+        for _ in 0..num_in_group {
+            let block = self.block(block_length, ORDER_BLOCK_LENGTH_V2 as usize)?;
+            let mut order = Order::new(block.i64_at(8), block.i64_at(16), block.data[24] as char);
+            order.set_id(&block.i64_at(0).to_string());
+            order.set_ord_type(block.data[25] as char);
+            order.set_time_in_force(block.data[26] as char);
+            let transact_time = &block.data[27..27 + TRANSACT_TIME_LENGTH];
+            let end = transact_time.iter().position(|&byte| byte == 0).unwrap_or(TRANSACT_TIME_LENGTH);
+            order.set_transact_time(&String::from_utf8_lossy(&transact_time[..end]));
+            // Orders of versions 1 and 2 do NOT have a priority sequence
+            if block.data.len() >= ORDER_BLOCK_LENGTH as usize {
+                order.set_priority(block.i64_at(ORDER_BLOCK_LENGTH_V2 as usize));
+            }
+            orders.push(order);
+        }
+        Ok(orders)
+    }
+}
@@ -0,0 +1,47 @@
+/*!
+    FILE PUBLISHER
+
+    This contains all relevant properties and functions of the publisher recording ALL events in a file
+    (JSON format, ONE event per line), e.g to replay a trading session
+*/
+
+extern crate serde_json;
+
+use std::fs::File;
+use std::io;
+use std::io::Write;
+use market_data::MarketDataPublisher;
+use objects::MarketDataEvent;
+
+/**
+    A file publisher has the following properties:
+        - file:
+            the file the events are recorded in
+*/
+pub struct FilePublisher {
+    file: File,
+}
+
+impl FilePublisher {
+    /**
+        Constructor
+        @params
+            path: path of the file (created, or truncated if it exists)
+        @return
+            - New file publisher
+            - Or the error if the file cannot be created
+    */
+    pub fn create(path: &str) -> io::Result<FilePublisher> {
+        Ok(FilePublisher {
+            file: File::create(path)?,
+        })
+    }
+}
+
+impl MarketDataPublisher for FilePublisher {
+    fn publish(&mut self, event: &MarketDataEvent) {
+        if let Err(e) = writeln!(self.file, "{}", serde_json::to_string(event).unwrap()) {
+            println!("Market data recording error: {}", e);
+        }
+    }
+}
@@ -0,0 +1,28 @@
+/*!
+    MARKET DATA PUBLISHER
+
+    This contains the interface between the matching engine and the delivery of its market data
+    (the matching engine emits events, the publishers decide how and where they are delivered)
+*/
+
+use objects::MarketDataEvent;
+
+/**
+    A market data publisher receives EVERY event of the matching engine, in the order they happen.
+    Implementations:
+        - UdpPublisher: multicasts the Incremental Feed and the Trade Feed
+        - ChannelPublisher: sends the Incremental Feed to a channel (e.g, to the Recovery thread)
+        - FilePublisher: records ALL events in a file
+        - NullPublisher: discards ALL events
+        - CapturePublisher: keeps ALL events in memory (e.g, for tests)
+        - ReplayBuffer: keeps the most recent Incremental messages (for the replay server)
+*/
+pub trait MarketDataPublisher: Send {
+    /**
+        This function publishes an event
+
+        @params
+            event: the event emitted by the matching engine
+    */
+    fn publish(&mut self, event: &MarketDataEvent);
+}
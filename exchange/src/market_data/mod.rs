@@ -0,0 +1,27 @@
+pub use self::market_data_publisher::MarketDataPublisher;
+pub use self::udp_publisher::UdpPublisher;
+pub use self::channel_publisher::ChannelPublisher;
+pub use self::file_publisher::FilePublisher;
+pub use self::null_publisher::NullPublisher;
+pub use self::capture_publisher::CapturePublisher;
+pub use self::feed_codec::{FeedEncoding, encode_incremental, decode_incremental, encode_recovery, decode_recovery};
+pub use self::feed_codec::{encode_fragment, decode_fragment};
+pub use self::snapshot_assembler::{SnapshotAssembler, MAX_FRAGMENTS};
+pub use self::bar_aggregator::BarAggregator;
+pub use self::fault_injector::{FaultConfig, FaultInjector, MAX_HOLD};
+pub use self::faulty_socket::FaultySocket;
+pub use self::replay_buffer::ReplayBuffer;
+pub use self::replay_server::ReplayServer;
+mod market_data_publisher;
+mod udp_publisher;
+mod channel_publisher;
+mod file_publisher;
+mod null_publisher;
+mod capture_publisher;
+mod feed_codec;
+mod snapshot_assembler;
+mod bar_aggregator;
+mod fault_injector;
+mod faulty_socket;
+mod replay_buffer;
+mod replay_server;
\ No newline at end of file
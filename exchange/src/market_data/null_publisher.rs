@@ -0,0 +1,17 @@
+/*!
+    NULL PUBLISHER
+
+    This contains the publisher discarding ALL events (e.g, for a matching engine without market data)
+*/
+
+use market_data::MarketDataPublisher;
+use objects::MarketDataEvent;
+
+/**
+    A null publisher has NO property, every event published is discarded
+*/
+pub struct NullPublisher;
+
+impl MarketDataPublisher for NullPublisher {
+    fn publish(&mut self, _event: &MarketDataEvent) {}
+}
@@ -0,0 +1,91 @@
+/*!
+    REPLAY BUFFER
+
+    This contains all relevant properties and functions of the publisher keeping the most recent
+    Incremental messages, so that clients can ask for the messages they missed (see ReplayServer)
+*/
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use market_data::MarketDataPublisher;
+use objects::{IncrementalMessage, MarketDataEvent};
+
+/**
+    A replay buffer has the following properties:
+        - capacity:
+            maximum number of messages kept (the oldest message is dropped when a message is added to a full buffer)
+        - messages:
+            the most recent Incremental messages, oldest first
+            (shared by ALL clones of the buffer, so that the messages can be read while the matching engine owns it)
+*/
+#[derive(Clone)]
+pub struct ReplayBuffer {
+    capacity: usize,
+    messages: Arc<Mutex<VecDeque<IncrementalMessage>>>,
+}
+
+impl ReplayBuffer {
+    /**
+        Constructor
+        @params
+            m_capacity: maximum number of messages kept
+        @return
+            New empty replay buffer
+    */
+    pub fn new(m_capacity: usize) -> ReplayBuffer {
+        ReplayBuffer {
+            capacity: m_capacity,
+            messages: Arc::new(Mutex::new(VecDeque::with_capacity(m_capacity))),
+        }
+    }
+
+    // Return maximum number of messages kept
+    pub fn get_capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /**
+        This function gets a range of messages
+
+        @params
+            first: sequential number of the first message
+            last: sequential number of the last message
+        @return
+            - ALL the messages of the range, in order
+            - Or the reason they cannot be replayed (invalid range, aged out, NOT published yet)
+    */
+    pub fn get_range(&self, first: i64, last: i64) -> Result<Vec<IncrementalMessage>, String> {
+        if first < 1 || first > last {
+            return Err(format!("invalid range {} to {}", first, last));
+        }
+        let messages = self.messages.lock().unwrap();
+        let (oldest, newest) = match (messages.front(), messages.back()) {
+            (Some(oldest), Some(newest)) => (oldest.get_num(), newest.get_num()),
+            _ => return Err("no message published yet".to_string()),
+        };
+        if last > newest {
+            return Err(format!("messages after {} not published yet", newest));
+        }
+        if first < oldest {
+            return Err(format!("messages before {} aged out", oldest));
+        }
+        // Sequential numbers are consecutive, the position of a message is its distance to the oldest one
+        let start = (first - oldest) as usize;
+        Ok(messages.range(start..=start + (last - first) as usize).cloned().collect())
+    }
+}
+
+impl MarketDataPublisher for ReplayBuffer {
+    fn publish(&mut self, event: &MarketDataEvent) {
+        if let MarketDataEvent::Incremental(ref message) = *event {
+            if self.capacity == 0 {
+                return;
+            }
+            let mut messages = self.messages.lock().unwrap();
+            if messages.len() == self.capacity {
+                messages.pop_front();
+            }
+            messages.push_back(message.clone());
+        }
+    }
+}
@@ -0,0 +1,116 @@
+/*!
+    REPLAY SERVER
+
+    This contains all relevant properties and functions of the TCP replay (retransmission) server.
+    A client which missed Incremental messages asks for them instead of waiting for the next Recovery Feed:
+        - Client sends requests (range of sequential numbers), ONE request per line in JSON format
+        (see ReplayRequest)
+        - Server sends back ONE response per request, in JSON format (see ReplayResponse),
+        followed by the messages of the range (if accepted), ONE message per line in JSON format
+*/
+
+extern crate serde_json;
+
+use market_data::ReplayBuffer;
+use objects::{ReplayRequest, ReplayResponse};
+use std::io;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::thread;
+
+/**
+    A replay server has the following properties:
+        - listener:
+            socket listening for connections of the clients
+*/
+pub struct ReplayServer {
+    listener: TcpListener,
+}
+
+impl ReplayServer {
+    /**
+        Constructor
+        @params
+            address: address the server listens on (MUST specify the PORT, port 0 lets the system choose one)
+        @return
+            - New replay server (NOT accepting connections yet)
+            - Or the error if the address cannot be bound
+    */
+    pub fn bind(address: &str) -> io::Result<ReplayServer> {
+        Ok(ReplayServer {
+            listener: TcpListener::bind(address)?,
+        })
+    }
+
+    // Return address the server listens on
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /**
+        This function starts accepting connections (in a new thread), ONE thread per client
+
+        @params
+            buffer: the replay buffer the messages are replayed from
+        @return
+            Handle of the thread accepting connections
+    */
+    pub fn start(self, buffer: &ReplayBuffer) -> thread::JoinHandle<()> {
+        let buffer = buffer.clone();
+        thread::spawn(move || {
+            for stream in self.listener.incoming() {
+                match stream {
+                    Ok(stream) => {
+                        let buffer = buffer.clone();
+                        thread::spawn(move || {
+                            if let Err(e) = run_session(stream, &buffer) {
+                                println!("Replay session error: {}", e);
+                            }
+                        });
+                    }
+                    Err(e) => println!("Replay connection error: {}", e),
+                }
+            }
+        })
+    }
+}
+
+/**
+    This function answers the requests of a client until the client disconnects
+
+    @params
+        stream: connection of the client
+        buffer: the replay buffer
+*/
+fn run_session(stream: TcpStream, buffer: &ReplayBuffer) -> io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let request = match serde_json::from_str::<ReplayRequest>(&line) {
+            Ok(request) => request,
+            Err(e) => {
+                let response = ReplayResponse::rejected(0, 0, &format!("invalid request: {}", e));
+                writeln!(writer, "{}", serde_json::to_string(&response).unwrap())?;
+                continue;
+            }
+        };
+        let (first, last) = (request.get_first(), request.get_last());
+        match buffer.get_range(first, last) {
+            Ok(messages) => {
+                let response = ReplayResponse::accepted(first, last, messages.len() as i64);
+                writeln!(writer, "{}", serde_json::to_string(&response).unwrap())?;
+                for message in messages {
+                    writeln!(writer, "{}", serde_json::to_string(&message).unwrap())?;
+                }
+            }
+            Err(reason) => {
+                let response = ReplayResponse::rejected(first, last, &reason);
+                writeln!(writer, "{}", serde_json::to_string(&response).unwrap())?;
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,108 @@
+/*!
+    SNAPSHOT ASSEMBLER
+
+    This contains all relevant properties and functions of the client-side reassembly of the Recovery Feed
+    (a recovery feed is sent in ONE or more fragments, see SnapshotFragment)
+*/
+
+use market_data::{FeedEncoding, decode_fragment, decode_recovery};
+use objects::{RecoveryFeed, SnapshotFragment};
+
+// Maximum number of fragments of a snapshot (a larger count read off the wire is rejected, NOT allocated)
+pub const MAX_FRAGMENTS: usize = 4096;
+
+/**
+    A snapshot assembler has the following properties:
+        - encoding:
+            encoding of the Recovery Feed
+        - snapshot_id:
+            ID of the snapshot being reassembled (None before the first fragment)
+        - payloads:
+            payloads of the fragments of the snapshot received so far (None if NOT received yet)
+
+    Only ONE snapshot is reassembled at a time: the fragments of a newer snapshot replace the fragments
+    received so far (the snapshot is then incomplete, e.g a fragment was lost), the fragments of an older
+    snapshot are ignored.
+*/
+pub struct SnapshotAssembler {
+    encoding: FeedEncoding,
+    snapshot_id: Option<i64>,
+    payloads: Vec<Option<Vec<u8>>>,
+}
+
+impl SnapshotAssembler {
+    /**
+        Constructor
+        @params
+            m_encoding: encoding of the Recovery Feed
+        @return
+            New snapshot assembler without any fragment
+    */
+    pub fn new(m_encoding: FeedEncoding) -> SnapshotAssembler {
+        SnapshotAssembler {
+            encoding: m_encoding,
+            snapshot_id: None,
+            payloads: Vec::new(),
+        }
+    }
+
+    /**
+        This function adds a datagram received on the Recovery Feed
+
+        @params
+            data: the datagram (an encoded SnapshotFragment)
+        @return
+            - The recovery feed if the datagram completes a snapshot
+            - None if the snapshot is NOT complete yet
+            - Or the reason the datagram (or the snapshot completed) is invalid
+    */
+    pub fn add_datagram(&mut self, data: &[u8]) -> Result<Option<RecoveryFeed>, String> {
+        let fragment = decode_fragment(data)?;
+        self.add_fragment(&fragment)
+    }
+
+    /**
+        This function adds a fragment of a snapshot
+
+        @params
+            fragment: the fragment received
+        @return
+            - The recovery feed if the fragment completes a snapshot
+            - None if the snapshot is NOT complete yet
+            - Or the reason the fragment (or the snapshot completed) is invalid, e.g more than MAX_FRAGMENTS fragments
+    */
+    pub fn add_fragment(&mut self, fragment: &SnapshotFragment) -> Result<Option<RecoveryFeed>, String> {
+        let (number, count) = (fragment.get_fragment() as usize, fragment.get_fragments() as usize);
+        if number == 0 || number > count {
+            return Err(format!("invalid fragment {} of {}", number, count));
+        }
+        if count > MAX_FRAGMENTS {
+            return Err(format!("snapshot of {} fragments, at most {} expected", count, MAX_FRAGMENTS));
+        }
+        match self.snapshot_id {
+            // Fragment of an older snapshot
+            Some(snapshot_id) if fragment.get_snapshot_id() < snapshot_id => return Ok(None),
+            Some(snapshot_id) if fragment.get_snapshot_id() == snapshot_id => {
+                // Snapshot already reassembled (e.g, fragment received twice)
+                if self.payloads.is_empty() {
+                    return Ok(None);
+                }
+                if self.payloads.len() != count {
+                    return Err(format!("snapshot {} has {} fragments, not {}", snapshot_id, self.payloads.len(), count));
+                }
+            }
+            // First fragment of a new snapshot
+            _ => {
+                self.snapshot_id = Some(fragment.get_snapshot_id());
+                self.payloads = vec![None; count];
+            }
+        }
+        self.payloads[number - 1] = Some(fragment.get_payload());
+        if self.payloads.iter().any(|payload| payload.is_none()) {
+            return Ok(None);
+        }
+        // ALL fragments received: the payloads, in order, make the recovery feed
+        let data: Vec<u8> = self.payloads.drain(..).flat_map(|payload| payload.unwrap()).collect();
+        decode_recovery(&data, self.encoding).map(Some)
+    }
+}
@@ -0,0 +1,136 @@
+/*!
+    UDP PUBLISHER
+
+    This contains all relevant properties and functions of the publisher multicasting the
+    Incremental Feed (JSON or binary format, on ONE or two lines), the Trade Feed, the MBP feed, the depth feed,
+    the BBO feed and the bar feed (JSON format), ONE message per datagram (with the faults configured for each feed)
+*/
+
+extern crate serde_json;
+
+use std::io;
+use std::net::UdpSocket;
+use clock::Clock;
+use config::Config;
+use market_data::{MarketDataPublisher, FeedEncoding, FaultySocket, encode_incremental};
+use objects::MarketDataEvent;
+
+/**
+    A UDP publisher has the following properties:
+        - socket:
+            socket used for multicasting (injecting the faults of the feeds)
+        - incremental_feed_group:
+            multicast group of the Incremental Feed
+        - incremental_feed_group_b:
+            multicast group of the second line of the Incremental Feed (empty if ONE line only)
+        - trade_feed_group:
+            multicast group of the Trade Feed
+        - mbp_feed_group:
+            multicast group of the MBP feed
+        - depth_feed_group:
+            multicast group of the depth feed
+        - quote_feed_group:
+            multicast group of the BBO feed
+        - bar_feed_group:
+            multicast group of the bar feed
+        - encoding:
+            encoding of the Incremental Feed
+*/
+pub struct UdpPublisher {
+    socket: FaultySocket,
+    incremental_feed_group: String,
+    incremental_feed_group_b: String,
+    trade_feed_group: String,
+    mbp_feed_group: String,
+    depth_feed_group: String,
+    quote_feed_group: String,
+    bar_feed_group: String,
+    encoding: FeedEncoding,
+}
+
+impl UdpPublisher {
+    /**
+        Constructor
+        @params
+            config: configuration of the exchange (bind address, multicast groups, TTL, encoding and faults of the feeds)
+        @return
+            - New UDP publisher
+            - Or the error if the socket cannot be bound
+    */
+    pub fn new(config: &Config) -> io::Result<UdpPublisher> {
+        let socket = UdpSocket::bind(config.multicast_bind_address(&config.get_feed_bind_address()))?;
+        socket.set_multicast_ttl_v4(config.get_multicast_ttl())?;
+        let faults: Vec<(String, String, _)> = [
+            ("incremental", config.get_incremental_feed_group()),
+            ("incremental_b", config.get_incremental_feed_group_b()),
+            ("trade", config.get_trade_feed_group()),
+            ("mbp", config.get_mbp_feed_group()),
+            ("depth", config.get_depth_feed_group()),
+            ("quote", config.get_quote_feed_group()),
+            ("bar", config.get_bar_feed_group()),
+        ].iter().map(|&(feed, ref group)| (feed.to_string(), group.clone(), config.get_faults(feed))).collect();
+        Ok(UdpPublisher {
+            socket: FaultySocket::new(socket, &faults, config.get_fault_seed())?,
+            incremental_feed_group: config.get_incremental_feed_group(),
+            incremental_feed_group_b: config.get_incremental_feed_group_b(),
+            trade_feed_group: config.get_trade_feed_group(),
+            mbp_feed_group: config.get_mbp_feed_group(),
+            depth_feed_group: config.get_depth_feed_group(),
+            quote_feed_group: config.get_quote_feed_group(),
+            bar_feed_group: config.get_bar_feed_group(),
+            encoding: config.get_feed_encoding(),
+        })
+    }
+
+    /**
+        Set the clock timing the faults of the feeds (see FaultySocket::set_clock)
+
+        @params
+            clock: the new clock
+    */
+    pub fn set_clock(&self, clock: Box<dyn Clock>) {
+        self.socket.set_clock(clock);
+    }
+
+    /**
+        This function multicasts contents of a feed to its multicast group
+
+        @params
+            contents: the contents to be published
+            feed: name of the feed (e.g, "incremental")
+            group_address: the multicast group of the feed (e.g, incremental_feed_group)
+    */
+    fn multicast(&self, contents: Vec<u8>, feed: &str, group_address: &str) {
+        self.socket.send_to(contents, feed, group_address);
+    }
+}
+
+impl MarketDataPublisher for UdpPublisher {
+    fn publish(&mut self, event: &MarketDataEvent) {
+        match *event {
+            MarketDataEvent::Incremental(ref message) => {
+                // Same message on both lines (A then B), clients keep the first copy received
+                let contents = encode_incremental(message, self.encoding);
+                self.multicast(contents.clone(), "incremental", &self.incremental_feed_group);
+                if !self.incremental_feed_group_b.is_empty() {
+                    self.multicast(contents, "incremental_b", &self.incremental_feed_group_b)
+                }
+            }
+            MarketDataEvent::Trade(ref trade) => {
+                self.multicast(serde_json::to_string(trade).unwrap().into_bytes(), "trade", &self.trade_feed_group)
+            }
+            MarketDataEvent::PriceLevel(ref message) => {
+                self.multicast(serde_json::to_string(message).unwrap().into_bytes(), "mbp", &self.mbp_feed_group)
+            }
+            MarketDataEvent::Depth(ref message) => {
+                self.multicast(serde_json::to_string(message).unwrap().into_bytes(), "depth", &self.depth_feed_group)
+            }
+            MarketDataEvent::Quote(ref message) => {
+                self.multicast(serde_json::to_string(message).unwrap().into_bytes(), "quote", &self.quote_feed_group)
+            }
+            MarketDataEvent::Bar(ref bar) => {
+                self.multicast(serde_json::to_string(bar).unwrap().into_bytes(), "bar", &self.bar_feed_group)
+            }
+        }
+    }
+}
@@ -1,232 +1,563 @@
-/**
+/*!
     MATCHING ENGINE
 
     This contains all relevant properties and functions of the matching engine
 */
 
-extern crate linked_hash_map;
-extern crate serde_json;
-
-use std::{cmp, str};
-use std::net::UdpSocket;
-use objects::{Order, IncrementalMessage};
-use std::collections::HashMap;
-use self::linked_hash_map::LinkedHashMap;
+use std::str;
+use std::cmp;
+use objects::{Order, IncrementalMessage, Trade, ExchangeError, ExecutionReport, MarketDataEvent};
+use objects::{PriceLevel, PriceLevelMessage, DepthMessage, QuoteMessage, Bar, SelfTradePrevention};
+use std::collections::{HashMap, VecDeque};
 use std::sync::mpsc;
+use matching_engine::order_book::OrderBook;
+use matching_engine::trigger_book::TriggerBook;
+use market_data::{MarketDataPublisher, ChannelPublisher, BarAggregator};
+use clock::{Clock, SystemClock};
 
-// IP address of the computer running main.rs (MUST specify the PORT)
-const SERVER_ADDRESS: &str = "192.168.1.8:21003";
-// IP address of the multicast group for Incremental Feed (MUST specify the PORT)
-const INCREMENTAL_FEED_MULTICAST_GROUP_ADDRESS: &str = "239.194.5.3:21003";
+// (price, quantity) of the best bid and (price, quantity) of the best offer of an instrument (0 if NO order)
+type Quote = ((i64, i64), (i64, i64));
 
 /**
     A matching engine has the following properties:
-        - sells_by_price: 
-            collection of lists of orders on SELL side with different prices
-        - buys_by_price:
-            collection of lists of orders on BUY side with different prices
+        - books:
+            instrument registry, ONE order book per symbol
+            (an order book is created when the first order of a symbol is inserted)
+        - triggers:
+            STOP and STOP LIMIT orders waiting to be elected, ONE trigger book per symbol
+        - last_prices:
+            price of the last trade of ALL instruments traded (elects the STOP and STOP LIMIT orders)
+        - order_index:
+            index of ALL resting orders and orders waiting to be elected (order ID -> symbol of the instrument),
+            so that an order is found without searching every order book
         - id_count:
             used to assign ID for new orders
-        - socket:
-            socket used for multicasting
-        - send_channel:
-            a channel to send Incremental Feed to Recovery thread in main
+        - trade_id_count:
+            used to assign ID for new trades
+        - priority_seq:
+            used to assign priority sequence for orders entering the order book or losing their priority
+        - publishers:
+            ALL market data publishers, every event (Incremental Feed, Trade Feed, MBP feed, depth feed, BBO feed, bar feed)
+            is published to each of them
         - seq_number:
             sequential number of the Incremental Feed
+        - mbp_seq_number:
+            sequential number of the MBP feed
+        - mbp_levels:
+            side of ALL price levels published on the MBP feed (and NOT deleted yet)
+        - quote_seq_number:
+            sequential number of the BBO feed
+        - quotes:
+            best bid and best offer of ALL instruments, as last published on the BBO feed
+        - bars:
+            OHLCV bars of ALL instruments, built from the trades (60 second bars by default)
+        - depth_levels:
+            number of price levels (per side) of the depth feed (0 = NO depth feed)
+        - report_channel:
+            a channel to send Execution Reports (responses to order entry requests), if any
+        - tick_size:
+            minimum price increment, prices of LIMIT orders must be a multiple of it
+        - clock:
+            time source of the time stamps of orders, trades and Execution Reports (wall clock by default)
+        - self_trade_prevention:
+            what happens when an order would trade against a resting order of the same account (NOTHING by default)
 */
 pub struct MatchingEngine {
-    /** 
-        Outer hash map: key = price -> value = inner hash map
-        Inner hash map: key = order id -> value = order
+    /**
+        Key = symbol -> value = order book of the instrument
+    */
+    books: HashMap<String, OrderBook>,
+    /**
+        Key = symbol -> value = trigger book of the instrument
     */
-    sells_by_price: HashMap<i64, LinkedHashMap<String, Order>>,
-    buys_by_price: HashMap<i64, LinkedHashMap<String, Order>>,
+    triggers: HashMap<String, TriggerBook>,
+    last_prices: HashMap<String, i64>,
+    order_index: HashMap<String, String>,
     id_count: i64,
-    socket: UdpSocket,
-    send_channel: mpsc::Sender<String>,
+    trade_id_count: i64,
+    priority_seq: i64,
+    publishers: Vec<Box<dyn MarketDataPublisher>>,
     seq_number: i64,
+    mbp_seq_number: i64,
+    /**
+        Key = (symbol, price) -> value = side of the price level
+    */
+    mbp_levels: HashMap<(String, i64), char>,
+    quote_seq_number: i64,
+    /**
+        Key = symbol -> value = best bid and best offer of the instrument
+    */
+    quotes: HashMap<String, Quote>,
+    bars: BarAggregator,
+    depth_levels: usize,
+    report_channel: Option<mpsc::Sender<ExecutionReport>>,
+    tick_size: i64,
+    clock: Box<dyn Clock>,
+    self_trade_prevention: SelfTradePrevention,
 }
 
 impl MatchingEngine {
-	/**
-		Constructor
-		@params 
-			sender: a channel to send data to the main thread (send incremental feed to recovery multicast thread in main)
-		@return
-			New matching engine with empty Hash Maps for sell and buy orders
-	*/
+    /**
+        Constructor
+        @params 
+            sender: a channel to send data to the main thread (send incremental feed to recovery multicast thread in main)
+        @return
+            New matching engine without any order book (publishing the Incremental Feed to the channel ONLY)
+    */
     pub fn new(sender: &mpsc::Sender<String>) -> MatchingEngine { 
+        MatchingEngine::with_publisher(Box::new(ChannelPublisher::new(sender)))
+    }
+
+    /**
+        Constructor
+        @params
+            publisher: the market data publisher (more publishers can be added with add_publisher)
+        @return
+            New matching engine without any order book
+    */
+    pub fn with_publisher(publisher: Box<dyn MarketDataPublisher>) -> MatchingEngine {
     	MatchingEngine {
-            sells_by_price: HashMap::new(),
-            buys_by_price: HashMap::new(),
+            books: HashMap::new(),
+            triggers: HashMap::new(),
+            last_prices: HashMap::new(),
+            order_index: HashMap::new(),
             id_count: 0,
-            socket: UdpSocket::bind(SERVER_ADDRESS).unwrap(),
-            send_channel: sender.clone(),
+            trade_id_count: 0,
+            priority_seq: 0,
+            publishers: vec![publisher],
             seq_number: 0,
+            mbp_seq_number: 0,
+            mbp_levels: HashMap::new(),
+            quote_seq_number: 0,
+            quotes: HashMap::new(),
+            bars: BarAggregator::new(&[60], 100),
+            depth_levels: 5,
+            report_channel: None,
+            tick_size: 1,
+            clock: Box::new(SystemClock),
+            self_trade_prevention: SelfTradePrevention::None,
     	}
     }
 
+    /**
+        Add a market data publisher (it receives the events emitted from now on)
+
+        @params
+            publisher: the market data publisher
+    */
+    pub fn add_publisher(&mut self, publisher: Box<dyn MarketDataPublisher>) {
+        self.publishers.push(publisher);
+    }
+
+    /**
+        Set the channel to which Execution Reports are sent
+        (acknowledgements, cancellations, trades and rejections of order entry requests)
+
+        @params
+            sender: a channel to send Execution Reports
+    */
+    pub fn set_report_channel(&mut self, sender: &mpsc::Sender<ExecutionReport>) {
+        self.report_channel = Some(sender.clone());
+    }
+
+    /**
+        Set the clock stamping orders, trades and Execution Reports
+        (e.g, a SimulatedClock so that scenario runs and tests are reproducible)
+
+        @params
+            clock: the new clock
+    */
+    pub fn set_clock(&mut self, clock: Box<dyn Clock>) {
+        self.clock = clock;
+    }
+
+    // Return a copy of the clock stamping orders, trades and Execution Reports
+    pub fn get_clock(&self) -> Box<dyn Clock> {
+        self.clock.clone_box()
+    }
+
+    /**
+        Set the number of price levels (per side) of the depth feed
+
+        @params
+            m_depth_levels: new number of price levels (0 = NO depth feed)
+    */
+    pub fn set_depth_levels(&mut self, m_depth_levels: usize) {
+        self.depth_levels = m_depth_levels;
+    }
+
+    /**
+        Set the lengths of the OHLCV bars (the bars built so far are dropped)
+
+        @params
+            intervals: new lengths (in seconds) of the bars (empty = NO bar)
+            history: number of completed bars kept per instrument and interval
+    */
+    pub fn set_bar_intervals(&mut self, intervals: &[u64], history: usize) {
+        self.bars = BarAggregator::new(intervals, history);
+    }
+
+    /**
+        This function publishes the OHLCV bars whose interval is over at the time of the clock (bar feed),
+        so that the last bar of a quiet instrument is published without waiting for a later trade
+        (e.g, called every second)
+    */
+    pub fn flush_bars(&mut self) {
+        for bar in self.bars.flush(self.clock.now_ms()) {
+            self.publish(MarketDataEvent::Bar(bar));
+        }
+    }
+
+    /**
+        This function gets the completed OHLCV bars of an instrument
+
+        @params
+            symbol: symbol of the instrument
+            interval: length (in seconds) of the bars
+        @return
+            The most recent completed bars (oldest first), empty list if NO bar was completed yet
+    */
+    pub fn get_bars(&self, symbol: &str, interval: u64) -> Vec<Bar> {
+        self.bars.get_bars(symbol, interval)
+    }
+
+    /**
+        This function gets the OHLCV bar of the current interval of an instrument
+
+        @params
+            symbol: symbol of the instrument
+            interval: length (in seconds) of the bar
+        @return
+            - The bar (NOT completed yet)
+            - None if there was NO trade of the instrument yet
+    */
+    pub fn get_current_bar(&self, symbol: &str, interval: u64) -> Option<Bar> {
+        self.bars.get_current_bar(symbol, interval)
+    }
+
+    /**
+        This function gets the STOP and STOP LIMIT orders of an instrument waiting to be elected
+
+        @params
+            symbol: symbol of the instrument
+        @return
+            The orders, in priority order (empty if there is NO order waiting)
+    */
+    pub fn get_stop_orders(&self, symbol: &str) -> Vec<Order> {
+        self.triggers.get(symbol).map(|triggers| triggers.get_orders()).unwrap_or_default()
+    }
+
+    /**
+        Set the tick size (minimum price increment) of the order book
+
+        @params
+            m_tick_size: new tick size (must be positive)
+    */
+    pub fn set_tick_size(&mut self, m_tick_size: i64) {
+        self.tick_size = m_tick_size;
+    }
+
+    /**
+        Set the self-trade prevention mode (applied to the orders matched from now on)
+
+        @params
+            m_mode: new self-trade prevention mode
+    */
+    pub fn set_self_trade_prevention(&mut self, m_mode: SelfTradePrevention) {
+        self.self_trade_prevention = m_mode;
+    }
+
     /**
         This function does order insertion. Before inserting an order into the order book,
         this will try to match new order with existing orders. After matchings (if any),
         if the new order is NOT fully matched, then insert the new order into the order book
 
+        Order type and time in force of the new order are honored:
+            - MARKET orders match against ANY price on the opposite side and never rest on the order book
+            - IOC orders are matched as much as possible, the remaining quantity is cancelled
+            - FOK orders are matched fully if there is enough quantity on the opposite side,
+            otherwise they are cancelled without touching the order book
+            - STOP and STOP LIMIT orders wait in the trigger book until the last trade price reaches their stop price
+            (they are elected at once if the last trade price already reached it)
+            - Post-only orders which would match are rejected (REJECT), or repriced one tick away
+            from the best price on the opposite side (REPRICE)
+            - Hidden orders rest after ALL displayed orders at their price and never appear on market data feeds
+
+        Trades elect the STOP and STOP LIMIT orders waiting in the trigger book (see trigger_stops)
+
         @params
             order: an order object for the new order to be inserted
+        @return
+            - Order object of the new order after trading finished (or waiting to be elected)
+            - List of trades resulting from the matchings, including the trades of the orders elected
+            (in the order they happened)
+            - Or the reason the order was rejected
     */
-    pub fn insert(&mut self, order: &Order) -> Order {
-        let mut cur_order = order.clone();
-        // The following is to differentiate between
-        // - a completely new order inserted
-        // - an order with ID assigned inserted (i.e, INSERT called from UPDATE, the order ID remains unchanged)
-        if cur_order.get_id() == "-1".to_string() {
-            // New order
-            cur_order.set_id(&self.id_count.to_string());
+    pub fn insert(&mut self, order: &Order) -> Result<(Order, Vec<Trade>), ExchangeError> {
+        // Reject invalid order (the order book is NOT touched)
+        if let Err(reason) = self.validate(order) {
+            self.report(ExecutionReport::rejected(&order.get_id(), order.get_side(), order.get_price(), &reason));
+            return Err(reason);
+        }
+        let mut new_order = order.clone();
+        // A post-only order must NOT match on entry (rejected, or repriced to rest)
+        match self.post_only_price(order) {
+            Ok(price) => new_order.set_price(price),
+            Err(reason) => {
+                self.report(ExecutionReport::rejected(&order.get_id(), order.get_side(), order.get_price(), &reason));
+                return Err(reason);
+            }
+        }
+        // The order is stamped when the matching engine accepts it, and ranked after ALL orders already accepted
+        new_order.set_transact_time(&self.clock.transact_time());
+        new_order.set_priority(self.next_priority());
+        // Only orders without ID are new orders, orders with ID assigned keep it
+        if new_order.get_id() == "-1" {
+            new_order.set_id(&self.id_count.to_string());
             self.id_count += 1;
         }
+        // Acknowledge the new order
+        self.report(ExecutionReport::new('0', &new_order));
 
-        if cur_order.get_side() == '1' {
-            // BUY side
-            // Look at order book and match (if possible)
-            while !self.sells_by_price.is_empty() && cur_order.get_qty() > 0 {
-                // Find LOWEST price on SELL side
-                let mut best_sell_price = i64::max_value();                
-                for &avail_price in self.sells_by_price.keys() {
-                    best_sell_price = cmp::min(best_sell_price, avail_price);
-                }
-                // Exit if there is no more SELL order 
-                // or LOWEST price on SELL side is higher than price of current order
-                if best_sell_price > cur_order.get_price() {
-                    break;
-                }
-                // Get all SELL orders at LOWEST price
-                {
-                    while true {
-                        {
-                            let best_price_orders: &mut LinkedHashMap<String, Order> = self.sells_by_price.get_mut(&best_sell_price).unwrap();
-                            if best_price_orders.is_empty() || cur_order.get_qty() == 0 {
-                                break;
-                            }
-                            let key: String;
-                            let sell_order: Order;
-                            // Get key and order object of the first order in Hash Map
-                            {
-                                let (m_key, m_sell_order) = best_price_orders.front().unwrap();
-                                key = m_key.clone();
-                                sell_order = m_sell_order.clone();
-                            }
-                            let mut min_sell = sell_order.clone();
-                            // Determine quantity matched
-                            // i.e, Minimum quantity of BUY and SELL order
-                            let qty_trade = cmp::min(min_sell.get_qty(), cur_order.get_qty());
-                            // Update the remaining quantity for SELL order
-                            let min_sell_qty = min_sell.get_qty();
-                            min_sell.set_qty(min_sell_qty - qty_trade);
-                            // Update the remaining quantity for BUY order (new order inserted)
-                            let cur_order_qty = cur_order.get_qty();
-                            cur_order.set_qty(cur_order_qty - qty_trade);
-                            // Delete SELL order (if fully matched)
-                            if min_sell.get_qty() == 0 {
-                                best_price_orders.pop_front();
-                            } else {
-                                best_price_orders.get_mut(&key).unwrap().set_qty(min_sell.get_qty());
-                            }                            
-                        }
-                        // Multicast Incremental Feed after a match
-                        self.incremental_feed(&best_sell_price);
-                    }
-                }
-                // If the list of orders becomes empty, remove the list
-                if self.sells_by_price.get(&best_sell_price).unwrap().is_empty() {
-                    self.sells_by_price.remove(&best_sell_price);
-                }
+        let symbol = new_order.get_symbol();
+        let (cur_order, mut trades) = if !new_order.is_stop() {
+            self.execute(new_order)
+        } else if self.is_triggered(&new_order) {
+            self.elect(new_order)
+        } else {
+            self.park(new_order.clone());
+            return Ok((new_order, Vec::new()));
+        };
+        trades.append(&mut self.trigger_stops(&symbol));
+        Ok((cur_order, trades))
+    }
+
+    /**
+        This function matches an order and cancels its remaining quantity if it cannot rest on the order book
+
+        @params
+            order: a valid order with ID assigned
+        @return
+            - Order object of the order after trading finished
+            - List of trades resulting from the matchings (in the order they happened)
+    */
+    fn execute(&mut self, order: Order) -> (Order, Vec<Trade>) {
+        let (cur_order, trades) = self.match_order(order);
+        // Remaining quantity which does NOT rest on the order book is cancelled
+        if cur_order.get_qty() > 0 && !cur_order.can_rest() {
+            self.report(ExecutionReport::canceled(&cur_order));
+        }
+        (cur_order, trades)
+    }
+
+    // Return true if an order would trade against a resting order of the same account (and self-trade prevention is on)
+    fn is_self_trade(&self, aggressor: &Order, resting: &Order) -> bool {
+        self.self_trade_prevention != SelfTradePrevention::None
+            && !aggressor.get_account().is_empty() && aggressor.get_account() == resting.get_account()
+    }
+
+    /**
+        This function prevents an order from trading against a resting order of the same account (see SelfTradePrevention).
+        Orders cancelled (exec type '4') or restated with a smaller quantity (exec type 'D') are reported to their owners
+
+        @params
+            aggressor: the order being matched (its remaining quantity is set to 0 if it is cancelled)
+            resting: the order at the front of the queue (same account)
+    */
+    fn prevent_self_trade(&mut self, aggressor: &mut Order, resting: &Order) {
+        // Quantity removed from each order (the WHOLE quantity if the order is cancelled)
+        let (aggressor_qty, resting_qty) = match self.self_trade_prevention {
+            SelfTradePrevention::None => return,
+            SelfTradePrevention::CancelNewest => (aggressor.get_qty(), 0),
+            SelfTradePrevention::CancelOldest => (0, resting.get_qty()),
+            SelfTradePrevention::CancelBoth => (aggressor.get_qty(), resting.get_qty()),
+            SelfTradePrevention::DecrementAndCancel => {
+                let qty = cmp::min(aggressor.get_qty(), resting.get_qty());
+                (qty, qty)
             }
-            // If remaining quantity of BUY order is non-zero
-            // push BUY order onto order book
-            if cur_order.get_qty() > 0 {
-                if !self.buys_by_price.contains_key(&cur_order.get_price()) {
-                    self.buys_by_price.insert(cur_order.get_price(), LinkedHashMap::new());
-                }
-                {
-                    let orders_list: &mut LinkedHashMap<String, Order> = self.buys_by_price.get_mut(&cur_order.get_price()).unwrap();
-                    orders_list.insert(cur_order.get_id(), cur_order.clone());
-                }
-                // Multicast Incremental Feed
-                self.incremental_feed(&cur_order.get_price());
+        };
+        if resting_qty == resting.get_qty() {
+            self.remove_order(resting);
+            self.report(ExecutionReport::canceled(resting));
+        } else if resting_qty > 0 {
+            // Resting order keeps its place in the queue (its quantity decreases)
+            let book = self.books.get_mut(&resting.get_symbol()).unwrap();
+            let updated = book.update_qty(&resting.get_id(), resting.get_qty() - resting_qty, None).unwrap();
+            let position = book.get_position(&resting.get_id()).unwrap_or(0);
+            self.incremental_feed('M', &updated, updated.get_visible_qty(), position);
+            self.report(ExecutionReport::new('D', &updated));
+        }
+        if aggressor_qty == aggressor.get_qty() {
+            self.report(ExecutionReport::canceled(aggressor));
+            aggressor.set_qty(0);
+        } else if aggressor_qty > 0 {
+            let remaining_qty = aggressor.get_qty() - aggressor_qty;
+            aggressor.set_qty(remaining_qty);
+            self.report(ExecutionReport::new('D', aggressor));
+        }
+    }
+
+    // Return true if a STOP or STOP LIMIT order is elected by the last trade price of its instrument
+    fn is_triggered(&self, order: &Order) -> bool {
+        self.last_prices.get(&order.get_symbol()).is_some_and(|&last_px| order.is_triggered(last_px))
+    }
+
+    /**
+        This function puts a STOP or STOP LIMIT order in the trigger book of its instrument (created if needed)
+
+        @params
+            order: the order waiting to be elected (with ID and priority sequence assigned)
+    */
+    fn park(&mut self, order: Order) {
+        let symbol = order.get_symbol();
+        self.order_index.insert(order.get_id(), symbol.clone());
+        self.triggers.entry(symbol.clone()).or_insert_with(|| TriggerBook::new(&symbol)).add_order(order);
+    }
+
+    /**
+        This function elects a STOP or STOP LIMIT order: it becomes a MARKET or LIMIT order (ranked after
+        ALL orders already accepted), the election is reported (exec type 'L' = TRIGGERED), then it is matched
+
+        @params
+            order: the order elected (NOT in the trigger book anymore)
+        @return
+            - Order object of the order after trading finished
+            - List of trades resulting from the matchings (in the order they happened)
+    */
+    fn elect(&mut self, mut order: Order) -> (Order, Vec<Trade>) {
+        self.order_index.remove(&order.get_id());
+        order.set_ord_type(if order.get_ord_type() == '3' { '1' } else { '2' });
+        order.set_transact_time(&self.clock.transact_time());
+        order.set_priority(self.next_priority());
+        self.report(ExecutionReport::new('L', &order));
+        self.execute(order)
+    }
+
+    /**
+        This function elects ALL STOP and STOP LIMIT orders of an instrument reached by the last trade price.
+        Orders elected by the same trade are processed in priority order (LOWEST priority sequence first),
+        and the orders elected by their trades (cascade) are processed after ALL the orders elected before them
+
+        @params
+            symbol: symbol of the instrument traded
+        @return
+            List of trades resulting from the matchings of the orders elected (in the order they happened)
+    */
+    fn trigger_stops(&mut self, symbol: &str) -> Vec<Trade> {
+        let mut trades = Vec::new();
+        let mut elected: VecDeque<Order> = VecDeque::new();
+        loop {
+            if let (Some(&last_px), Some(triggers)) = (self.last_prices.get(symbol), self.triggers.get_mut(symbol)) {
+                elected.extend(triggers.take_triggered(last_px));
             }
-        } else if cur_order.get_side() == '2' {
-            // SELL side
-            // Look at order book and match (if possible)
-            while !self.buys_by_price.is_empty() && cur_order.get_qty() > 0 {
-                // Find HIGHEST price on BUY side
-                let mut best_buy_price = -i64::max_value();                
-                for &avail_price in self.buys_by_price.keys() {
-                    best_buy_price = cmp::max(best_buy_price, avail_price);
-                }
-                // Exit if there is no more BUY order 
-                // or HIGHEST price on BUY side is lower than price of current order
-                if best_buy_price < cur_order.get_price() {
-                    break;
-                }
-                // Get all BUY orders at HIGHEST price
-                {
-                    while true {
-                        {
-                            let best_price_orders: &mut LinkedHashMap<String, Order> = self.buys_by_price.get_mut(&best_buy_price).unwrap();
-                            if best_price_orders.is_empty() || cur_order.get_qty() == 0 {
-                                break;
-                            }
-                            let key: String;
-                            let buy_order: Order;
-                            // Get key and order object of the first order in Hash Map
-                            {
-                                let (m_key, m_buy_order) = best_price_orders.front().unwrap();
-                                key = m_key.clone();
-                                buy_order = m_buy_order.clone();
-                            }
-                            let mut max_buy = buy_order.clone();
-                            // Determine quantity matched
-                            // i.e, Minimum quantity of BUY and SELL order
-                            let qty_trade = cmp::min(max_buy.get_qty(), cur_order.get_qty());
-                            // Update the remaining quantity for BUY order
-                            let max_buy_qty = max_buy.get_qty();
-                            max_buy.set_qty(max_buy_qty - qty_trade);
-                            // Update the remaining quantity for SELL order (new order inserted)
-                            let cur_order_qty = cur_order.get_qty();
-                            cur_order.set_qty(cur_order_qty - qty_trade);
-                            // Delete BUY order (if fully matched)
-                            if max_buy.get_qty() == 0 {
-                                best_price_orders.pop_front();
-                            } else {
-                                best_price_orders.get_mut(&key).unwrap().set_qty(max_buy.get_qty());
-                            }                            
-                        }
-                        // Multicast Incremental Feed after a match
-                        self.incremental_feed(&best_buy_price);                        
-                    }
-                }
-                // If the list of orders becomes empty, remove the list                
-                if self.buys_by_price.get(&best_buy_price).unwrap().is_empty() {
-                    self.buys_by_price.remove(&best_buy_price);
+            let order = match elected.pop_front() {
+                Some(order) => order,
+                None => break,
+            };
+            let (_, mut order_trades) = self.elect(order);
+            trades.append(&mut order_trades);
+        }
+        trades
+    }
+
+    /**
+        This function matches an order with existing orders and, if the order is NOT fully matched
+        (and allowed to rest), inserts it into the order book
+
+        @params
+            order: a valid order with ID assigned
+        @return
+            - Order object of the order after trading finished
+            - List of trades resulting from the matchings (in the order they happened)
+    */
+    fn match_order(&mut self, order: Order) -> (Order, Vec<Trade>) {
+        let mut trades: Vec<Trade> = Vec::new();
+        let mut cur_order = order;
+
+        // FILL OR KILL order is cancelled (NOT matched at all) if it cannot be fully matched
+        if cur_order.get_time_in_force() == '4' && self.available_qty(&cur_order) < cur_order.get_qty() {
+            return (cur_order, trades);
+        }
+
+        // Create order book for the instrument (if it does not exist yet)
+        let symbol = cur_order.get_symbol();
+        if !self.books.contains_key(&symbol) {
+            self.books.insert(symbol.clone(), OrderBook::new(&symbol));
+        }
+        let opposite_side = if cur_order.get_side() == '1' { '2' } else { '1' };
+
+        // Look at order book and match (if possible)
+        while cur_order.get_qty() > 0 {
+            // Find best price on the opposite side
+            // (LOWEST price on SELL side for a BUY order, HIGHEST price on BUY side for a SELL order)
+            // Exit if there is no more order on the opposite side
+            // or best price cannot be matched with price of current order (unless it is a MARKET order)
+            let best_price = match self.books[&symbol].best_price(opposite_side) {
+                Some(best_price) if cur_order.crosses(best_price) => best_price,
+                _ => break,
+            };
+            // Match the orders at best price, from HIGHEST priority to LOWEST priority
+            while cur_order.get_qty() > 0 {
+                // Self-trade prevention: the resting order at the front of the queue belongs to the account of current order
+                let same_account = self.books[&symbol].front(opposite_side, best_price).filter(|resting| self.is_self_trade(&cur_order, resting));
+                if let Some(resting) = same_account {
+                    self.prevent_self_trade(&mut cur_order, &resting);
+                    continue;
                 }
-            }
-            // If remaining quantity of SELL order is non-zero
-            // push SELL order onto order book
-            if cur_order.get_qty() > 0 {
-                if !self.sells_by_price.contains_key(&cur_order.get_price()) {
-                    self.sells_by_price.insert(cur_order.get_price(), LinkedHashMap::new());
+                let (mut passive, qty_trade) = match self.books.get_mut(&symbol).unwrap().fill_front(opposite_side, best_price, cur_order.get_qty()) {
+                    Some(fill) => fill,
+                    None => break,
+                };
+                // Record the trade (at the price of the resting order)
+                let mut trade = Trade::new(&self.trade_id_count.to_string(), &symbol, &cur_order.get_id(), &passive.get_id(), best_price, qty_trade, cur_order.get_side());
+                trade.set_transact_time(&self.clock.transact_time());
+                self.last_prices.insert(symbol.clone(), best_price);
+                self.trade_id_count += 1;
+                // Resting order fully matched is removed from the order book
+                if passive.get_qty() == 0 {
+                    self.order_index.remove(&passive.get_id());
                 }
-                {
-                    let orders_list: &mut LinkedHashMap<String, Order> = self.sells_by_price.get_mut(&cur_order.get_price()).unwrap();
-                    orders_list.insert(cur_order.get_id(), cur_order.clone());
+                // Multicast Incremental Feed after a match (the resting order was at the front of the queue)
+                self.incremental_feed('E', &passive, qty_trade, 0);
+                // ICEBERG order whose displayed slice is exhausted: a new slice is displayed from its reserve
+                // at the rear of the queue (it loses its priority), hence matching continues through the reserve
+                if passive.get_qty() > 0 && passive.get_visible_qty() == 0 {
+                    passive.replenish();
+                    passive.set_priority(self.next_priority());
+                    self.books.get_mut(&symbol).unwrap().add_order(passive.clone());
+                    let position = self.books[&symbol].get_position(&passive.get_id()).unwrap_or(0);
+                    self.incremental_feed('A', &passive, passive.get_visible_qty(), position);
                 }
-                // Multicast Incremental Feed
-                self.incremental_feed(&cur_order.get_price());                
+                // Update the remaining quantity of current order, then report the trade to both orders
+                let cur_order_qty = cur_order.get_qty();
+                cur_order.set_qty(cur_order_qty - qty_trade);
+                self.report(ExecutionReport::trade(&cur_order, &trade));
+                self.report(ExecutionReport::trade(&passive, &trade));
+                trades.push(trade);
             }
         }
+        // If remaining quantity of current order is non-zero
+        // push current order onto order book
+        // (MARKET, IOC and FOK orders never rest, their remaining quantity is cancelled)
+        if cur_order.get_qty() > 0 && cur_order.can_rest() {
+            // ICEBERG order displays its first slice
+            cur_order.replenish();
+            self.order_index.insert(cur_order.get_id(), symbol.clone());
+            self.books.get_mut(&symbol).unwrap().add_order(cur_order.clone());
+            // Multicast Incremental Feed
+            let position = self.books[&symbol].get_position(&cur_order.get_id()).unwrap_or(0);
+            self.incremental_feed('A', &cur_order, cur_order.get_visible_qty(), position);
+        }
 
-        // Return order object of current order after trading finished
-        cur_order
+        // Publish the trades on the Trade Feed
+        for trade in &trades {
+            self.trade_feed(trade);
+        }
+        // Multicast BBO feed (if the best bid or offer changed)
+        self.quote_feed(&symbol);
+
+        // Return order object of current order after trading finished, along with the trades
+        (cur_order, trades)
     }  
 
     /**
@@ -234,118 +565,276 @@ impl MatchingEngine {
 
         @params
             ord_id: ID of the order to be deleted
+        @return
+            - Order object of the order deleted
+            - Or the reason the request was rejected (i.e, there is no order with the specific ID)
     */
-    pub fn delete(&mut self, ord_id: &String) {
+    pub fn delete(&mut self, ord_id: &str) -> Result<Order, ExchangeError> {
         // Find existing order by order ID
-        let existing_ord: Order = self.find_order_by_id(&ord_id);
-        // Exit if there is no order with the specific ID
-        if existing_ord.get_side() == '*' {
-            ()
-        } else {
-            if existing_ord.get_side() == '1' {
-            // Buy side
-                // Remove order from the order book
-                self.buys_by_price.get_mut(&existing_ord.get_price()).unwrap().remove(ord_id);
-                // If the list of orders at that price becomes empty, then remove the list
-                if self.buys_by_price.get(&existing_ord.get_price()).unwrap().is_empty() {
-                    self.buys_by_price.remove(&existing_ord.get_price());
-                }
-            } else if existing_ord.get_side() == '2' {
-            // Sell side
-                // Remove order from the order book
-                self.sells_by_price.get_mut(&existing_ord.get_price()).unwrap().remove(ord_id);
-                // If the list of orders at that price becomes empty, then remove the list
-                if self.sells_by_price.get(&existing_ord.get_price()).unwrap().is_empty() {
-                    self.sells_by_price.remove(&existing_ord.get_price());
-                }
+        let existing_ord: Order = match self.find_order_by_id(ord_id) {
+            Ok(existing_ord) => existing_ord,
+            Err(reason) => {
+                self.report(ExecutionReport::rejected(ord_id, '*', 0, &reason));
+                return Err(reason);
             }
-            // Multicast incremental feed after deleting
-            self.incremental_feed(&existing_ord.get_price());
+        };
+        if existing_ord.is_stop() {
+            // Orders waiting to be elected are NOT on the market data feeds
+            self.unpark(&existing_ord);
+        } else {
+            self.remove_order(&existing_ord);
+            self.quote_feed(&existing_ord.get_symbol());
         }
-    }    
+        self.report(ExecutionReport::canceled(&existing_ord));
+        Ok(existing_ord)
+    }
+
+    /**
+        This function removes a STOP or STOP LIMIT order from the trigger book
+
+        @params
+            existing_ord: the order to be removed (MUST be in the trigger book)
+    */
+    fn unpark(&mut self, existing_ord: &Order) {
+        self.order_index.remove(&existing_ord.get_id());
+        self.triggers.get_mut(&existing_ord.get_symbol()).unwrap().remove_order(&existing_ord.get_id());
+    }
+
+    /**
+        This function removes an existing order from the order book
+
+        @params
+            existing_ord: the order to be removed (MUST be in the order book)
+    */
+    fn remove_order(&mut self, existing_ord: &Order) {
+        let symbol = existing_ord.get_symbol();
+        // Remove order from the order book (its position is published, hence taken before removing)
+        let position = self.books[&symbol].get_position(&existing_ord.get_id()).unwrap_or(0);
+        self.order_index.remove(&existing_ord.get_id());
+        self.books.get_mut(&symbol).unwrap().remove_order(&existing_ord.get_id());
+        // Multicast incremental feed after deleting
+        self.incremental_feed('C', existing_ord, existing_ord.get_visible_qty(), position);
+    }
 
     /**
         This function updates an order with a specific ID with a new order
 
         @params
             ord_id: ID of the order to be updated
-            order: order object for the new order
+            order: order object for the new order (only price, quantity and stop price are used,
+            the stop price of a STOP or STOP LIMIT order is kept if the new one is 0)
+        @return
+            - List of trades resulting from the update
+            (trades only happen if the price changes and the order is re-inserted, or if the order is elected)
+            - Or the reason the request was rejected
     */
-    pub fn update(&mut self, ord_id: &String, order: &Order) {
+    pub fn update(&mut self, ord_id: &str, order: &Order) -> Result<Vec<Trade>, ExchangeError> {
         // Find existing order by order ID
-        let existing_ord: Order = self.find_order_by_id(ord_id);
-        // If order not found then exit
-        if existing_ord.get_side() == '*' {
-            ()
-        }
+        let existing_ord: Order = match self.find_order_by_id(ord_id) {
+            Ok(existing_ord) => existing_ord,
+            Err(reason) => {
+                self.report(ExecutionReport::rejected(ord_id, '*', order.get_price(), &reason));
+                return Err(reason);
+            }
+        };
         let mut order_clone = order.clone();
         order_clone.set_side(existing_ord.get_side());
         order_clone.set_id(ord_id);        
-        // Compare updated order object with existing order
-        if order.get_price() == existing_ord.get_price() {
-            // Price remains as before, only QUANTITY is updated
-            if existing_ord.get_side() == '1' {
-                // Buy side
-                if order.get_qty() > existing_ord.get_qty() {
-                    // Quantity increases, then the order moves to the rear of the queue
-                    self.buys_by_price.get_mut(&existing_ord.get_price()).unwrap().remove(ord_id);
-                    self.buys_by_price.get_mut(&existing_ord.get_price()).unwrap().insert(ord_id.clone(), order_clone);
-                } else {
-                    // Quantity decreases, then the order stays in the queue and quantity is updated
-                    self.buys_by_price.get_mut(&existing_ord.get_price()).unwrap().get_mut(ord_id).unwrap().set_qty(order.get_qty());
-                }
-            } else if existing_ord.get_side() == '2' {
-                // Sell side
-                if order.get_qty() > existing_ord.get_qty() {
-                    // Quantity increases, then the order moves to the rear of the queue                    
-                    self.sells_by_price.get_mut(&existing_ord.get_price()).unwrap().remove(ord_id);
-                    self.sells_by_price.get_mut(&existing_ord.get_price()).unwrap().insert(ord_id.clone(), order_clone);
-                } else {
-                    // Quantity decreases, then the order stays in the queue and quantity is updated
-                    self.sells_by_price.get_mut(&existing_ord.get_price()).unwrap().get_mut(ord_id).unwrap().set_qty(order.get_qty());   
+        order_clone.set_transact_time(&self.clock.transact_time());
+        // The order stays on the same instrument
+        order_clone.set_symbol(&existing_ord.get_symbol());
+        // Only resting orders (and orders waiting to be elected) can be updated,
+        // hence they keep their order type and time in force (and their stop price, unless a new one is given)
+        order_clone.set_ord_type(existing_ord.get_ord_type());
+        order_clone.set_time_in_force(existing_ord.get_time_in_force());
+        if order.get_stop_px() == 0 {
+            order_clone.set_stop_px(existing_ord.get_stop_px());
+        }
+        // The order keeps its account and its post-only instruction, and stays hidden (or displayed)
+        order_clone.set_account(&existing_ord.get_account());
+        order_clone.set_post_only(existing_ord.get_post_only());
+        order_clone.set_hidden(existing_ord.is_hidden());
+        // An ICEBERG order keeps its display quantity (at most its new quantity)
+        order_clone.set_display_qty(cmp::min(existing_ord.get_display_qty(), order.get_qty()));
+        // Reject invalid price/quantity (the existing order is NOT touched)
+        if let Err(reason) = self.validate(&order_clone) {
+            self.report(ExecutionReport::rejected(ord_id, existing_ord.get_side(), order.get_price(), &reason));
+            return Err(reason);
+        }
+        if existing_ord.is_stop() {
+            // Order waiting to be elected: replaced in the trigger book (it loses its priority),
+            // elected at once if the last trade price already reached its new stop price
+            order_clone.set_priority(self.next_priority());
+            self.unpark(&existing_ord);
+            self.report(ExecutionReport::new('5', &order_clone));
+            if !self.is_triggered(&order_clone) {
+                self.park(order_clone);
+                return Ok(Vec::new());
+            }
+            let (_, mut trades) = self.elect(order_clone);
+            trades.append(&mut self.trigger_stops(&existing_ord.get_symbol()));
+            return Ok(trades);
+        }
+        // A post-only order moved to a new price must NOT match (the existing order is NOT touched if rejected)
+        if order_clone.get_price() != existing_ord.get_price() {
+            match self.post_only_price(&order_clone) {
+                Ok(price) => order_clone.set_price(price),
+                Err(reason) => {
+                    self.report(ExecutionReport::rejected(ord_id, existing_ord.get_side(), order.get_price(), &reason));
+                    return Err(reason);
                 }
             }
+        }
+        // Compare updated order object with existing order
+        if order_clone.get_price() == existing_ord.get_price() {
+            // Price remains as before, only QUANTITY is updated
+            // If quantity increases, then the order moves to the rear of the queue
+            // If quantity decreases, then the order stays in the queue and quantity is updated
+            let new_priority = if order.get_qty() > existing_ord.get_qty() { Some(self.next_priority()) } else { None };
+            order_clone.set_priority(new_priority.unwrap_or(existing_ord.get_priority()));
+            let book = self.books.get_mut(&existing_ord.get_symbol()).unwrap();
+            let updated = book.update_qty(ord_id, order.get_qty(), new_priority).unwrap();
+            let position = book.get_position(ord_id).unwrap_or(0);
             // Multicast Incremental Feed after updating
-            self.incremental_feed(&existing_ord.get_price());            
+            self.incremental_feed('M', &updated, updated.get_visible_qty(), position);
+            self.quote_feed(&existing_ord.get_symbol());
+            self.report(ExecutionReport::new('5', &order_clone));
+            Ok(Vec::new())
         } else {
             // Price changes then DELETE old order and INSERT new one
-            // * NOTE: This requires 2 operations, hence there will be 2 Incremental Feeds (for DELETE and INSERT)
-            self.delete(ord_id);
-            self.insert(&order_clone);
+            // * NOTE: This requires 2 operations, hence there will be 2 Incremental Feeds (CANCEL and ADD)
+            // The order loses its priority (ranked after ALL orders already accepted)
+            order_clone.set_priority(self.next_priority());
+            self.remove_order(&existing_ord);
+            self.report(ExecutionReport::new('5', &order_clone));
+            let (_, mut trades) = self.match_order(order_clone);
+            trades.append(&mut self.trigger_stops(&existing_ord.get_symbol()));
+            Ok(trades)
         }
     }
 
     /**
-        This function finds an order in the order book by a specific ID and returns it
-        
+        This function checks that an order can be accepted by the exchange
+
         @params
-            ord_id: order ID to find
+            order: the order to be checked
         @return
-            - Order object if FOUND
-            - DUMMY order object if NOT FOUND
-    */
-    pub fn find_order_by_id(&self, ord_id: &String) -> Order {
-        // Find order in SELL side
-        for (key, inner_hashmap) in self.sells_by_price.clone() {
-            // If found then return order object
-            if inner_hashmap.contains_key(ord_id) {
-                return inner_hashmap.get(ord_id).unwrap().clone();
+            - Nothing if the order is valid
+            - Or the reason the order must be rejected
+    */
+    fn validate(&self, order: &Order) -> Result<(), ExchangeError> {
+        if order.get_side() != '1' && order.get_side() != '2' {
+            return Err(ExchangeError::InvalidSide(order.get_side()));
+        }
+        match order.get_ord_type() {
+            '1' | '2' | '3' | '4' => {},
+            ord_type => return Err(ExchangeError::InvalidOrderType(ord_type)),
+        }
+        match order.get_time_in_force() {
+            '0' | '1' | '3' | '4' => {},
+            time_in_force => return Err(ExchangeError::InvalidTimeInForce(time_in_force)),
+        }
+        if order.get_qty() <= 0 {
+            return Err(ExchangeError::NonPositiveQuantity(order.get_qty()));
+        }
+        // A hidden order displays nothing, hence it cannot be an ICEBERG order
+        if order.get_display_qty() < 0 || order.get_display_qty() > order.get_qty() || (order.is_hidden() && order.is_iceberg()) {
+            return Err(ExchangeError::InvalidDisplayQuantity(order.get_display_qty()));
+        }
+        // Only LIMIT orders which may rest on the order book can be post-only
+        match order.get_post_only() {
+            '0' => {},
+            '1' | '2' if order.can_rest() => {},
+            post_only => return Err(ExchangeError::InvalidPostOnly(post_only)),
+        }
+        // Price matters for LIMIT and STOP LIMIT orders only
+        if order.get_ord_type() == '2' || order.get_ord_type() == '4' {
+            if order.get_price() <= 0 {
+                return Err(ExchangeError::NonPositivePrice(order.get_price()));
+            }
+            if order.get_price() % self.tick_size != 0 {
+                return Err(ExchangeError::PriceOffTick(order.get_price()));
             }
         }
-        // Find order in BUY side
-        for (key, inner_hashmap) in self.buys_by_price.clone() {
-            // If found then return order object
-            if inner_hashmap.contains_key(ord_id) {
-                return inner_hashmap.get(ord_id).unwrap().clone();
+        // Stop price matters for STOP and STOP LIMIT orders only
+        if order.is_stop() {
+            if order.get_stop_px() <= 0 {
+                return Err(ExchangeError::NonPositiveStopPrice(order.get_stop_px()));
             }
+            if order.get_stop_px() % self.tick_size != 0 {
+                return Err(ExchangeError::PriceOffTick(order.get_stop_px()));
+            }
+        }
+        Ok(())
+    }
+
+    /**
+        This function determines the price at which a post-only order can be entered without matching
+
+        @params
+            order: the order to be entered (valid)
+        @return
+            - Price of the order if it does NOT match (or if it is NOT post-only)
+            - One tick below the best SELL price (BUY order) or above the best BUY price (SELL order)
+            if a post-only order (REPRICE) would match
+            - Or the reason a post-only order (REJECT) must be rejected
+    */
+    fn post_only_price(&self, order: &Order) -> Result<i64, ExchangeError> {
+        let opposite_side = if order.get_side() == '1' { '2' } else { '1' };
+        let best_price = match self.books.get(&order.get_symbol()).and_then(|book| book.best_price(opposite_side)) {
+            Some(best_price) if order.get_post_only() != '0' && order.crosses(best_price) => best_price,
+            _ => return Ok(order.get_price()),
+        };
+        let price = if order.get_side() == '1' { best_price - self.tick_size } else { best_price + self.tick_size };
+        if order.get_post_only() == '1' || price <= 0 {
+            return Err(ExchangeError::PostOnlyWouldCross(order.get_price()));
+        }
+        Ok(price)
+    }
+
+    /**
+        This function calculates the total quantity on the opposite side of an order
+        at the prices the order can be matched with
+
+        @params
+            order: the order to be matched
+        @return
+            Total quantity available for the order to be matched with, without the orders
+            of its own account (at least the quantity of the order if it can be fully matched)
+    */
+    fn available_qty(&self, order: &Order) -> i64 {
+        match self.books.get(&order.get_symbol()) {
+            Some(book) => book.available_qty(order, self.self_trade_prevention),
+            None => 0,
+        }
+    }
+
+    /**
+        This function finds an order in the order book (or in the trigger book) by a specific ID and returns it
+        
+        @params
+            ord_id: order ID to find
+        @return
+            - Order object if FOUND
+            - UnknownOrder error if NOT FOUND
+    */
+    pub fn find_order_by_id(&self, ord_id: &str) -> Result<Order, ExchangeError> {
+        // Find the instrument of the order, then the order in its order book (or in its trigger book)
+        let found = self.order_index.get(ord_id).and_then(|symbol| {
+            self.books.get(symbol).and_then(|book| book.find_order_by_id(ord_id))
+                .or_else(|| self.triggers.get(symbol).and_then(|triggers| triggers.find_order_by_id(ord_id)))
+        });
+        // If found then return order object
+        if let Some(order) = found {
+            return Ok(order);
         }
 
-        // If not found, return DUMMY order
-        Order::new(-1, -1, '*')
+        // If not found, the order is unknown
+        Err(ExchangeError::UnknownOrder(ord_id.to_string()))
     }
 
     /**
-        This function displays the FULL order book, in increasing order of the prices.
+        This function displays the FULL order book of every instrument, in increasing order of the prices.
         At a price, orders with HIGHER priority are CLOSER to the MIDDLE.
         (i.e, For BUY side, HIGH priority to LOW priority is from RIGHT to LEFT
             For SELL side, HIGH priority to LOW priority is from LEFT to RIGHT)
@@ -360,146 +849,228 @@ impl MatchingEngine {
         "buy", "PRICE", "sell");
         println!("{:-<1$}", "", 100);
 
-        // List the BUY orders (in ascending order of price)
-        let mut last_price = -i64::max_value();
-        while true {
-            // Initialize next price in increasing order on BUY side to be INFINITY 
-            let mut cur_price = i64::max_value();
-            // Find the next price on BUY side
-            for price in self.buys_by_price.keys(){
-                if *price > last_price {
-                    cur_price = cmp::min(cur_price, *price);
-                }
-            }
-            // If reached the maximum price on BUY side (i.e, ALL BUY orders are listed), then exit
-            if cur_price == i64::max_value() {
-                break;
-            }
-            // Update last price listed for next iteration
-            last_price = cur_price;
-            // Get BUY orders at current price
-            let mut buy_vec = Vec::new();
-            let buy_orders: & LinkedHashMap<String, Order> = self.buys_by_price.get(&cur_price).unwrap();
-            for order in buy_orders.values() {
-                buy_vec.push(order.clone());
-            }
-            // Reverse the vector to print orders with HIGHER priority CLOSER to the MIDDLE
-            buy_vec.reverse();
-            // Print the orders
-            let mut cur_line = String::new();
-            for (index, order) in buy_vec.iter().enumerate() {
-                if index > 0 {
-                    cur_line.push(' ');
-                }
-                cur_line.push_str((order.get_qty().to_string() + "(ID: " + order.get_id().as_str() + ")").as_str());
+        // List the orders of every instrument (in alphabetical order of the symbols)
+        let mut symbols: Vec<&String> = self.books.keys().collect();
+        symbols.sort();
+        for symbol in symbols {
+            let book = &self.books[symbol];
+            println!("SYMBOL: {}", book.get_symbol());
+
+            // List the BUY orders (in ascending order of price)
+            for (cur_price, mut buy_vec) in book.get_levels('1') {
+                // Reverse the vector to print orders with HIGHER priority CLOSER to the MIDDLE
+                buy_vec.reverse();
+                println!("| {0: >40} | {1: ^10} | {2: <40} |", 
+                MatchingEngine::format_orders(&buy_vec), cur_price, "");
             }
-            println!("| {0: >40} | {1: ^10} | {2: <40} |", 
-            cur_line, cur_price, "");
-        }
 
-        println!();
+            println!();
 
-        // List the SELL orders (in ascending order of price)
-        let mut last_price = -i64::max_value();
-        while true {
-            // Initialize next price in increasing order on SELL side to be INFINITY             
-            let mut cur_price = i64::max_value();
-            // Find the next price on SELL side            
-            for price in self.sells_by_price.keys(){
-                if *price > last_price {
-                    cur_price = cmp::min(cur_price, *price);
-                }
-            }
-            // If reached the maximum price on SELL side (i.e, ALL SELL orders are listed), then exit
-            if cur_price == i64::max_value() {
-                break;
+            // List the SELL orders (in ascending order of price)
+            for (cur_price, sell_vec) in book.get_levels('2') {
+                println!("| {0: >40} | {1: ^10} | {2: <40} |", 
+                "", cur_price, MatchingEngine::format_orders(&sell_vec));
             }
-            // Update last price listed for next iteration
-            last_price = cur_price;
-            // Get SELL orders at current price
-            let mut sell_vec = Vec::new();
-            let sell_orders: & LinkedHashMap<String, Order> = self.sells_by_price.get(&cur_price).unwrap();
-            for order in sell_orders.values() {
-                sell_vec.push(order.clone());
-            }
-            // Print the orders
-            let mut cur_line = String::new();
-            for (index, order) in sell_vec.iter().enumerate() {
-                if index > 0 {
-                    cur_line.push(' ');
-                }
-                cur_line.push_str((order.get_qty().to_string() + "(ID: " + order.get_id().as_str() + ")").as_str());
+
+            println!("{:-<1$}", "", 100);
+        }
+
+        // List the STOP and STOP LIMIT orders waiting to be elected (in priority order)
+        let mut symbols: Vec<&String> = self.triggers.keys().collect();
+        symbols.sort();
+        for symbol in symbols {
+            let triggers = &self.triggers[symbol];
+            for order in triggers.get_orders() {
+                println!("STOP ORDER (SYMBOL: {}, ID: {}): {} {} at stop {}{}", triggers.get_symbol(), order.get_id(),
+                    if order.get_side() == '1' { "buy" } else { "sell" }, order.get_qty(), order.get_stop_px(),
+                    if order.get_ord_type() == '4' { format!(", limit {}", order.get_price()) } else { String::new() });
             }
-            println!("| {0: >40} | {1: ^10} | {2: <40} |", 
-            "", cur_price, cur_line);
         }
     }
 
     /**
-        This function gets ALL the orders at a price
+        This function formats the orders at a price for display
+        (e.g, "10(ID: 0) 5(ID: 3)")
 
         @params
-            price: Price at which user wants to get the orders
+            orders: the orders to be displayed
         @return
-            - A list of orders 
-            - Empty list if there is NO order at that price
-
-        * NOTE: ALL orders at the price will be on ONE side.
-        Because if they are on both side, they should already be matched.
-    */
-    fn get_orders_by_price(&self, price: &i64) -> Vec<Order> {
-        // Get all buy orders at a specific price (A Linked Hash Map)
-        let buy_orders = self.buys_by_price.get(&price);
-        // Convert Linked Hash Map into a Vector
-        let mut buys_vec: Vec<Order> = Vec::new();
-        if buy_orders != None {
-            for order in buy_orders.unwrap().values() {
-                buys_vec.push(order.clone());
+            The orders in ONE line
+    */
+    fn format_orders(orders: &[Order]) -> String {
+        let mut cur_line = String::new();
+        for (index, order) in orders.iter().enumerate() {
+            if index > 0 {
+                cur_line.push(' ');
             }
-            return buys_vec.clone();
-        }
-        // Get all sell orders at a specific price (A Linked Hash Map)
-        let sell_orders = self.sells_by_price.get(&price);
-        // Convert Linked Hash Map into a Vector
-        let mut sells_vec: Vec<Order> = Vec::new();
-        if sell_orders != None {
-            for order in sell_orders.unwrap().values() {
-                sells_vec.push(order.clone());
+            // ICEBERG order: displayed quantity / total quantity
+            if order.is_iceberg() {
+                cur_line.push_str((order.get_visible_qty().to_string() + "/").as_str());
             }
-            return sells_vec.clone();
-        }        
-        // Return empty list
-        Vec::new()
+            cur_line.push_str((order.get_qty().to_string() + "(ID: " + order.get_id().as_str()
+                + if order.is_hidden() { ", hidden)" } else { ")" }).as_str());
+        }
+        cur_line
     }
 
     /**
-        This function multicasts Incremental Feed to the multicast group
+        This function publishes Incremental Feed (what happened to ONE order, and latest status at its price)
 
         @params
-            price_affected: The price at which orders were matched during the previous operation
+            action: What happened to the order ('A' = ADD, 'M' = MODIFY, 'C' = CANCEL, 'E' = EXECUTE)
+            order: The order affected (after the action)
+            qty: The quantity of the action (see IncrementalMessage)
+            position: The priority of the order in the queue at its price (see IncrementalMessage)
     */
-    fn incremental_feed(&mut self, price_affected: &i64) {
+    fn incremental_feed(&mut self, action: char, order: &Order, qty: i64, position: i64) {
+        // Hidden orders never appear on the feeds (they do NOT change the price levels either)
+        if order.is_hidden() {
+            return;
+        }
+        let symbol = &order.get_symbol();
+        let price_affected = order.get_price();
         // Update sequential number for Incremental Feed
         self.seq_number += 1;
+        // Get all the displayed orders at the price affected (as displayed, the reserve of ICEBERG orders is hidden)
+        let orders_vec = match self.books.get(symbol) {
+            Some(book) => book.get_orders_by_price(price_affected).iter()
+                .filter(|resting_order| !resting_order.is_hidden())
+                .map(Order::displayed)
+                .collect(),
+            None => Vec::new(),
+        };
         // Create an Incremental Message object with 
-        let message = IncrementalMessage::new(*price_affected, self.seq_number, self.get_orders_by_price(&price_affected));
-        // Convert incremental feed to JSON format for multicasting
-        let incre_feed = serde_json::to_string(&message).unwrap();
-        // Multicast latest status at the price of the new order
-        self.multicast(incre_feed.clone());
-        // Send update info to Recovery thread
-        self.send_channel.send(incre_feed.clone());
+        let mut message = IncrementalMessage::new(symbol, price_affected, self.seq_number, orders_vec);
+        message.set_order_action(action, &order.get_id(), order.get_side(), qty, position);
+        self.publish(MarketDataEvent::Incremental(message));
+        // Same modification, aggregated by price level
+        self.price_level_feed(symbol, price_affected);
+    }
+
+    /**
+        This function publishes the MBP feed (price level at the price affected, aggregated)
+        and the depth feed (if the price level is one of the best price levels)
+
+        @params
+            symbol: The symbol of the instrument affected during the previous operation
+            price_affected: The price at which orders were modified during the previous operation
+    */
+    fn price_level_feed(&mut self, symbol: &str, price_affected: i64) {
+        let key = (symbol.to_string(), price_affected);
+        let level = self.books.get(symbol).and_then(|book| book.get_level(price_affected));
+        let previous_side = self.mbp_levels.get(&key).cloned();
+        let mut messages = Vec::new();
+        // Price level removed (or moved to the other side)
+        if let Some(previous_side) = previous_side {
+            if level.as_ref().is_none_or(|&(side, _)| side != previous_side) {
+                self.mbp_levels.remove(&key);
+                messages.push(('2', previous_side, PriceLevel::new(price_affected, 0, 0)));
+            }
+        }
+        // Price level created or modified
+        if let Some((side, level)) = level {
+            let action = if previous_side == Some(side) { '1' } else { '0' };
+            self.mbp_levels.insert(key, side);
+            messages.push((action, side, level));
+        }
+        if messages.is_empty() {
+            return;
+        }
+        let mut sides = Vec::new();
+        for (action, side, level) in messages {
+            self.mbp_seq_number += 1;
+            sides.push(side);
+            let message = PriceLevelMessage::new(symbol, self.mbp_seq_number, action, side, level);
+            self.publish(MarketDataEvent::PriceLevel(message));
+        }
+
+        // Depth feed, if the price affected is (or was) one of the best price levels
+        if self.depth_levels == 0 {
+            return;
+        }
+        let (buys, sells) = match self.books.get(symbol) {
+            Some(book) => (book.get_depth('1', self.depth_levels), book.get_depth('2', self.depth_levels)),
+            None => return,
+        };
+        let in_depth = |levels: &Vec<PriceLevel>, side: char| match levels.last() {
+            Some(worst) if levels.len() == self.depth_levels => {
+                if side == '1' { price_affected >= worst.get_price() } else { price_affected <= worst.get_price() }
+            }
+            _ => true,
+        };
+        if sides.iter().any(|&side| in_depth(if side == '1' { &buys } else { &sells }, side)) {
+            let message = DepthMessage::new(symbol, self.mbp_seq_number, buys, sells);
+            self.publish(MarketDataEvent::Depth(message));
+        }
+    }
+
+    /**
+        This function publishes the BBO feed (best bid and best offer of an instrument),
+        if one of them changed since the last quote of the instrument
+
+        @params
+            symbol: The symbol of the instrument affected during the previous operation
+    */
+    fn quote_feed(&mut self, symbol: &str) {
+        let best = |book: &OrderBook, side: char| match book.get_depth(side, 1).first() {
+            Some(level) => (level.get_price(), level.get_qty()),
+            None => (0, 0),
+        };
+        let quote: Quote = match self.books.get(symbol) {
+            Some(book) => (best(book, '1'), best(book, '2')),
+            None => return,
+        };
+        // An instrument without any quote yet has an empty book on both sides
+        if self.quotes.get(symbol).cloned().unwrap_or(((0, 0), (0, 0))) == quote {
+            return;
+        }
+        self.quotes.insert(symbol.to_string(), quote);
+        self.quote_seq_number += 1;
+        let message = QuoteMessage::new(symbol, self.quote_seq_number, quote.0, quote.1);
+        self.publish(MarketDataEvent::Quote(message));
+    }
+
+    // Return a new priority sequence (HIGHER than ALL the sequences assigned before)
+    fn next_priority(&mut self) -> i64 {
+        self.priority_seq += 1;
+        self.priority_seq
+    }
+
+    /**
+        This function stamps an Execution Report with the time of the clock and sends it to the report channel (if any)
+
+        @params
+            report: the Execution Report to be sent
+    */
+    fn report(&self, mut report: ExecutionReport) {
+        if let Some(ref channel) = self.report_channel {
+            report.set_transact_time(&self.clock.transact_time());
+            let _ = channel.send(report);
+        }
+    }
+
+    /**
+        This function publishes a trade (Trade Feed) and the OHLCV bars completed by the trade (bar feed)
+
+        @params
+            trade: the trade to be published
+    */
+    fn trade_feed(&mut self, trade: &Trade) {
+        self.publish(MarketDataEvent::Trade(trade.clone()));
+        for bar in self.bars.add_trade(trade) {
+            self.publish(MarketDataEvent::Bar(bar));
+        }
     }
 
     /**
-    This function multicasts an Incremental Feed to INCREMENTAL_FEED_MULTICAST_GROUP_ADDRESS
+        This function publishes an event to ALL market data publishers
 
-    @params
-        contents: the contents to be published
+        @params
+            event: the event to be published
     */
-    fn multicast(&self, contents: String) {
-        // Recovery feed must be converted to bytes for multicasting        
-        let send_buffer = contents.into_bytes();
-        self.socket.send_to(&send_buffer, INCREMENTAL_FEED_MULTICAST_GROUP_ADDRESS);
+    fn publish(&mut self, event: MarketDataEvent) {
+        for publisher in &mut self.publishers {
+            publisher.publish(&event);
+        }
     }
-}
\ No newline at end of file
+}
@@ -1,2 +1,5 @@
 pub use self::matching_engine::MatchingEngine;
-mod matching_engine;
\ No newline at end of file
+#[allow(clippy::module_inception)]
+mod matching_engine;
+mod order_book;
+mod trigger_book;
\ No newline at end of file
@@ -0,0 +1,384 @@
+/*!
+    ORDER BOOK
+
+    This contains the orders of ONE instrument (symbol) in the matching engine
+*/
+
+use objects::{Order, PriceLevel, SelfTradePrevention};
+use std::cmp;
+use std::collections::{BTreeMap, HashMap};
+
+// Queue of the orders at ONE price (key = (hidden, priority sequence) -> value = order)
+type Level = BTreeMap<(bool, i64), Order>;
+
+/**
+    An order book has the following properties:
+        - symbol:
+            symbol of the instrument traded in the order book
+        - sells_by_price:
+            price levels on SELL side, ordered by price (best SELL price is the LOWEST price)
+        - buys_by_price:
+            price levels on BUY side, ordered by price (best BUY price is the HIGHEST price)
+        - locations:
+            index of ALL orders in the order book (order ID -> side, price and queue key of the order),
+            so that an order can be found without scanning the price levels
+*/
+pub struct OrderBook {
+    symbol: String,
+    /**
+        Ordered map: key = price -> value = price level (ordered map)
+        Price level: key = queue key (hidden, priority sequence) -> value = order
+        (in priority order, HIGHEST priority first, hidden orders after ALL displayed orders)
+    */
+    sells_by_price: BTreeMap<i64, Level>,
+    buys_by_price: BTreeMap<i64, Level>,
+    locations: HashMap<String, (char, i64, (bool, i64))>,
+}
+
+impl OrderBook {
+    /**
+        Constructor
+        @params
+            m_symbol: symbol of the instrument
+        @return
+            New order book without any price level
+    */
+    pub fn new(m_symbol: &str) -> OrderBook {
+        OrderBook {
+            symbol: m_symbol.to_string(),
+            sells_by_price: BTreeMap::new(),
+            buys_by_price: BTreeMap::new(),
+            locations: HashMap::new(),
+        }
+    }
+
+    // Return symbol of the instrument
+    pub fn get_symbol(&self) -> String {
+        self.symbol.clone()
+    }
+
+    // Return price levels of a side ('1' = BUY, '2' = SELL)
+    fn levels(&self, side: char) -> &BTreeMap<i64, Level> {
+        if side == '1' {
+            &self.buys_by_price
+        } else {
+            &self.sells_by_price
+        }
+    }
+
+    // Return (mutable) price levels of a side ('1' = BUY, '2' = SELL)
+    fn levels_mut(&mut self, side: char) -> &mut BTreeMap<i64, Level> {
+        if side == '1' {
+            &mut self.buys_by_price
+        } else {
+            &mut self.sells_by_price
+        }
+    }
+
+    // Return price levels of a side ('1' = BUY, '2' = SELL), from the BEST price
+    fn levels_from_best(&self, side: char) -> Box<dyn Iterator<Item = (&i64, &Level)> + '_> {
+        let levels = self.levels(side);
+        if side == '1' {
+            Box::new(levels.iter().rev())
+        } else {
+            Box::new(levels.iter())
+        }
+    }
+
+    /**
+        This function returns the best price of a side
+        (HIGHEST price on BUY side, LOWEST price on SELL side)
+
+        @params
+            side: side of the order book ('1' = BUY, '2' = SELL)
+        @return
+            - Best price
+            - None if there is NO order on that side
+    */
+    pub fn best_price(&self, side: char) -> Option<i64> {
+        if side == '1' {
+            self.buys_by_price.keys().next_back().cloned()
+        } else {
+            self.sells_by_price.keys().next().cloned()
+        }
+    }
+
+    /**
+        This function adds an order to the queue at its price, ranked by its priority sequence
+        (an order entering the order book has the HIGHEST sequence, hence it is appended to the rear of the queue,
+        or to the rear of the displayed orders if it is displayed)
+
+        @params
+            order: the order to be added (with ID and priority sequence assigned)
+    */
+    pub fn add_order(&mut self, order: Order) {
+        self.locations.insert(order.get_id(), (order.get_side(), order.get_price(), order.queue_key()));
+        self.levels_mut(order.get_side())
+            .entry(order.get_price())
+            .or_default()
+            .insert(order.queue_key(), order);
+    }
+
+    /**
+        This function removes an order from the order book
+
+        @params
+            ord_id: ID of the order to be removed
+        @return
+            - Order object of the order removed
+            - None if the order is NOT in the order book
+    */
+    pub fn remove_order(&mut self, ord_id: &str) -> Option<Order> {
+        let (side, price, priority) = self.locations.remove(ord_id)?;
+        let levels = self.levels_mut(side);
+        let order = levels.get_mut(&price).and_then(|level| level.remove(&priority));
+        // If the list of orders at that price becomes empty, then remove the list
+        if levels.get(&price).is_some_and(|level| level.is_empty()) {
+            levels.remove(&price);
+        }
+        order
+    }
+
+    /**
+        This function changes the quantity of an order in the order book
+
+        @params
+            ord_id: ID of the order to be updated
+            qty: new quantity
+            new_priority: new priority sequence if the order loses its priority
+            (it moves to the rear of the queue at its price), None if it keeps its place
+        @return
+            - Order object of the order updated
+            - None if the order is NOT in the order book
+    */
+    pub fn update_qty(&mut self, ord_id: &str, qty: i64, new_priority: Option<i64>) -> Option<Order> {
+        let &(side, price, priority) = self.locations.get(ord_id)?;
+        let level = self.levels_mut(side).get_mut(&price).unwrap();
+        match new_priority {
+            Some(new_priority) => {
+                let mut order = level.remove(&priority).unwrap();
+                order.set_qty(qty);
+                order.set_priority(new_priority);
+                // An ICEBERG order moved to the rear of the queue displays a new slice
+                order.replenish();
+                level.insert(order.queue_key(), order.clone());
+                self.locations.insert(ord_id.to_string(), (side, price, order.queue_key()));
+                Some(order)
+            }
+            None => {
+                let order = level.get_mut(&priority).unwrap();
+                order.set_qty(qty);
+                Some(order.clone())
+            }
+        }
+    }
+
+    /**
+        This function gets the first order (HIGHEST priority) at a price
+
+        @params
+            side: side of the order ('1' = BUY, '2' = SELL)
+            price: price of the order
+        @return
+            - Order object of the first order
+            - None if there is NO order at that price
+    */
+    pub fn front(&self, side: char, price: i64) -> Option<Order> {
+        self.levels(side).get(&price).and_then(|level| level.values().next()).cloned()
+    }
+
+    /**
+        This function matches the first order (HIGHEST priority) at a price with a quantity.
+        The order is removed from the order book if it is fully matched
+        (or if the displayed slice of an ICEBERG order is exhausted, the exchange replenishes it)
+
+        @params
+            side: side of the resting order ('1' = BUY, '2' = SELL)
+            price: price of the resting order
+            qty: quantity to be matched
+        @return
+            - Order object of the resting order after the match, and quantity matched
+            - None if there is NO order at that price
+    */
+    pub fn fill_front(&mut self, side: char, price: i64, qty: i64) -> Option<(Order, i64)> {
+        let (order, qty_trade) = {
+            let level = self.levels_mut(side).get_mut(&price)?;
+            // Get queue key of the first order (displayed before hidden, LOWEST sequence)
+            let priority = match level.keys().next() {
+                Some(priority) => *priority,
+                None => return None,
+            };
+            let (order, qty_trade) = {
+                let order = level.get_mut(&priority).unwrap();
+                // Determine quantity matched
+                // i.e, Minimum quantity of BUY and SELL order (displayed slice only for an ICEBERG order)
+                let qty_trade = cmp::min(order.get_visible_qty(), qty);
+                // Update the remaining quantity of the resting order
+                order.fill(qty_trade);
+                (order.clone(), qty_trade)
+            };
+            // Delete resting order (if fully matched or its displayed slice is exhausted)
+            if order.get_visible_qty() == 0 {
+                level.remove(&priority);
+            }
+            (order, qty_trade)
+        };
+        if order.get_visible_qty() == 0 {
+            self.locations.remove(&order.get_id());
+            // If the list of orders at that price becomes empty, then remove the list
+            let levels = self.levels_mut(side);
+            if levels.get(&price).is_some_and(|level| level.is_empty()) {
+                levels.remove(&price);
+            }
+        }
+        Some((order, qty_trade))
+    }
+
+    /**
+        This function calculates the total quantity on one side at the prices an order can be matched with.
+        Resting orders of the same account as the order are NEVER counted (see SelfTradePrevention):
+            - CancelOldest: they are cancelled, matching goes on with the orders behind them
+            - CancelNewest, CancelBoth, DecrementAndCancel: the order stops matching (fully) at the first one,
+            ONLY the orders ahead of it are counted (the displayed slice only for ICEBERG orders,
+            their reserve is replenished behind it)
+
+        @params
+            order: the order to be matched (on the opposite side)
+            self_trade_prevention: self-trade prevention mode of the matching engine
+        @return
+            Total quantity available for the order to be matched with
+            (calculation stops as soon as it reaches the quantity of the order)
+    */
+    pub fn available_qty(&self, order: &Order, self_trade_prevention: SelfTradePrevention) -> i64 {
+        let opposite_side = if order.get_side() == '1' { '2' } else { '1' };
+        let is_own = |resting_order: &Order| self_trade_prevention != SelfTradePrevention::None
+            && !order.get_account().is_empty() && resting_order.get_account() == order.get_account();
+        let mut total_qty = 0;
+        // Visit the price levels from the best price
+        for (price, level) in self.levels_from_best(opposite_side) {
+            if !order.crosses(*price) || total_qty >= order.get_qty() {
+                break;
+            }
+            if self_trade_prevention == SelfTradePrevention::CancelOldest {
+                total_qty += level.values().filter(|resting_order| !is_own(resting_order)).map(|resting_order| resting_order.get_qty()).sum::<i64>();
+                continue;
+            }
+            match level.values().position(&is_own) {
+                Some(own_position) => {
+                    return total_qty + level.values().take(own_position).map(|resting_order| resting_order.get_visible_qty()).sum::<i64>();
+                }
+                None => total_qty += level.values().map(|resting_order| resting_order.get_qty()).sum::<i64>(),
+            }
+        }
+        total_qty
+    }
+
+    /**
+        This function finds an order in the order book by a specific ID and returns it
+
+        @params
+            ord_id: order ID to find
+        @return
+            - Order object if FOUND
+            - None if NOT FOUND
+    */
+    pub fn find_order_by_id(&self, ord_id: &str) -> Option<Order> {
+        let &(side, price, priority) = self.locations.get(ord_id)?;
+        self.levels(side).get(&price).and_then(|level| level.get(&priority)).cloned()
+    }
+
+    /**
+        This function gets the priority of an order in the queue at its price
+
+        @params
+            ord_id: order ID to find
+        @return
+            - Position of the order in the queue (0 = HIGHEST priority)
+            - None if NOT FOUND
+    */
+    pub fn get_position(&self, ord_id: &str) -> Option<i64> {
+        let &(side, price, priority) = self.locations.get(ord_id)?;
+        let level = self.levels(side).get(&price)?;
+        Some(level.range(..priority).count() as i64)
+    }
+
+    /**
+        This function gets ALL the orders at a price
+
+        @params
+            price: Price at which user wants to get the orders
+        @return
+            - A list of orders
+            - Empty list if there is NO order at that price
+
+        * NOTE: ALL orders at the price will be on ONE side.
+          Because if they are on both side, they should already be matched.
+    */
+    pub fn get_orders_by_price(&self, price: i64) -> Vec<Order> {
+        // Get all orders at a specific price (an ordered map) on BUY side, otherwise on SELL side
+        match self.buys_by_price.get(&price).or_else(|| self.sells_by_price.get(&price)) {
+            // Convert the ordered map into a Vector (in priority order)
+            Some(orders) => orders.values().cloned().collect(),
+            // Return empty list
+            None => Vec::new(),
+        }
+    }
+
+    /**
+        This function gets the price level at a price (aggregated, displayed orders only)
+
+        @params
+            price: price of the level
+        @return
+            - Side of the level and the level
+            - None if there is NO displayed order at that price
+    */
+    pub fn get_level(&self, price: i64) -> Option<(char, PriceLevel)> {
+        for side in &['1', '2'] {
+            if let Some(level) = self.levels(*side).get(&price).filter(|level| OrderBook::is_displayed(level)) {
+                return Some((*side, OrderBook::aggregate(price, level)));
+            }
+        }
+        None
+    }
+
+    /**
+        This function gets the best price levels of one side (aggregated, displayed orders only)
+
+        @params
+            side: side of the order book ('1' = BUY, '2' = SELL)
+            count: maximum number of price levels
+        @return
+            The best price levels, BEST price first (price levels with hidden orders only are left out)
+    */
+    pub fn get_depth(&self, side: char, count: usize) -> Vec<PriceLevel> {
+        self.levels_from_best(side).filter(|(_, level)| OrderBook::is_displayed(level)).take(count)
+            .map(|(price, level)| OrderBook::aggregate(*price, level))
+            .collect()
+    }
+
+    // Return total displayed quantity and number of the displayed orders of a price level (hidden orders are left out)
+    fn aggregate(price: i64, level: &Level) -> PriceLevel {
+        let displayed = level.values().filter(|order| !order.is_hidden());
+        PriceLevel::new(price, displayed.clone().map(|order| order.get_visible_qty()).sum(), displayed.count() as i64)
+    }
+
+    // Return true if a price level has at least one displayed order
+    fn is_displayed(level: &Level) -> bool {
+        level.values().any(|order| !order.is_hidden())
+    }
+
+    /**
+        This function gets the orders of one side, price level by price level
+
+        @params
+            side: side of the order book ('1' = BUY, '2' = SELL)
+        @return
+            A list of (price, list of orders at the price), in increasing order of the prices
+    */
+    pub fn get_levels(&self, side: char) -> Vec<(i64, Vec<Order>)> {
+        self.levels(side).iter()
+            .map(|(price, level)| (*price, level.values().cloned().collect()))
+            .collect()
+    }
+}
@@ -0,0 +1,114 @@
+/*!
+    TRIGGER BOOK
+
+    This contains the STOP and STOP LIMIT orders of ONE instrument (symbol) waiting to be elected
+    (they are NOT in the order book, hence NOT visible on the market data feeds)
+*/
+
+use objects::Order;
+use std::collections::{BTreeMap, HashMap};
+
+/**
+    A trigger book has the following properties:
+        - symbol:
+            symbol of the instrument of the orders
+        - orders:
+            ALL orders waiting to be elected, in priority order (LOWEST priority sequence first)
+        - locations:
+            index of ALL orders in the trigger book (order ID -> priority sequence of the order)
+*/
+pub struct TriggerBook {
+    symbol: String,
+    /**
+        Ordered map: key = priority sequence -> value = order
+    */
+    orders: BTreeMap<i64, Order>,
+    locations: HashMap<String, i64>,
+}
+
+impl TriggerBook {
+    /**
+        Constructor
+        @params
+            m_symbol: symbol of the instrument
+        @return
+            New trigger book without any order
+    */
+    pub fn new(m_symbol: &str) -> TriggerBook {
+        TriggerBook {
+            symbol: m_symbol.to_string(),
+            orders: BTreeMap::new(),
+            locations: HashMap::new(),
+        }
+    }
+
+    // Return symbol of the instrument
+    pub fn get_symbol(&self) -> String {
+        self.symbol.clone()
+    }
+
+    // Return ALL orders waiting to be elected, in priority order
+    pub fn get_orders(&self) -> Vec<Order> {
+        self.orders.values().cloned().collect()
+    }
+
+    /**
+        This function adds an order waiting to be elected
+
+        @params
+            order: the STOP or STOP LIMIT order (with ID and priority sequence assigned)
+    */
+    pub fn add_order(&mut self, order: Order) {
+        self.locations.insert(order.get_id(), order.get_priority());
+        self.orders.insert(order.get_priority(), order);
+    }
+
+    /**
+        This function removes an order from the trigger book
+
+        @params
+            ord_id: ID of the order to be removed
+        @return
+            - Order object of the order removed
+            - None if the order is NOT in the trigger book
+    */
+    pub fn remove_order(&mut self, ord_id: &str) -> Option<Order> {
+        let priority = self.locations.remove(ord_id)?;
+        self.orders.remove(&priority)
+    }
+
+    /**
+        This function finds an order in the trigger book by a specific ID and returns it
+
+        @params
+            ord_id: order ID to find
+        @return
+            - Order object if FOUND
+            - None if NOT FOUND
+    */
+    pub fn find_order_by_id(&self, ord_id: &str) -> Option<Order> {
+        self.locations.get(ord_id).and_then(|priority| self.orders.get(priority)).cloned()
+    }
+
+    /**
+        This function removes ALL orders elected by a trade price
+
+        @params
+            last_px: price of the last trade of the instrument
+        @return
+            The orders elected, in priority order (LOWEST priority sequence first, whatever their side and stop price)
+    */
+    pub fn take_triggered(&mut self, last_px: i64) -> Vec<Order> {
+        let elected: Vec<i64> = self.orders.iter()
+            .filter(|(_, order)| order.is_triggered(last_px))
+            .map(|(priority, _)| *priority)
+            .collect();
+        let mut orders = Vec::new();
+        for priority in elected {
+            let order = self.orders.remove(&priority).unwrap();
+            self.locations.remove(&order.get_id());
+            orders.push(order);
+        }
+        orders
+    }
+}
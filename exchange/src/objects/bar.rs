@@ -0,0 +1,127 @@
+/*!
+    BAR
+
+    This contains all relevant properties and functions of an OHLCV bar
+    (summary of ALL the trades of an instrument during ONE interval of time)
+*/
+
+/**
+    A bar has the following properties:
+        - symbol:
+            Symbol of the instrument traded
+        - interval:
+            Length of the interval (in seconds)
+        - start_time:
+            Time stamp of the beginning of the interval (UTC format: YYYYMMDD-HH:MM:SS.sss)
+        - open:
+            Price of the first trade of the interval
+        - high:
+            HIGHEST price traded during the interval
+        - low:
+            LOWEST price traded during the interval
+        - close:
+            Price of the last trade of the interval
+        - volume:
+            Total quantity traded during the interval
+        - trade_count:
+            Number of trades during the interval
+*/
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct Bar {
+    symbol: String,
+    interval: u64,
+    start_time: String, // UTC format: YYYYMMDD-HH:MM:SS.sss
+    open: i64,
+    high: i64,
+    low: i64,
+    close: i64,
+    volume: i64,
+    trade_count: i64,
+}
+
+impl Bar {
+    /**
+        Constructor
+        @params
+            m_symbol: symbol of the instrument traded
+            m_interval: length of the interval (in seconds)
+            m_start_time: beginning of the interval
+            m_price: price of the first trade of the interval
+            m_qty: quantity of the first trade of the interval
+        @return
+            New bar with ONE trade
+    */
+    pub fn new(m_symbol: &str, m_interval: u64, m_start_time: &str, m_price: i64, m_qty: i64) -> Bar {
+        Bar {
+            symbol: m_symbol.to_string(),
+            interval: m_interval,
+            start_time: m_start_time.to_string(),
+            open: m_price,
+            high: m_price,
+            low: m_price,
+            close: m_price,
+            volume: m_qty,
+            trade_count: 1,
+        }
+    }
+
+    /**
+        This function adds a trade of the interval to the bar
+
+        @params
+            price: price of the trade
+            qty: quantity of the trade
+    */
+    pub fn add_trade(&mut self, price: i64, qty: i64) {
+        self.high = self.high.max(price);
+        self.low = self.low.min(price);
+        self.close = price;
+        self.volume += qty;
+        self.trade_count += 1;
+    }
+
+    // Return symbol of the instrument traded
+    pub fn get_symbol(&self) -> String {
+        self.symbol.clone()
+    }
+
+    // Return length of the interval (in seconds)
+    pub fn get_interval(&self) -> u64 {
+        self.interval
+    }
+
+    // Return beginning of the interval
+    pub fn get_start_time(&self) -> String {
+        self.start_time.clone()
+    }
+
+    // Return price of the first trade
+    pub fn get_open(&self) -> i64 {
+        self.open
+    }
+
+    // Return HIGHEST price traded
+    pub fn get_high(&self) -> i64 {
+        self.high
+    }
+
+    // Return LOWEST price traded
+    pub fn get_low(&self) -> i64 {
+        self.low
+    }
+
+    // Return price of the last trade
+    pub fn get_close(&self) -> i64 {
+        self.close
+    }
+
+    // Return total quantity traded
+    pub fn get_volume(&self) -> i64 {
+        self.volume
+    }
+
+    // Return number of trades
+    pub fn get_trade_count(&self) -> i64 {
+        self.trade_count
+    }
+}
@@ -0,0 +1,68 @@
+/*!
+    DEPTH MESSAGE
+
+    This contains all relevant properties and functions of a message of the depth feed
+    (the N best price levels of both sides of an instrument, sent when ONE of them is modified)
+*/
+
+use objects::PriceLevel;
+
+/**
+    A depth message has the following properties:
+        - symbol:
+            Symbol of the instrument
+        - last_msg:
+            Sequential number of the last message of the MBP feed the depth includes
+        - buys:
+            The best price levels on BUY side (at most N, BEST price first)
+        - sells:
+            The best price levels on SELL side (at most N, BEST price first)
+*/
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct DepthMessage {
+    symbol: String,
+    last_msg: i64,
+    buys: Vec<PriceLevel>,
+    sells: Vec<PriceLevel>,
+}
+
+impl DepthMessage {
+    /**
+        Constructor
+        @params
+            m_symbol: symbol of the instrument
+            m_last_msg: sequential number of the last message of the MBP feed
+            m_buys: the best price levels on BUY side
+            m_sells: the best price levels on SELL side
+        @return
+            New depth message
+    */
+    pub fn new(m_symbol: &str, m_last_msg: i64, m_buys: Vec<PriceLevel>, m_sells: Vec<PriceLevel>) -> DepthMessage {
+        DepthMessage {
+            symbol: m_symbol.to_string(),
+            last_msg: m_last_msg,
+            buys: m_buys,
+            sells: m_sells,
+        }
+    }
+
+    // Return symbol of the instrument
+    pub fn get_symbol(&self) -> String {
+        self.symbol.clone()
+    }
+
+    // Return sequential number of the last message of the MBP feed
+    pub fn get_last_msg(&self) -> i64 {
+        self.last_msg
+    }
+
+    // Return the best price levels on BUY side (BEST price first)
+    pub fn get_buys(&self) -> Vec<PriceLevel> {
+        self.buys.clone()
+    }
+
+    // Return the best price levels on SELL side (BEST price first)
+    pub fn get_sells(&self) -> Vec<PriceLevel> {
+        self.sells.clone()
+    }
+}
@@ -0,0 +1,73 @@
+/*!
+    EXCHANGE ERROR
+
+    This contains all the reasons for which the exchange rejects a request
+*/
+
+use std::error::Error;
+use std::fmt;
+
+/**
+    A request is rejected by the exchange for one of the following reasons:
+        - UnknownOrder:
+            There is no order with the given ID in the order book
+        - InvalidSide:
+            Side of the order is neither '1' (BUY) nor '2' (SELL)
+        - InvalidOrderType:
+            Type of the order is not one of '1' (MARKET), '2' (LIMIT), '3' (STOP), '4' (STOP LIMIT)
+        - InvalidTimeInForce:
+            Time in force of the order is not one of '0' (DAY), '1' (GTC), '3' (IOC), '4' (FOK)
+        - NonPositiveQuantity:
+            Quantity of the order is zero or negative
+        - InvalidDisplayQuantity:
+            Display quantity of an ICEBERG order is negative or greater than the quantity of the order
+        - NonPositivePrice:
+            Price of a LIMIT order is zero or negative
+        - NonPositiveStopPrice:
+            Stop price of a STOP or STOP LIMIT order is zero or negative
+        - InvalidPostOnly:
+            Post-only instruction of the order is not one of '0' (NONE), '1' (REJECT), '2' (REPRICE),
+            or the order is post-only without being a LIMIT order which may rest on the order book
+        - PostOnlyWouldCross:
+            A post-only order (REJECT) would match on entry at its price
+        - PriceOffTick:
+            Price (or stop price) of an order is not a multiple of the tick size
+        - InvalidMessage:
+            The request could not be understood (malformed message or unknown message type)
+*/
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub enum ExchangeError {
+    UnknownOrder(String),
+    InvalidSide(char),
+    InvalidOrderType(char),
+    InvalidTimeInForce(char),
+    NonPositiveQuantity(i64),
+    InvalidDisplayQuantity(i64),
+    NonPositivePrice(i64),
+    NonPositiveStopPrice(i64),
+    InvalidPostOnly(char),
+    PostOnlyWouldCross(i64),
+    PriceOffTick(i64),
+    InvalidMessage(String),
+}
+
+impl fmt::Display for ExchangeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ExchangeError::UnknownOrder(ref id) => write!(f, "unknown order (ID: {})", id),
+            ExchangeError::InvalidSide(side) => write!(f, "invalid side '{}'", side),
+            ExchangeError::InvalidOrderType(ord_type) => write!(f, "invalid order type '{}'", ord_type),
+            ExchangeError::InvalidTimeInForce(time_in_force) => write!(f, "invalid time in force '{}'", time_in_force),
+            ExchangeError::NonPositiveQuantity(qty) => write!(f, "non-positive quantity {}", qty),
+            ExchangeError::InvalidDisplayQuantity(display_qty) => write!(f, "invalid display quantity {}", display_qty),
+            ExchangeError::NonPositivePrice(price) => write!(f, "non-positive price {}", price),
+            ExchangeError::NonPositiveStopPrice(stop_px) => write!(f, "non-positive stop price {}", stop_px),
+            ExchangeError::InvalidPostOnly(post_only) => write!(f, "invalid post-only instruction '{}'", post_only),
+            ExchangeError::PostOnlyWouldCross(price) => write!(f, "post-only order would cross at price {}", price),
+            ExchangeError::PriceOffTick(price) => write!(f, "price {} is not a multiple of the tick size", price),
+            ExchangeError::InvalidMessage(ref reason) => write!(f, "invalid message ({})", reason),
+        }
+    }
+}
+
+impl Error for ExchangeError {}
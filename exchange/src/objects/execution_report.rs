@@ -0,0 +1,193 @@
+/*!
+    EXECUTION REPORT
+
+    This contains all relevant properties and functions of an execution report
+    (the response of the exchange to an order entry request)
+*/
+use objects::{Order, Trade, ExchangeError};
+use clock::{Clock, SystemClock};
+
+/**
+    An execution report has the following properties:
+        - order_id:
+            ID of the order the report is about
+        - symbol:
+            Symbol of the instrument of the order
+        - exec_type:
+            What happened to the order
+            ('0' for NEW, '4' for CANCELED, '5' for REPLACED, '8' for REJECTED, 'F' for TRADE,
+            'L' for TRIGGERED: a STOP or STOP LIMIT order was elected,
+            'D' for RESTATED: the quantity of the order was reduced by self-trade prevention)
+        - side:
+            Side of the order ('1' for BUY, '2' for SELL)
+        - price:
+            Price of the order
+        - leaves_qty:
+            Quantity of the order which is still open for matching
+        - trade_id:
+            ID of the trade (TRADE reports only, empty otherwise)
+        - last_qty:
+            Quantity traded (TRADE reports only, 0 otherwise)
+        - last_px:
+            Price of the trade (TRADE reports only, 0 otherwise)
+        - reject_reason:
+            Reason the request was rejected (REJECTED reports only)
+        - transact_time:
+            Time stamp of the report. Assigned by the exchange at the time the report was created
+            (UTC format: YYYYMMDD-HH:MM:SS.sss)
+*/
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct ExecutionReport {
+    order_id: String,
+    symbol: String,
+    exec_type: char, // '0' = NEW, '4' = CANCELED, '5' = REPLACED, '8' = REJECTED, 'F' = TRADE, 'L' = TRIGGERED, 'D' = RESTATED
+    side: char, // '1' = BUY, '2' = SELL
+    price: i64,
+    leaves_qty: i64,
+    trade_id: String,
+    last_qty: i64,
+    last_px: i64,
+    reject_reason: Option<ExchangeError>,
+    transact_time: String, // UTC format: YYYYMMDD-HH:MM:SS.sss
+}
+
+impl ExecutionReport {
+    /**
+        Constructor
+        @params
+            m_exec_type: what happened to the order ('0' = NEW, '4' = CANCELED, '5' = REPLACED, '8' = REJECTED, 'F' = TRADE,
+            'L' = TRIGGERED, 'D' = RESTATED)
+            m_order: the order (ID, symbol, side, price and remaining quantity are taken from it)
+        @return
+            New execution report without trade details and reject reason
+    */
+    pub fn new(m_exec_type: char, m_order: &Order) -> ExecutionReport {
+        // Get current time in UTC format (the matching engine stamps it again with its own clock)
+        let cur_time = SystemClock.transact_time();
+
+        ExecutionReport {
+            order_id: m_order.get_id(),
+            symbol: m_order.get_symbol(),
+            exec_type: m_exec_type,
+            side: m_order.get_side(),
+            price: m_order.get_price(),
+            leaves_qty: m_order.get_qty(),
+            trade_id: String::new(),
+            last_qty: 0,
+            last_px: 0,
+            reject_reason: None,
+            transact_time: cur_time,
+        }
+    }
+
+    /**
+        Constructor for a CANCELED report
+        @params
+            m_order: the order cancelled
+        @return
+            New execution report with exec type CANCELED and no quantity left open
+    */
+    pub fn canceled(m_order: &Order) -> ExecutionReport {
+        let mut report = ExecutionReport::new('4', m_order);
+        report.leaves_qty = 0;
+        report
+    }
+
+    /**
+        Constructor for a REJECTED report
+        @params
+            m_order_id: ID of the order in the rejected request ("-1" for a new order)
+            m_side: side of the order in the rejected request ('*' if unknown)
+            m_price: price of the order in the rejected request (0 if unknown)
+            m_reason: reason of the rejection
+        @return
+            New execution report with exec type REJECTED and no quantity left open
+    */
+    pub fn rejected(m_order_id: &str, m_side: char, m_price: i64, m_reason: &ExchangeError) -> ExecutionReport {
+        let mut order = Order::new(0, m_price, m_side);
+        order.set_id(m_order_id);
+        let mut report = ExecutionReport::new('8', &order);
+        report.reject_reason = Some(m_reason.clone());
+        report
+    }
+
+    /**
+        Constructor for a TRADE report
+        @params
+            m_order: the order traded (after the trade)
+            m_trade: the trade
+        @return
+            New execution report with exec type TRADE and trade details
+    */
+    pub fn trade(m_order: &Order, m_trade: &Trade) -> ExecutionReport {
+        let mut report = ExecutionReport::new('F', m_order);
+        report.trade_id = m_trade.get_trade_id();
+        report.last_qty = m_trade.get_qty();
+        report.last_px = m_trade.get_price();
+        report
+    }
+
+    // Return ID of the order
+    pub fn get_order_id(&self) -> String {
+        self.order_id.clone()
+    }
+
+    // Return symbol of the instrument of the order
+    pub fn get_symbol(&self) -> String {
+        self.symbol.clone()
+    }
+
+    // Return exec type of report ('0' = NEW, '4' = CANCELED, '5' = REPLACED, '8' = REJECTED, 'F' = TRADE, 'L' = TRIGGERED, 'D' = RESTATED)
+    pub fn get_exec_type(&self) -> char {
+        self.exec_type
+    }
+
+    // Return side of the order ('1' = BUY, '2' = SELL)
+    pub fn get_side(&self) -> char {
+        self.side
+    }
+
+    // Return price of the order
+    pub fn get_price(&self) -> i64 {
+        self.price
+    }
+
+    // Return quantity of the order still open for matching
+    pub fn get_leaves_qty(&self) -> i64 {
+        self.leaves_qty
+    }
+
+    // Return ID of the trade (TRADE reports only)
+    pub fn get_trade_id(&self) -> String {
+        self.trade_id.clone()
+    }
+
+    // Return quantity traded (TRADE reports only)
+    pub fn get_last_qty(&self) -> i64 {
+        self.last_qty
+    }
+
+    // Return price of the trade (TRADE reports only)
+    pub fn get_last_px(&self) -> i64 {
+        self.last_px
+    }
+
+    // Return reason of the rejection (REJECTED reports only)
+    pub fn get_reject_reason(&self) -> Option<ExchangeError> {
+        self.reject_reason.clone()
+    }
+
+    // Return transaction time of report
+    pub fn get_transact_time(&self) -> String {
+        self.transact_time.clone()
+    }
+
+    /**
+        Set new transaction time for report
+        @params
+            m_time: new transaction time
+    */
+    pub fn set_transact_time(&mut self, m_time: &str) {
+        self.transact_time = m_time.to_string();
+    }
+}
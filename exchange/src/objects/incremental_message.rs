@@ -1,32 +1,52 @@
-/** 
+/*!
     INCREMENTAL MESSAGE
 
     This contains all relevant properties and functions of an incremental message
+    (market-by-order: ONE message per order added, modified, cancelled or executed)
 */
 
 extern crate serde;
 
-use self::serde::ser::{Serialize, Serializer, SerializeStruct};
-use self::serde::de::Deserialize;
 use objects::Order;
 
 /**
     An incremental message has the following properties:
+        - symbol:
+            Symbol of the instrument affected after an operation
         - price:               
             Price affected after an operation
             (i.e, List of orders at this price is modified (inserted new order, deleted an order, updated an order))
         - seq_number:
             Sequential number of the incremental message. Assigned by the exchange
+        - action:
+            What happened to the order ('A' for ADD, 'M' for MODIFY, 'C' for CANCEL, 'E' for EXECUTE)
+            (a price change is published as CANCEL at the old price, then ADD at the new price)
+        - order_id:
+            ID of the order
+        - side:
+            Side of the order ('1' for BUY, '2' for SELL)
+        - qty:
+            Quantity of the action: quantity of the order (ADD), new quantity of the order (MODIFY),
+            quantity removed from the order book (CANCEL), quantity traded (EXECUTE)
+        - position:
+            Priority of the order in the queue at its price (0 = HIGHEST priority), after the action
+            (before the action for CANCEL and EXECUTE)
         - orders_vec:
             A vector containing all the orders at the affected price 
             (After an operation, ONLY list orders at this price is modified.)
             At a time, at a specific price during TRADING period, orders at that price can be on ONE side only.
             Because if there are orders on both side, they will be matched immediately until one side is completely matched.
 */
-#[derive (Serialize, Deserialize)]
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
 pub struct IncrementalMessage {
+    symbol: String,
 	price: i64,
     seq_number: i64,
+    action: char, // 'A' = ADD, 'M' = MODIFY, 'C' = CANCEL, 'E' = EXECUTE
+    order_id: String,
+    side: char, // '1' = BUY, '2' = SELL
+    qty: i64,
+    position: i64,
     orders_vec: Vec<Order>,
 }
 
@@ -34,24 +54,55 @@ impl IncrementalMessage {
     /**
         Constructor
         @params 
+            symbol: symbol of the instrument affected
             p_in: price affected
             seq_number: sequential number of incremental message
             orders_vec: list of orders affected
         @return
             New incremental message with:
+            - Symbol of the instrument affected
             - Price affected
             - Sequential number assigned by the exchange
             - List of orders affected (a vector)
+            - NO order action yet (see set_order_action)
     */
-    pub fn new(p_in: i64, seq_number: i64, orders_vec: Vec<Order>) -> IncrementalMessage { 
+    pub fn new(symbol: &str, p_in: i64, seq_number: i64, orders_vec: Vec<Order>) -> IncrementalMessage { 
         // Return a new incremental message
         IncrementalMessage {
+            symbol: symbol.to_string(),
         	price: p_in,
-            seq_number: seq_number,
-            orders_vec: orders_vec,
+            seq_number,
+            action: '*',
+            order_id: String::new(),
+            side: '*',
+            qty: 0,
+            position: 0,
+            orders_vec,
         }
     }  
 
+    /**
+        Set the order action described by the message
+        @params
+            m_action: what happened to the order ('A' = ADD, 'M' = MODIFY, 'C' = CANCEL, 'E' = EXECUTE)
+            m_order_id: ID of the order
+            m_side: side of the order ('1' = BUY, '2' = SELL)
+            m_qty: quantity of the action
+            m_position: priority of the order in the queue at its price
+    */
+    pub fn set_order_action(&mut self, m_action: char, m_order_id: &str, m_side: char, m_qty: i64, m_position: i64) {
+        self.action = m_action;
+        self.order_id = m_order_id.to_string();
+        self.side = m_side;
+        self.qty = m_qty;
+        self.position = m_position;
+    }
+
+    // Return symbol of the instrument affected
+    pub fn get_symbol(&self) -> String {
+        self.symbol.clone()
+    }
+
     // Return price affected
     pub fn get_price(&self) -> i64 {
         self.price
@@ -62,6 +113,31 @@ impl IncrementalMessage {
     	self.seq_number
     }
 
+    // Return what happened to the order ('A' = ADD, 'M' = MODIFY, 'C' = CANCEL, 'E' = EXECUTE)
+    pub fn get_action(&self) -> char {
+        self.action
+    }
+
+    // Return ID of the order
+    pub fn get_order_id(&self) -> String {
+        self.order_id.clone()
+    }
+
+    // Return side of the order ('1' = BUY, '2' = SELL)
+    pub fn get_side(&self) -> char {
+        self.side
+    }
+
+    // Return quantity of the action
+    pub fn get_qty(&self) -> i64 {
+        self.qty
+    }
+
+    // Return priority of the order in the queue at its price (0 = HIGHEST priority)
+    pub fn get_position(&self) -> i64 {
+        self.position
+    }
+
     // Return list of orders affected
     pub fn get_orders(&self) -> Vec<Order> {
     	self.orders_vec.clone()
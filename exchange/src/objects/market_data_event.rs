@@ -0,0 +1,32 @@
+/*!
+    MARKET DATA EVENT
+
+    This contains all events published by the matching engine to the market data publishers
+*/
+
+use objects::{IncrementalMessage, Trade, PriceLevelMessage, DepthMessage, QuoteMessage, Bar};
+
+/**
+    A market data event is one of:
+        - Incremental:
+            the orders at ONE price of an instrument were modified (Incremental Feed)
+        - Trade:
+            two orders were matched (Trade Feed)
+        - PriceLevel:
+            ONE price level of an instrument was modified (MBP feed)
+        - Depth:
+            ONE of the best price levels of an instrument was modified (depth feed)
+        - Quote:
+            the best bid or the best offer of an instrument changed (BBO feed)
+        - Bar:
+            an OHLCV bar of an instrument was completed (bar feed)
+*/
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub enum MarketDataEvent {
+    Incremental(IncrementalMessage),
+    Trade(Trade),
+    PriceLevel(PriceLevelMessage),
+    Depth(DepthMessage),
+    Quote(QuoteMessage),
+    Bar(Bar),
+}
@@ -1,6 +1,34 @@
 pub use self::order::Order;
 pub use self::incremental_message::IncrementalMessage;
 pub use self::recovery_feed::RecoveryFeed;
+pub use self::trade::Trade;
+pub use self::exchange_error::ExchangeError;
+pub use self::execution_report::ExecutionReport;
+pub use self::order_entry_request::OrderEntryRequest;
+pub use self::market_data_event::MarketDataEvent;
+pub use self::snapshot_fragment::{SnapshotFragment, MAX_PAYLOAD};
+pub use self::price_level::PriceLevel;
+pub use self::price_level_message::PriceLevelMessage;
+pub use self::depth_message::DepthMessage;
+pub use self::quote_message::QuoteMessage;
+pub use self::bar::Bar;
+pub use self::replay_request::ReplayRequest;
+pub use self::replay_response::ReplayResponse;
+pub use self::self_trade_prevention::SelfTradePrevention;
 mod order;
 mod incremental_message;
-mod recovery_feed;
\ No newline at end of file
+mod recovery_feed;
+mod trade;
+mod exchange_error;
+mod execution_report;
+mod order_entry_request;
+mod market_data_event;
+mod snapshot_fragment;
+mod price_level;
+mod price_level_message;
+mod depth_message;
+mod quote_message;
+mod bar;
+mod replay_request;
+mod replay_response;
+mod self_trade_prevention;
\ No newline at end of file
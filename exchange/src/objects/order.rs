@@ -3,19 +3,32 @@
 
     This contains all relevant properties and functions of an order object
 */
-extern crate time;
 extern crate serde;
 
 use std::cmp::Ordering;
-use self::serde::ser::{Serialize, Serializer, SerializeStruct};
+use clock::{Clock, SystemClock};
 
 /**
     An order has the following properties:
         - id:               
             ID of an order. Assigned by the exchange
             (e.g, ID is used when users send an UPDATE or DELETE request)
+        - symbol:
+            Symbol of the instrument traded. Assigned by users when entering the order
+            (orders without symbol trade on the default, unnamed instrument)
+        - account:
+            Account (participant) of an order. Assigned by users when entering the order
+            (orders of the same account are subject to self-trade prevention, empty = NO account)
         - order_qty:
             Quantity of an order. Assigned by users when entering the order
+            (total open quantity, including the hidden reserve of an ICEBERG order)
+        - display_qty:
+            Display quantity of an order. Assigned by users when entering the order
+            (0 = NOT an ICEBERG order, the whole quantity is displayed)
+            An ICEBERG order only displays a slice of at most display_qty on market data feeds,
+            the slice is replenished from its hidden reserve (losing its priority) when exhausted
+        - visible_qty:
+            Quantity of the slice currently displayed by an ICEBERG order. Assigned by the exchange
         - price:
             Price of an order. Assigned by users when entering the order
         - side:             
@@ -24,14 +37,59 @@ use self::serde::ser::{Serialize, Serializer, SerializeStruct};
         - transact_time:
             Time stamp of an order. Assigned by the exchange at the time the order was processed
             (UTC format: YYYYMMDD-HH:MM:SS.sss)
+        - priority:
+            Priority sequence of an order. Assigned by the exchange when the order enters the order book
+            and when it loses its priority (increasing, LOWER sequence = HIGHER priority at the same price, 0 = NOT assigned)
+        - ord_type:
+            Type of an order. Assigned by users when entering the order
+            ('1' for MARKET, '2' for LIMIT, '3' for STOP, '4' for STOP LIMIT)
+            A MARKET order ignores its price and matches against ANY price on the opposite side
+            A STOP (STOP LIMIT) order waits until the last trade price reaches its stop price,
+            then it is elected and becomes a MARKET (LIMIT) order
+        - stop_px:
+            Stop price of an order. Assigned by users when entering a STOP or STOP LIMIT order (0 otherwise)
+            + BUY side: elected when the last trade price is at or ABOVE the stop price
+            + SELL side: elected when the last trade price is at or BELOW the stop price
+        - post_only:
+            Post-only instruction of a LIMIT order. Assigned by users when entering the order
+            ('0' for NONE, '1' for REJECT, '2' for REPRICE)
+            A post-only order never takes liquidity: if it would match on entry, it is rejected (REJECT)
+            or its price is moved one tick away from the best price on the opposite side (REPRICE)
+        - hidden:
+            True if an order is fully hidden. Assigned by users when entering the order
+            A hidden order rests on the order book without ever appearing on market data feeds,
+            after ALL displayed orders at the same price
+        - time_in_force:
+            Time in force of an order. Assigned by users when entering the order
+            ('0' for DAY, '1' for GOOD TILL CANCEL, '3' for IMMEDIATE OR CANCEL, '4' for FILL OR KILL)
+            + DAY/GTC: remaining quantity (after matchings) rests on the order book
+            + IOC: remaining quantity (after matchings) is cancelled
+            + FOK: order is matched fully or not at all
 */
-#[derive(PartialEq, Clone, Serialize, Deserialize)]
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
 pub struct Order {
     id: String,
+    symbol: String,
+    #[serde(default)]
+    account: String,
     order_qty: i64,
+    #[serde(default)]
+    display_qty: i64,
+    #[serde(default)]
+    visible_qty: i64,
     price: i64,
     side: char, // '1' = BUY, '2' = SELL
     transact_time: String, // UTC format: YYYYMMDD-HH:MM:SS.sss
+    #[serde(default)]
+    priority: i64,
+    ord_type: char, // '1' = MARKET, '2' = LIMIT, '3' = STOP, '4' = STOP LIMIT
+    #[serde(default)]
+    stop_px: i64,
+    #[serde(default = "no_post_only")]
+    post_only: char, // '0' = NONE, '1' = REJECT, '2' = REPRICE
+    #[serde(default)]
+    hidden: bool,
+    time_in_force: char, // '0' = DAY, '1' = GTC, '3' = IOC, '4' = FOK
 }
 
 impl Order {
@@ -44,24 +102,39 @@ impl Order {
         @return
             New order with:
             - DUMMY ID (-1). Wait to be assigned by the exchange
+            - Default (empty) symbol
+            - NO account
             - Quantity assigned by user
+            - NOT an ICEBERG order (whole quantity displayed)
             - Price assigned by user
             - Side assigned by user
             - Transaction time is the time when order created, assigned automatically when a new order created
+            - NO priority sequence (0). Wait to be assigned by the exchange
+            - LIMIT order type
+            - NOT post-only, displayed
+            - DAY time in force
     */
     pub fn new(m_qty: i64, m_price: i64, m_side: char) -> Order { 
-        // Get current time in UTC format
-        let mut cur_time: String = time::now_utc().strftime("%Y%m%d-%H:%M:%S.%f").unwrap().to_string();
-        // Remove unnecessary characters to ensure correct UTC format
-        cur_time.truncate(21);
+        // Get current time in UTC format (the matching engine stamps it again with its own clock)
+        let cur_time = SystemClock.transact_time();
 
         // Return a new order object
         Order {
         	id: "-1".to_string(),
+            symbol: String::new(),
+            account: String::new(),
             order_qty: m_qty,
+            display_qty: 0,
+            visible_qty: 0,
             price: m_price,
             side: m_side,
             transact_time: cur_time,
+            priority: 0,
+            ord_type: '2',
+            stop_px: 0,
+            post_only: '0',
+            hidden: false,
+            time_in_force: '0',
         }
     }
 
@@ -70,11 +143,54 @@ impl Order {
         self.id.clone()
     }
     
+    // Return symbol of the instrument traded
+    pub fn get_symbol(&self) -> String {
+        self.symbol.clone()
+    }
+
+    // Return account of order (empty if NO account)
+    pub fn get_account(&self) -> String {
+        self.account.clone()
+    }
+
     // Return quantity of order 
     pub fn get_qty(&self) -> i64 {
         self.order_qty
     }    
 
+    // Return display quantity of order (0 if it is NOT an ICEBERG order)
+    pub fn get_display_qty(&self) -> i64 {
+        self.display_qty
+    }
+
+    // Return quantity of order displayed on market data feeds (the current slice of an ICEBERG order)
+    pub fn get_visible_qty(&self) -> i64 {
+        if self.is_iceberg() {
+            self.visible_qty
+        } else {
+            self.order_qty
+        }
+    }
+
+    // Return true if order is an ICEBERG order (only a slice of its quantity is displayed)
+    pub fn is_iceberg(&self) -> bool {
+        self.display_qty > 0
+    }
+
+    /**
+        Return a copy of order as displayed on market data feeds
+        (an ICEBERG order shows its current slice only, its reserve and display quantity are hidden,
+        the account of an order is NEVER displayed)
+    */
+    pub fn displayed(&self) -> Order {
+        let mut shown = self.clone();
+        shown.account = String::new();
+        shown.order_qty = self.get_visible_qty();
+        shown.display_qty = 0;
+        shown.visible_qty = 0;
+        shown
+    }
+
     // Return price of order
     pub fn get_price(&self) -> i64 {
         self.price
@@ -90,15 +206,111 @@ impl Order {
         self.transact_time.clone()
     }
 
+    // Return priority sequence of order (0 = NOT assigned)
+    pub fn get_priority(&self) -> i64 {
+        self.priority
+    }
+
+    // Return type of order ('1' = MARKET, '2' = LIMIT, '3' = STOP, '4' = STOP LIMIT)
+    pub fn get_ord_type(&self) -> char {
+        self.ord_type
+    }
+
+    // Return stop price of order (0 if it is neither a STOP nor a STOP LIMIT order)
+    pub fn get_stop_px(&self) -> i64 {
+        self.stop_px
+    }
+
+    // Return true if order is a STOP or STOP LIMIT order (NOT elected yet)
+    pub fn is_stop(&self) -> bool {
+        self.ord_type == '3' || self.ord_type == '4'
+    }
+
+    /**
+        Return true if a STOP or STOP LIMIT order is elected by a trade price
+        (BUY: trade price at or above the stop price, SELL: trade price at or below the stop price)
+        @params
+            m_last_px: price of the last trade of the instrument
+    */
+    pub fn is_triggered(&self, m_last_px: i64) -> bool {
+        if self.side == '1' {
+            m_last_px >= self.stop_px
+        } else {
+            m_last_px <= self.stop_px
+        }
+    }
+
+    // Return post-only instruction of order ('0' = NONE, '1' = REJECT, '2' = REPRICE)
+    pub fn get_post_only(&self) -> char {
+        self.post_only
+    }
+
+    // Return true if order is fully hidden (never on market data feeds)
+    pub fn is_hidden(&self) -> bool {
+        self.hidden
+    }
+
+    /**
+        Return the key of order in the queue at its price
+        (displayed orders first, then hidden orders, each ranked by priority sequence)
+    */
+    pub fn queue_key(&self) -> (bool, i64) {
+        (self.hidden, self.priority)
+    }
+
+    // Return time in force of order ('0' = DAY, '1' = GTC, '3' = IOC, '4' = FOK)
+    pub fn get_time_in_force(&self) -> char {
+        self.time_in_force
+    }
+
+    // Return true if order may rest on the order book (i.e, LIMIT order which is neither IOC nor FOK)
+    pub fn can_rest(&self) -> bool {
+        self.ord_type == '2' && self.time_in_force != '3' && self.time_in_force != '4'
+    }
+
+    /**
+        Return true if order can match at a price on the opposite side
+        (MARKET orders match at any price)
+        @params
+            m_price: price of an order on the opposite side
+    */
+    pub fn crosses(&self, m_price: i64) -> bool {
+        if self.ord_type == '1' {
+            true
+        } else if self.side == '1' {
+            m_price <= self.price
+        } else {
+            m_price >= self.price
+        }
+    }
+
     /**
         Set new ID for order (used by the exchange)
         @params
             m_id: new ID
     */
-	pub fn set_id(&mut self, m_id: &String) {
-        self.id = m_id.clone();
+	pub fn set_id(&mut self, m_id: &str) {
+        self.id = m_id.to_string();
     }    
 
+    /**
+        Set new symbol for order
+        @params
+            m_symbol: symbol of the instrument traded
+    */
+    pub fn set_symbol(&mut self, m_symbol: &str) {
+        self.symbol = m_symbol.to_string();
+    }
+
+    /**
+        Set new account for order
+        @params
+            m_account: account of the order (empty = NO account)
+    */
+    pub fn set_account(&mut self, m_account: &str) {
+        self.account = m_account.to_string();
+    }
+
     /**
         Set new quantity for order
         @params
@@ -106,8 +318,40 @@ impl Order {
     */
     pub fn set_qty(&mut self, m_qty: i64) {
         self.order_qty = m_qty;
+        // The displayed slice of an ICEBERG order never exceeds its quantity
+        if self.visible_qty > m_qty {
+            self.visible_qty = m_qty;
+        }
     }    
 
+    /**
+        Reduce quantity of order by a matched quantity
+        (the displayed slice of an ICEBERG order is reduced as well)
+        @params
+            m_qty: matched quantity
+    */
+    pub fn fill(&mut self, m_qty: i64) {
+        let remaining = self.order_qty - m_qty;
+        if self.is_iceberg() {
+            self.visible_qty -= m_qty;
+        }
+        self.set_qty(remaining);
+    }
+
+    /**
+        Set new display quantity for order
+        @params
+            m_display_qty: new display quantity (0 = NOT an ICEBERG order)
+    */
+    pub fn set_display_qty(&mut self, m_display_qty: i64) {
+        self.display_qty = m_display_qty;
+    }
+
+    // Display a new slice of an ICEBERG order from its reserve (at most display quantity)
+    pub fn replenish(&mut self) {
+        self.visible_qty = if self.display_qty < self.order_qty { self.display_qty } else { self.order_qty };
+    }
+
     /**
         Set new price for order
         @params
@@ -131,11 +375,70 @@ impl Order {
         @params
             m_time: new transaction time
     */
-    pub fn set_transact_time(&mut self, m_time: &String) {
-        self.transact_time = m_time.clone();
+    pub fn set_transact_time(&mut self, m_time: &str) {
+        self.transact_time = m_time.to_string();
+    }
+
+    /**
+        Set new priority sequence for order
+        @params
+            m_priority: new priority sequence (LOWER sequence = HIGHER priority)
+    */
+    pub fn set_priority(&mut self, m_priority: i64) {
+        self.priority = m_priority;
+    }
+
+    /**
+        Set new type for order
+        @params
+            m_ord_type: new order type ('1' = MARKET, '2' = LIMIT, '3' = STOP, '4' = STOP LIMIT)
+    */
+    pub fn set_ord_type(&mut self, m_ord_type: char) {
+        self.ord_type = m_ord_type;
+    }
+
+    /**
+        Set new stop price for order
+        @params
+            m_stop_px: new stop price (STOP and STOP LIMIT orders only)
+    */
+    pub fn set_stop_px(&mut self, m_stop_px: i64) {
+        self.stop_px = m_stop_px;
+    }
+
+    /**
+        Set new post-only instruction for order
+        @params
+            m_post_only: new post-only instruction ('0' = NONE, '1' = REJECT, '2' = REPRICE)
+    */
+    pub fn set_post_only(&mut self, m_post_only: char) {
+        self.post_only = m_post_only;
+    }
+
+    /**
+        Set order hidden or displayed
+        @params
+            m_hidden: true if order is fully hidden
+    */
+    pub fn set_hidden(&mut self, m_hidden: bool) {
+        self.hidden = m_hidden;
+    }
+
+    /**
+        Set new time in force for order
+        @params
+            m_time_in_force: new time in force ('0' = DAY, '1' = GTC, '3' = IOC, '4' = FOK)
+    */
+    pub fn set_time_in_force(&mut self, m_time_in_force: char) {
+        self.time_in_force = m_time_in_force;
     }
 }
 
+// Default post-only instruction of an order (NONE)
+fn no_post_only() -> char {
+    '0'
+}
+
 impl Eq for Order {}
 
 /**
@@ -144,41 +447,42 @@ impl Eq for Order {}
         1. Orders with different prices are ranked by their prices
             + SELL side: LOWER price, HIGHER priority
             + BUY side: HIGHER price, HIGHER priority
-        2. Else, orders with same price are ranked by their transaction time
-            + EARLIER order has HIGHER priority
+        2. Else, displayed orders have HIGHER priority than hidden orders at the same price
+        3. Else, orders with same price are ranked by their priority sequence
+            + LOWER sequence (order entered/lost its priority EARLIER) has HIGHER priority
 */
-impl PartialOrd for Order {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+impl Ord for Order {
+    fn cmp(&self, other: &Order) -> Ordering {
         if self.side == '2' {
             // Sell side
             // Determine priority of orders
             if other.price.eq(&self.price) {
                 // Consider time priority only if the orders have same price
-                // i.e, EARLIER transaction time, HIGHER priority
-                other.transact_time.partial_cmp(&self.transact_time)
+                // i.e, displayed before hidden, then LOWER priority sequence, HIGHER priority
+                other.queue_key().cmp(&self.queue_key())
             } else {
                 // Else consider price priority
                 // i.e, SELL side: LOWER price, HIGHER priority
-                other.price.partial_cmp(&self.price)
+                other.price.cmp(&self.price)
             }
         } else {
             // Buy side
             // Determine priority of orders
             if other.price.eq(&self.price) {
                 // Consider time priority only if the orders have same price
-                // i.e, EARLIER transaction time, HIGHER priority
-                other.transact_time.partial_cmp(&self.transact_time)
+                // i.e, displayed before hidden, then LOWER priority sequence, HIGHER priority
+                other.queue_key().cmp(&self.queue_key())
             } else {            
                 // Else consider price priority
                 // i.e, BUY side: HIGER price, HIGHER priority
-                self.price.partial_cmp(&other.price)
+                self.price.cmp(&other.price)
             }
         }
     }
 }
 
-impl Ord for Order {
-    fn cmp(&self, other: &Order) -> Ordering {
-        self.partial_cmp(other).unwrap()
+impl PartialOrd for Order {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
 }
\ No newline at end of file
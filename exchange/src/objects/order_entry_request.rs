@@ -0,0 +1,239 @@
+/*!
+    ORDER ENTRY REQUEST
+
+    This contains all relevant properties and functions of a request sent by a client
+    to the order entry gateway (ONE request per line, in JSON format)
+*/
+
+use objects::Order;
+
+/**
+    An order entry request has the following properties:
+        - msg_type:
+            Type of the request ('D' for NEW ORDER, 'F' for CANCEL, 'G' for REPLACE)
+        - order_id:
+            ID of the order to be cancelled/replaced (CANCEL and REPLACE requests only)
+        - symbol:
+            Symbol of the instrument (NEW ORDER requests only)
+        - account:
+            Account of the order (NEW ORDER requests only, empty = NO account)
+        - side:
+            Side of the order ('1' for BUY, '2' for SELL) (NEW ORDER requests only)
+        - ord_type:
+            Type of the order ('1' for MARKET, '2' for LIMIT, '3' for STOP, '4' for STOP LIMIT) (NEW ORDER requests only)
+        - price:
+            Price of the order (NEW ORDER and REPLACE requests)
+        - stop_px:
+            Stop price of a STOP or STOP LIMIT order (NEW ORDER and REPLACE requests, 0 = unchanged in REPLACE requests)
+        - qty:
+            Quantity of the order (NEW ORDER and REPLACE requests)
+        - display_qty:
+            Display quantity of an ICEBERG order (NEW ORDER requests only, 0 = whole quantity displayed)
+        - post_only:
+            Post-only instruction of a LIMIT order ('0' for NONE, '1' for REJECT, '2' for REPRICE) (NEW ORDER requests only)
+        - hidden:
+            True for a fully hidden order (NEW ORDER requests only)
+        - time_in_force:
+            Time in force of the order ('0' for DAY, '1' for GTC, '3' for IOC, '4' for FOK) (NEW ORDER requests only)
+
+    Properties which are not used by a request can be left out, e.g:
+        {"msg_type":"D","symbol":"AAA","side":"1","price":10,"qty":5}
+        {"msg_type":"D","symbol":"AAA","side":"1","ord_type":"4","price":12,"stop_px":11,"qty":5}
+        {"msg_type":"D","symbol":"AAA","side":"2","price":10,"qty":100,"display_qty":10}
+        {"msg_type":"D","symbol":"AAA","side":"1","price":10,"qty":5,"post_only":"2","hidden":true}
+        {"msg_type":"D","symbol":"AAA","account":"ACC1","side":"1","price":10,"qty":5}
+        {"msg_type":"G","order_id":"0","price":11,"qty":5}
+        {"msg_type":"F","order_id":"0"}
+*/
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OrderEntryRequest {
+    msg_type: char, // 'D' = NEW ORDER, 'F' = CANCEL, 'G' = REPLACE
+    order_id: String,
+    symbol: String,
+    account: String,
+    side: char, // '1' = BUY, '2' = SELL
+    ord_type: char, // '1' = MARKET, '2' = LIMIT, '3' = STOP, '4' = STOP LIMIT
+    price: i64,
+    stop_px: i64,
+    qty: i64,
+    display_qty: i64,
+    post_only: char, // '0' = NONE, '1' = REJECT, '2' = REPRICE
+    hidden: bool,
+    time_in_force: char, // '0' = DAY, '1' = GTC, '3' = IOC, '4' = FOK
+}
+
+impl Default for OrderEntryRequest {
+    fn default() -> OrderEntryRequest {
+        OrderEntryRequest {
+            msg_type: '*',
+            order_id: String::new(),
+            symbol: String::new(),
+            account: String::new(),
+            side: '*',
+            ord_type: '2',
+            price: 0,
+            stop_px: 0,
+            qty: 0,
+            display_qty: 0,
+            post_only: '0',
+            hidden: false,
+            time_in_force: '0',
+        }
+    }
+}
+
+impl OrderEntryRequest {
+    /**
+        Constructor for a NEW ORDER request
+        @params
+            m_order: the order to be inserted (ID and transaction time are ignored)
+        @return
+            New request with message type NEW ORDER
+    */
+    pub fn new_order(m_order: &Order) -> OrderEntryRequest {
+        OrderEntryRequest {
+            msg_type: 'D',
+            symbol: m_order.get_symbol(),
+            account: m_order.get_account(),
+            side: m_order.get_side(),
+            ord_type: m_order.get_ord_type(),
+            price: m_order.get_price(),
+            stop_px: m_order.get_stop_px(),
+            qty: m_order.get_qty(),
+            display_qty: m_order.get_display_qty(),
+            post_only: m_order.get_post_only(),
+            hidden: m_order.is_hidden(),
+            time_in_force: m_order.get_time_in_force(),
+            ..OrderEntryRequest::default()
+        }
+    }
+
+    /**
+        Constructor for a CANCEL request
+        @params
+            m_order_id: ID of the order to be cancelled
+        @return
+            New request with message type CANCEL
+    */
+    pub fn cancel(m_order_id: &str) -> OrderEntryRequest {
+        OrderEntryRequest {
+            msg_type: 'F',
+            order_id: m_order_id.to_string(),
+            ..OrderEntryRequest::default()
+        }
+    }
+
+    /**
+        Constructor for a REPLACE request
+        @params
+            m_order_id: ID of the order to be replaced
+            m_price: new price of the order
+            m_qty: new quantity of the order
+        @return
+            New request with message type REPLACE
+    */
+    pub fn replace(m_order_id: &str, m_price: i64, m_qty: i64) -> OrderEntryRequest {
+        OrderEntryRequest {
+            msg_type: 'G',
+            order_id: m_order_id.to_string(),
+            price: m_price,
+            qty: m_qty,
+            ..OrderEntryRequest::default()
+        }
+    }
+
+    // Return type of request ('D' = NEW ORDER, 'F' = CANCEL, 'G' = REPLACE)
+    pub fn get_msg_type(&self) -> char {
+        self.msg_type
+    }
+
+    // Return ID of the order to be cancelled/replaced
+    pub fn get_order_id(&self) -> String {
+        self.order_id.clone()
+    }
+
+    // Return symbol of the instrument
+    pub fn get_symbol(&self) -> String {
+        self.symbol.clone()
+    }
+
+    // Return account of the order (empty if NO account)
+    pub fn get_account(&self) -> String {
+        self.account.clone()
+    }
+
+    // Return side of the order ('1' = BUY, '2' = SELL)
+    pub fn get_side(&self) -> char {
+        self.side
+    }
+
+    // Return type of the order ('1' = MARKET, '2' = LIMIT, '3' = STOP, '4' = STOP LIMIT)
+    pub fn get_ord_type(&self) -> char {
+        self.ord_type
+    }
+
+    // Return price of the order
+    pub fn get_price(&self) -> i64 {
+        self.price
+    }
+
+    // Return stop price of the order (STOP and STOP LIMIT orders only)
+    pub fn get_stop_px(&self) -> i64 {
+        self.stop_px
+    }
+
+    // Return quantity of the order
+    pub fn get_qty(&self) -> i64 {
+        self.qty
+    }
+
+    // Return display quantity of the order (0 = whole quantity displayed)
+    pub fn get_display_qty(&self) -> i64 {
+        self.display_qty
+    }
+
+    // Return post-only instruction of the order ('0' = NONE, '1' = REJECT, '2' = REPRICE)
+    pub fn get_post_only(&self) -> char {
+        self.post_only
+    }
+
+    // Return true if the order is fully hidden
+    pub fn is_hidden(&self) -> bool {
+        self.hidden
+    }
+
+    // Return time in force of the order ('0' = DAY, '1' = GTC, '3' = IOC, '4' = FOK)
+    pub fn get_time_in_force(&self) -> char {
+        self.time_in_force
+    }
+
+    /**
+        Set new stop price for the order (e.g, in a REPLACE request of a STOP or STOP LIMIT order)
+        @params
+            m_stop_px: new stop price
+    */
+    pub fn set_stop_px(&mut self, m_stop_px: i64) {
+        self.stop_px = m_stop_px;
+    }
+
+    /**
+        This function creates the order described by the request
+        (side of a REPLACE request is a "DUMMY" value, determined later by Matching Engine using order ID)
+
+        @return
+            New order without ID assigned
+    */
+    pub fn to_order(&self) -> Order {
+        let mut order = Order::new(self.qty, self.price, self.side);
+        order.set_symbol(&self.symbol);
+        order.set_account(&self.account);
+        order.set_ord_type(self.ord_type);
+        order.set_stop_px(self.stop_px);
+        order.set_display_qty(self.display_qty);
+        order.set_post_only(self.post_only);
+        order.set_hidden(self.hidden);
+        order.set_time_in_force(self.time_in_force);
+        order
+    }
+}
@@ -0,0 +1,55 @@
+/*!
+    PRICE LEVEL
+
+    This contains all relevant properties and functions of a price level (ALL orders at ONE price, aggregated)
+*/
+
+/**
+    A price level has the following properties:
+        - price:
+            Price of the level
+        - qty:
+            Total quantity of the orders at the price
+        - order_count:
+            Number of orders at the price
+*/
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct PriceLevel {
+    price: i64,
+    qty: i64,
+    order_count: i64,
+}
+
+impl PriceLevel {
+    /**
+        Constructor
+        @params
+            m_price: price of the level
+            m_qty: total quantity of the orders at the price
+            m_order_count: number of orders at the price
+        @return
+            New price level
+    */
+    pub fn new(m_price: i64, m_qty: i64, m_order_count: i64) -> PriceLevel {
+        PriceLevel {
+            price: m_price,
+            qty: m_qty,
+            order_count: m_order_count,
+        }
+    }
+
+    // Return price of the level
+    pub fn get_price(&self) -> i64 {
+        self.price
+    }
+
+    // Return total quantity of the orders at the price
+    pub fn get_qty(&self) -> i64 {
+        self.qty
+    }
+
+    // Return number of orders at the price
+    pub fn get_order_count(&self) -> i64 {
+        self.order_count
+    }
+}
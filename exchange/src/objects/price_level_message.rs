@@ -0,0 +1,78 @@
+/*!
+    PRICE LEVEL MESSAGE
+
+    This contains all relevant properties and functions of a message of the market-by-price (MBP) feed
+    (ONE message per price level modified, without the orders of the level)
+*/
+
+use objects::PriceLevel;
+
+/**
+    A price level message has the following properties:
+        - symbol:
+            Symbol of the instrument
+        - seq_number:
+            Sequential number of the message on the MBP feed. Assigned by the exchange
+        - action:
+            What happened to the price level ('0' for NEW, '1' for CHANGE, '2' for DELETE)
+        - side:
+            Side of the price level ('1' for BUY, '2' for SELL)
+        - level:
+            The price level after the modification (quantity and number of orders are 0 if DELETED)
+*/
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct PriceLevelMessage {
+    symbol: String,
+    seq_number: i64,
+    action: char, // '0' = NEW, '1' = CHANGE, '2' = DELETE
+    side: char, // '1' = BUY, '2' = SELL
+    level: PriceLevel,
+}
+
+impl PriceLevelMessage {
+    /**
+        Constructor
+        @params
+            m_symbol: symbol of the instrument
+            m_seq_number: sequential number of the message
+            m_action: what happened to the price level ('0' = NEW, '1' = CHANGE, '2' = DELETE)
+            m_side: side of the price level ('1' = BUY, '2' = SELL)
+            m_level: the price level after the modification
+        @return
+            New price level message
+    */
+    pub fn new(m_symbol: &str, m_seq_number: i64, m_action: char, m_side: char, m_level: PriceLevel) -> PriceLevelMessage {
+        PriceLevelMessage {
+            symbol: m_symbol.to_string(),
+            seq_number: m_seq_number,
+            action: m_action,
+            side: m_side,
+            level: m_level,
+        }
+    }
+
+    // Return symbol of the instrument
+    pub fn get_symbol(&self) -> String {
+        self.symbol.clone()
+    }
+
+    // Return sequential number
+    pub fn get_num(&self) -> i64 {
+        self.seq_number
+    }
+
+    // Return what happened to the price level ('0' = NEW, '1' = CHANGE, '2' = DELETE)
+    pub fn get_action(&self) -> char {
+        self.action
+    }
+
+    // Return side of the price level ('1' = BUY, '2' = SELL)
+    pub fn get_side(&self) -> char {
+        self.side
+    }
+
+    // Return the price level after the modification
+    pub fn get_level(&self) -> PriceLevel {
+        self.level.clone()
+    }
+}
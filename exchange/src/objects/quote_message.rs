@@ -0,0 +1,84 @@
+/*!
+    QUOTE MESSAGE
+
+    This contains all relevant properties and functions of a message of the BBO feed
+    (best bid and best offer of an instrument, sent when ONE of them changes)
+*/
+
+/**
+    A quote message has the following properties:
+        - symbol:
+            Symbol of the instrument
+        - seq_number:
+            Sequential number of the message on the BBO feed. Assigned by the exchange
+        - bid_price:
+            Best price on BUY side (0 if there is NO order on BUY side)
+        - bid_qty:
+            Total quantity at the best price on BUY side (0 if there is NO order on BUY side)
+        - ask_price:
+            Best price on SELL side (0 if there is NO order on SELL side)
+        - ask_qty:
+            Total quantity at the best price on SELL side (0 if there is NO order on SELL side)
+*/
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct QuoteMessage {
+    symbol: String,
+    seq_number: i64,
+    bid_price: i64,
+    bid_qty: i64,
+    ask_price: i64,
+    ask_qty: i64,
+}
+
+impl QuoteMessage {
+    /**
+        Constructor
+        @params
+            m_symbol: symbol of the instrument
+            m_seq_number: sequential number of the message
+            m_bid: (price, quantity) of the best bid
+            m_ask: (price, quantity) of the best offer
+        @return
+            New quote message
+    */
+    pub fn new(m_symbol: &str, m_seq_number: i64, m_bid: (i64, i64), m_ask: (i64, i64)) -> QuoteMessage {
+        QuoteMessage {
+            symbol: m_symbol.to_string(),
+            seq_number: m_seq_number,
+            bid_price: m_bid.0,
+            bid_qty: m_bid.1,
+            ask_price: m_ask.0,
+            ask_qty: m_ask.1,
+        }
+    }
+
+    // Return symbol of the instrument
+    pub fn get_symbol(&self) -> String {
+        self.symbol.clone()
+    }
+
+    // Return sequential number of the message
+    pub fn get_num(&self) -> i64 {
+        self.seq_number
+    }
+
+    // Return best price on BUY side
+    pub fn get_bid_price(&self) -> i64 {
+        self.bid_price
+    }
+
+    // Return total quantity at the best price on BUY side
+    pub fn get_bid_qty(&self) -> i64 {
+        self.bid_qty
+    }
+
+    // Return best price on SELL side
+    pub fn get_ask_price(&self) -> i64 {
+        self.ask_price
+    }
+
+    // Return total quantity at the best price on SELL side
+    pub fn get_ask_qty(&self) -> i64 {
+        self.ask_qty
+    }
+}
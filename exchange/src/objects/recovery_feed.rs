@@ -5,19 +5,20 @@
 */
 extern crate serde;
 
-use self::serde::ser::{Serialize, Serializer, SerializeStruct};
-use self::serde::de::Deserialize;
 use objects::Order;
 
 /**
     A recovery feed has the following properties:
+        - symbol:
+            Symbol of the instrument (ONE recovery feed is sent per instrument)
         - last_msg:               
             Index of the last sequential message received BEFORE the recovery feed being built and sent
         - state:
-            A collection of lists of orders at ALL prices in the order book
+            A collection of lists of orders at ALL prices in the order book of the instrument
 */
-#[derive (Serialize, Deserialize)]
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
 pub struct RecoveryFeed {
+    symbol: String,
     last_msg: i64,
     state: Vec<Vec<Order>>,
 }
@@ -26,18 +27,36 @@ impl RecoveryFeed {
     /**
         Constructor
         @params 
+            symbol: symbol of the instrument
             last_msg_index: Index of the last sequential meesage, assigned by the exchange
             cur_state: Collection of the lists of orders at ALL prices, describing current state of the order book
         @return
             New recovery feed with:
+            - Symbol of the instrument
             - Index of the last sequential message, assigned by the exchange
             - Current state of the order book (Collection of the lists of all orders)
     */
-    pub fn new(last_msg_index: i64, cur_state: Vec<Vec<Order>>) -> RecoveryFeed { 
+    pub fn new(symbol: &str, last_msg_index: i64, cur_state: Vec<Vec<Order>>) -> RecoveryFeed { 
         // Return a new recovery feed
         RecoveryFeed {
+            symbol: symbol.to_string(),
         	last_msg: last_msg_index,
             state: cur_state,
         }
     }
+
+    // Return symbol of the instrument
+    pub fn get_symbol(&self) -> String {
+        self.symbol.clone()
+    }
+
+    // Return index of the last sequential message
+    pub fn get_last_msg(&self) -> i64 {
+        self.last_msg
+    }
+
+    // Return state of the order book (lists of orders at ALL prices)
+    pub fn get_state(&self) -> Vec<Vec<Order>> {
+        self.state.clone()
+    }
 }
\ No newline at end of file
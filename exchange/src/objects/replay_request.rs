@@ -0,0 +1,47 @@
+/*!
+    REPLAY REQUEST
+
+    This contains all relevant properties and functions of a request sent by a client to the replay server
+    (ONE request per line, in JSON format), e.g:
+        {"first":10,"last":20}
+*/
+
+/**
+    A replay request has the following properties:
+        - first:
+            Sequential number of the first Incremental message requested
+        - last:
+            Sequential number of the last Incremental message requested
+*/
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct ReplayRequest {
+    first: i64,
+    last: i64,
+}
+
+impl ReplayRequest {
+    /**
+        Constructor
+        @params
+            m_first: sequential number of the first message requested
+            m_last: sequential number of the last message requested
+        @return
+            New replay request
+    */
+    pub fn new(m_first: i64, m_last: i64) -> ReplayRequest {
+        ReplayRequest {
+            first: m_first,
+            last: m_last,
+        }
+    }
+
+    // Return sequential number of the first message requested
+    pub fn get_first(&self) -> i64 {
+        self.first
+    }
+
+    // Return sequential number of the last message requested
+    pub fn get_last(&self) -> i64 {
+        self.last
+    }
+}
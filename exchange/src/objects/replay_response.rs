@@ -0,0 +1,93 @@
+/*!
+    REPLAY RESPONSE
+
+    This contains all relevant properties and functions of the response of the replay server to a request
+    (ONE line in JSON format, followed by the Incremental messages replayed, ONE per line in JSON format)
+*/
+
+/**
+    A replay response has the following properties:
+        - first:
+            Sequential number of the first Incremental message requested
+        - last:
+            Sequential number of the last Incremental message requested
+        - status:
+            Status of the request ('0' for ACCEPTED, '8' for REJECTED)
+        - count:
+            Number of Incremental messages following the response (0 if REJECTED)
+        - reason:
+            Reason the request was rejected (e.g, the messages aged out of the replay buffer), empty if ACCEPTED
+*/
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct ReplayResponse {
+    first: i64,
+    last: i64,
+    status: char, // '0' = ACCEPTED, '8' = REJECTED
+    count: i64,
+    reason: String,
+}
+
+impl ReplayResponse {
+    /**
+        Constructor
+        @params
+            first: sequential number of the first message requested
+            last: sequential number of the last message requested
+            count: number of messages replayed
+        @return
+            New response accepting the request
+    */
+    pub fn accepted(first: i64, last: i64, count: i64) -> ReplayResponse {
+        ReplayResponse {
+            first,
+            last,
+            status: '0',
+            count,
+            reason: String::new(),
+        }
+    }
+
+    /**
+        Constructor
+        @params
+            first: sequential number of the first message requested
+            last: sequential number of the last message requested
+            reason: reason the request was rejected
+        @return
+            New response rejecting the request
+    */
+    pub fn rejected(first: i64, last: i64, reason: &str) -> ReplayResponse {
+        ReplayResponse {
+            first,
+            last,
+            status: '8',
+            count: 0,
+            reason: reason.to_string(),
+        }
+    }
+
+    // Return sequential number of the first message requested
+    pub fn get_first(&self) -> i64 {
+        self.first
+    }
+
+    // Return sequential number of the last message requested
+    pub fn get_last(&self) -> i64 {
+        self.last
+    }
+
+    // Return status of the request ('0' = ACCEPTED, '8' = REJECTED)
+    pub fn get_status(&self) -> char {
+        self.status
+    }
+
+    // Return number of messages following the response
+    pub fn get_count(&self) -> i64 {
+        self.count
+    }
+
+    // Return reason the request was rejected
+    pub fn get_reason(&self) -> String {
+        self.reason.clone()
+    }
+}
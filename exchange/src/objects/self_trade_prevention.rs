@@ -0,0 +1,49 @@
+/*!
+    SELF-TRADE PREVENTION
+
+    This contains the modes of self-trade prevention of the matching engine
+*/
+
+/**
+    An order would trade against itself when it meets a resting order of the same account
+    (orders without account never trade against themselves). The matching engine then applies one of the modes:
+        - None: the orders trade (default)
+        - CancelNewest: the remaining quantity of the incoming order is cancelled
+        - CancelOldest: the resting order is cancelled, the incoming order keeps matching
+        - CancelBoth: both orders are cancelled
+        - DecrementAndCancel: both quantities are reduced by the smaller quantity,
+        an order left without quantity is cancelled (the other one is restated)
+*/
+#[derive(PartialEq, Clone, Copy, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SelfTradePrevention {
+    #[default]
+    None,
+    CancelNewest,
+    CancelOldest,
+    CancelBoth,
+    DecrementAndCancel,
+}
+
+impl SelfTradePrevention {
+    /**
+        This function gets the mode from its name
+
+        @params
+            name: "none", "cancel_newest", "cancel_oldest", "cancel_both" or "decrement_and_cancel"
+        @return
+            - The mode
+            - Or the reason the name is invalid
+    */
+    pub fn parse(name: &str) -> Result<SelfTradePrevention, String> {
+        match name {
+            "none" => Ok(SelfTradePrevention::None),
+            "cancel_newest" => Ok(SelfTradePrevention::CancelNewest),
+            "cancel_oldest" => Ok(SelfTradePrevention::CancelOldest),
+            "cancel_both" => Ok(SelfTradePrevention::CancelBoth),
+            "decrement_and_cancel" => Ok(SelfTradePrevention::DecrementAndCancel),
+            _ => Err(format!("unknown self-trade prevention '{}' \
+                (none, cancel_newest, cancel_oldest, cancel_both or decrement_and_cancel expected)", name)),
+        }
+    }
+}
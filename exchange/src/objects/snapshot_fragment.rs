@@ -0,0 +1,102 @@
+/*!
+    SNAPSHOT FRAGMENT
+
+    This contains all relevant properties and functions of a fragment of a recovery feed
+    (a recovery feed larger than ONE datagram is split into several fragments, reassembled by clients)
+*/
+
+// Maximum size (in bytes) of the payload of a fragment (its length is encoded on 16 bits)
+pub const MAX_PAYLOAD: usize = u16::MAX as usize;
+
+/**
+    A snapshot fragment has the following properties:
+        - snapshot_id:
+            ID of the snapshot (recovery feed) the fragment belongs to. Assigned by the exchange, increasing
+        - last_msg:
+            Index of the last sequential message received BEFORE the recovery feed being built and sent
+        - fragment:
+            Number of the fragment in the snapshot (from 1 to fragments)
+        - fragments:
+            Number of fragments of the snapshot
+        - payload:
+            Part of the encoded recovery feed (the payloads of ALL fragments, in order, make the recovery feed)
+*/
+#[derive(PartialEq, Clone, Debug)]
+pub struct SnapshotFragment {
+    snapshot_id: i64,
+    last_msg: i64,
+    fragment: u32,
+    fragments: u32,
+    payload: Vec<u8>,
+}
+
+impl SnapshotFragment {
+    /**
+        Constructor
+        @params
+            m_snapshot_id: ID of the snapshot
+            m_last_msg: index of the last sequential message
+            m_fragment: number of the fragment (from 1 to m_fragments)
+            m_fragments: number of fragments of the snapshot
+            m_payload: part of the encoded recovery feed
+        @return
+            New snapshot fragment
+    */
+    pub fn new(m_snapshot_id: i64, m_last_msg: i64, m_fragment: u32, m_fragments: u32, m_payload: &[u8]) -> SnapshotFragment {
+        SnapshotFragment {
+            snapshot_id: m_snapshot_id,
+            last_msg: m_last_msg,
+            fragment: m_fragment,
+            fragments: m_fragments,
+            payload: m_payload.to_vec(),
+        }
+    }
+
+    /**
+        This function splits an encoded recovery feed into fragments
+
+        @params
+            snapshot_id: ID of the snapshot
+            last_msg: index of the last sequential message
+            data: the encoded recovery feed
+            max_payload: maximum size (in bytes) of the payload of a fragment (at most MAX_PAYLOAD)
+        @return
+            The fragments (at least ONE, even if the recovery feed is empty)
+    */
+    pub fn split(snapshot_id: i64, last_msg: i64, data: &[u8], max_payload: usize) -> Vec<SnapshotFragment> {
+        let chunks: Vec<&[u8]> = if data.is_empty() {
+            vec![data]
+        } else {
+            data.chunks(max_payload.clamp(1, MAX_PAYLOAD)).collect()
+        };
+        let fragments = chunks.len() as u32;
+        chunks.iter().enumerate()
+            .map(|(index, chunk)| SnapshotFragment::new(snapshot_id, last_msg, index as u32 + 1, fragments, chunk))
+            .collect()
+    }
+
+    // Return ID of the snapshot
+    pub fn get_snapshot_id(&self) -> i64 {
+        self.snapshot_id
+    }
+
+    // Return index of the last sequential message
+    pub fn get_last_msg(&self) -> i64 {
+        self.last_msg
+    }
+
+    // Return number of the fragment (from 1 to fragments)
+    pub fn get_fragment(&self) -> u32 {
+        self.fragment
+    }
+
+    // Return number of fragments of the snapshot
+    pub fn get_fragments(&self) -> u32 {
+        self.fragments
+    }
+
+    // Return part of the encoded recovery feed
+    pub fn get_payload(&self) -> Vec<u8> {
+        self.payload.clone()
+    }
+}
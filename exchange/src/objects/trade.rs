@@ -0,0 +1,118 @@
+/*!
+    TRADE
+
+    This contains all relevant properties and functions of a trade (execution)
+*/
+use clock::{Clock, SystemClock};
+
+/**
+    A trade has the following properties:
+        - trade_id:
+            ID of the trade. Assigned by the exchange
+        - symbol:
+            Symbol of the instrument traded
+        - aggressor_id:
+            ID of the incoming order which caused the match
+        - passive_id:
+            ID of the resting order in the order book which was matched
+        - price:
+            Price of the trade (always the price of the resting order)
+        - qty:
+            Quantity traded
+        - aggressor_side:
+            Side of the incoming order ('1' for BUY, '2' for SELL)
+        - transact_time:
+            Time stamp of the trade. Assigned by the exchange at the time the match happened
+            (UTC format: YYYYMMDD-HH:MM:SS.sss)
+*/
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct Trade {
+    trade_id: String,
+    symbol: String,
+    aggressor_id: String,
+    passive_id: String,
+    price: i64,
+    qty: i64,
+    aggressor_side: char, // '1' = BUY, '2' = SELL
+    transact_time: String, // UTC format: YYYYMMDD-HH:MM:SS.sss
+}
+
+impl Trade {
+    /**
+        Constructor
+        @params
+            m_trade_id: trade ID assigned by the exchange
+            m_symbol: symbol of the instrument traded
+            m_aggressor_id: ID of the incoming order
+            m_passive_id: ID of the resting order
+            m_price: price of the trade
+            m_qty: quantity traded
+            m_aggressor_side: side of the incoming order ('1' = BUY, '2' = SELL)
+        @return
+            New trade with transaction time being the time when the trade was created
+    */
+    pub fn new(m_trade_id: &str, m_symbol: &str, m_aggressor_id: &str, m_passive_id: &str, m_price: i64, m_qty: i64, m_aggressor_side: char) -> Trade {
+        // Get current time in UTC format (the matching engine stamps it again with its own clock)
+        let cur_time = SystemClock.transact_time();
+
+        Trade {
+            trade_id: m_trade_id.to_string(),
+            symbol: m_symbol.to_string(),
+            aggressor_id: m_aggressor_id.to_string(),
+            passive_id: m_passive_id.to_string(),
+            price: m_price,
+            qty: m_qty,
+            aggressor_side: m_aggressor_side,
+            transact_time: cur_time,
+        }
+    }
+
+    // Return ID of trade
+    pub fn get_trade_id(&self) -> String {
+        self.trade_id.clone()
+    }
+
+    // Return symbol of the instrument traded
+    pub fn get_symbol(&self) -> String {
+        self.symbol.clone()
+    }
+
+    // Return ID of the incoming (aggressive) order
+    pub fn get_aggressor_id(&self) -> String {
+        self.aggressor_id.clone()
+    }
+
+    // Return ID of the resting (passive) order
+    pub fn get_passive_id(&self) -> String {
+        self.passive_id.clone()
+    }
+
+    // Return price of trade
+    pub fn get_price(&self) -> i64 {
+        self.price
+    }
+
+    // Return quantity of trade
+    pub fn get_qty(&self) -> i64 {
+        self.qty
+    }
+
+    // Return side of the incoming order ('1' = BUY, '2' = SELL)
+    pub fn get_aggressor_side(&self) -> char {
+        self.aggressor_side
+    }
+
+    // Return transaction time of trade
+    pub fn get_transact_time(&self) -> String {
+        self.transact_time.clone()
+    }
+
+    /**
+        Set new transaction time for trade
+        @params
+            m_time: new transaction time
+    */
+    pub fn set_transact_time(&mut self, m_time: &str) {
+        self.transact_time = m_time.to_string();
+    }
+}